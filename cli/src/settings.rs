@@ -1,7 +1,8 @@
 use ark_serialize::SerializationError;
 use clap::{Parser, ValueEnum};
 use solidity_verifiers::{
-    Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    Groth16VerifierKey, HyperNovaCycleFoldVerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey,
+    ProtocolVerifierKey,
 };
 use std::{env, fmt::Display, path::PathBuf};
 
@@ -16,6 +17,7 @@ pub(crate) enum Protocol {
     Groth16,
     Kzg,
     NovaCycleFold,
+    HyperNovaCycleFold,
 }
 
 impl Display for Protocol {
@@ -42,6 +44,10 @@ impl Protocol {
                 data,
             )?
             .render_as_template(pragma)),
+            Self::HyperNovaCycleFold => Ok(
+                HyperNovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(data)?
+                    .render_as_template(pragma),
+            ),
         }
     }
 }
@@ -85,6 +91,9 @@ Solidity Verifier currently supports the generation of Solidity smart contracts
 
     Nova + CycleFold Decider:
         Implements the decider circuit verification for the Nova proof system in conjunction with the CycleFold protocol optimization.
+
+    HyperNova + CycleFold Decider:
+        Implements the decider circuit verification for the HyperNova proof system in conjunction with the CycleFold protocol optimization.
 ";
 #[derive(Debug, Parser)]
 #[command(author = "0xPARC & PSE", version, about = ABOUT, long_about = Some(LONG_ABOUT))]
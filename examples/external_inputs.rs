@@ -18,7 +18,6 @@ use ark_r1cs_std::alloc::AllocVar;
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use core::marker::PhantomData;
-use std::time::Instant;
 
 use folding_schemes::commitment::{kzg::KZG, pedersen::Pedersen};
 use folding_schemes::folding::nova::{Nova, PreprocessorParam};
@@ -193,14 +192,19 @@ fn main() {
     println!("Initialize FoldingScheme");
     let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state.clone()).unwrap();
 
-    // compute a step of the IVC
-    for (i, external_inputs_at_step) in external_inputs.iter().enumerate() {
-        let start = Instant::now();
-        folding_scheme
-            .prove_step(rng, external_inputs_at_step.clone(), None)
-            .unwrap();
-        println!("Nova::prove_step {}: {:?}", i, start.elapsed());
-    }
+    // compute a step of the IVC for each of the external inputs, which are consumed one at a time
+    // from the iterator, so they could also be streamed from a file or a channel. Nova is used
+    // without extra instances (mu = nu = 1), so no other instances are given at each step
+    let steps = external_inputs.into_iter().map(|e| (e, None));
+    folding_scheme
+        .prove_steps(rng, steps, |progress| {
+            println!(
+                "Nova::prove_step {}: {:?} ({} constraints)",
+                progress.step, progress.metrics.total, progress.num_constraints
+            );
+            Ok(())
+        })
+        .unwrap();
     println!(
         "state at last step (after {} iterations): {:?}",
         num_steps,
@@ -6,7 +6,7 @@ use ark_r1cs_std::R1CSVar;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
 use ark_relations::r1cs::ConstraintSystem;
 use folding_schemes::zcash::byte_utils::{
-    bytes_to_field_element_and_field_element_variable, fpvar_to_bytes,
+    field_element_from_bytes, field_element_to_bytes, BytesGadget, Endianness,
 };
 pub fn main() {
     let cs = ConstraintSystem::new_ref();
@@ -17,11 +17,18 @@ pub fn main() {
     // Create FpVar from field element
     let leaf =
         FpVar::<Fr>::new_variable(cs.clone(), || Ok(field_element), AllocationMode::Input).unwrap();
-    let bytes_converted = fpvar_to_bytes(leaf.clone());
-    let (a, recon) = bytes_to_field_element_and_field_element_variable(bytes_converted);
-    let res = recon.is_eq(&leaf).unwrap().value().unwrap();
-    let res2 = a == field_element;
+    let bytes_converted = BytesGadget::fpvar_to_bytes(&leaf, Endianness::Little).unwrap();
+    let bytes = field_element_to_bytes(field_element, Endianness::Little);
 
+    // unpacking and then reading back the canonical encoding gives the original element
+    let recon = BytesGadget::bytes_to_fpvar(&bytes_converted, Endianness::Little).unwrap();
+    recon.enforce_equal(&leaf).unwrap();
+    let a = field_element_from_bytes::<Fr>(&bytes, Endianness::Little).unwrap();
+
+    let res = bytes_converted.value().unwrap() == bytes;
+    let res2 = recon.value().unwrap() == field_element && a == field_element;
     println!("{res}");
     println!("{res2}");
+    println!("{}", cs.is_satisfied().unwrap());
+    assert!(res && res2 && cs.is_satisfied().unwrap());
 }
@@ -8,7 +8,11 @@ use folding_schemes::{
     folding::nova::{Nova, PreprocessorParam},
     frontend::FCircuit,
     transcript::poseidon::poseidon_canonical_config,
-    zcash::{public_ledger::PublicLedgerCircuit, spend_key::SpendKey, t_circuit::TCircuit},
+    zcash::{
+        public_ledger::{transactions_commitment, PublicLedgerCircuit, LEDGER_ENTRIES_PER_STEP},
+        spend_key::SpendKey,
+        t_circuit::TCircuit,
+    },
     FoldingScheme,
 };
 use std::time::Instant;
@@ -23,15 +27,30 @@ pub fn prepare_data(length: i32) -> Vec<(SpendKey, Fr)> {
 pub fn test() {
     let n_steps: i32 = 1;
     let poseidon_config = poseidon_canonical_config::<Fr>();
-    let data = prepare_data(2);
+    let data = prepare_data(LEDGER_ENTRIES_PER_STEP as i32);
     let (spend_keys, transaction_hashes): (Vec<_>, Vec<_>) = data.into_iter().unzip();
     let mapped_spend_keys: Vec<Fr> = spend_keys.iter().map(|sk| sk.to_fr()).collect();
     println!("Spend Key Length: {:?}", mapped_spend_keys.len());
     let spend_key_secret_hash =
         CRH::<Fr>::evaluate(&poseidon_config, mapped_spend_keys.clone()).unwrap();
-    let transaction_hashes_secret_hash =
-        CRH::<Fr>::evaluate(&poseidon_config, transaction_hashes.clone()).unwrap();
-    let z_0 = vec![transaction_hashes_secret_hash];
+    // the transaction included at each step is not time-locked (lock_until=0) and expires at
+    // height n_steps
+    let (lock_until, expiry_height) = (Fr::from(0_u32), Fr::from(n_steps as u32));
+    let transactions_cm = transactions_commitment(
+        &poseidon_config,
+        &transaction_hashes,
+        lock_until,
+        expiry_height,
+    )
+    .unwrap();
+    // the ledger starts at block height 0
+    let z_0 = vec![spend_key_secret_hash, transactions_cm, Fr::from(0_u32)];
+    let external_inputs = [
+        mapped_spend_keys,
+        transaction_hashes,
+        vec![lock_until, expiry_height],
+    ]
+    .concat();
     let f_circuit = PublicLedgerCircuit::<Fr>::new(poseidon_config.clone()).unwrap();
     let mut rng = rand::rngs::OsRng;
     let nova_preprocess_params = PreprocessorParam::new(poseidon_config, f_circuit.clone());
@@ -42,10 +61,7 @@ pub fn test() {
     let mut nova = NOVA::init(&nova_params, f_circuit, z_0.clone()).unwrap();
     for i in 0..n_steps {
         let start = Instant::now();
-        // nova.prove_step(rng, mapped_spend_keys.clone(), None)
-        //     .unwrap();
-        nova.prove_step(rng, transaction_hashes.clone(), None)
-            .unwrap();
+        nova.prove_step(rng, external_inputs.clone(), None).unwrap();
         println!("Nova::prove_step {}: {:?}", i, start.elapsed());
     }
     let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
@@ -1,14 +1,15 @@
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::log2;
 
 use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, SparseMatrix};
 use crate::Error;
 
-use super::{r1cs::R1CS, Arith};
+use super::{r1cs::R1CS, Arith, UnsatisfiedConstraint};
 
 /// CCS represents the Customizable Constraint Systems structure defined in
 /// the [CCS paper](https://eprint.iacr.org/2023/552)
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CCS<F: PrimeField> {
     /// m: number of rows in M_i (such that M_i \in F^{m, n})
     pub m: usize,
@@ -37,8 +38,12 @@ pub struct CCS<F: PrimeField> {
 
 impl<F: PrimeField> Arith<F> for CCS<F> {
     /// check that a CCS structure is satisfied by a z vector. Only for testing.
+    /// If it is not, returns the first unsatisfied row together with the value of each of the
+    /// terms c_i * ∘_{j \in S_i} M_j z at that row.
     fn check_relation(&self, z: &[F]) -> Result<(), Error> {
         let mut result = vec![F::zero(); self.m];
+        // c_i * ∘_{j \in S_i} M_j z for each i in [q]
+        let mut terms = Vec::with_capacity(self.q);
 
         for i in 0..self.q {
             // extract the needed M_j matrices out of S_i
@@ -55,13 +60,21 @@ impl<F: PrimeField> Arith<F> for CCS<F> {
 
             // add it to the final vector
             result = vec_add(&result, &c_M_j_z)?;
+            terms.push(c_M_j_z);
         }
 
         // make sure the final vector is all zeroes
-        for e in result {
-            if !e.is_zero() {
-                return Err(Error::NotSatisfied);
-            }
+        if let Some(row) = result.iter().position(|e| !e.is_zero()) {
+            let names: Vec<String> = (0..self.q).map(|i| format!("term_{}", i)).collect();
+            let values: Vec<(&str, F)> = names
+                .iter()
+                .zip(&terms)
+                .map(|(name, term)| (name.as_str(), term[row]))
+                .chain([("sum", result[row])])
+                .collect();
+            return Err(Error::UnsatisfiedConstraint(UnsatisfiedConstraint::new(
+                row, &values,
+            )));
         }
 
         Ok(())
@@ -132,4 +145,25 @@ pub mod tests {
 
         ccs.check_relation(&z).unwrap();
     }
+
+    #[test]
+    fn test_ccs_relation_diagnostics() {
+        let ccs = get_test_ccs::<Fr>();
+        // break the x^2 value of the witness, which is first used at the row 0, x * x = x^2
+        let mut z = get_test_z::<Fr>(3);
+        z[3] = Fr::from(8_u32);
+
+        let expected = UnsatisfiedConstraint::new(
+            0,
+            &[
+                ("term_0", Fr::from(9_u32)),
+                ("term_1", -Fr::from(8_u32)),
+                ("sum", Fr::from(1_u32)),
+            ],
+        );
+        match ccs.check_relation(&z) {
+            Err(Error::UnsatisfiedConstraint(unsatisfied)) => assert_eq!(unsatisfied, expected),
+            res => panic!("expected an unsatisfied constraint, got {:?}", res),
+        }
+    }
 }
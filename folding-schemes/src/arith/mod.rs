@@ -1,4 +1,5 @@
 use ark_ff::PrimeField;
+use ark_std::fmt;
 
 use crate::Error;
 
@@ -13,3 +14,42 @@ pub trait Arith<F: PrimeField> {
     /// public inputs, etc, without the matrices/polynomials values.
     fn params_to_le_bytes(&self) -> Vec<u8>;
 }
+
+/// Details of the first unsatisfied constraint found when checking a relation, returned inside of
+/// [`Error::UnsatisfiedConstraint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint {
+    /// index of the first row (constraint) that is not satisfied
+    pub row: usize,
+    /// name of the constraint, given by the arkworks namespaces, when available
+    pub name: Option<String>,
+    /// values of the terms of the constraint at the given row, as (term, value), eg. the values
+    /// of Az, Bz and Cz for R1CS
+    pub values: Vec<(String, String)>,
+}
+
+impl UnsatisfiedConstraint {
+    pub fn new<F: PrimeField>(row: usize, values: &[(&str, F)]) -> Self {
+        Self {
+            row,
+            name: None,
+            values: values
+                .iter()
+                .map(|(term, value)| (term.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for UnsatisfiedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}", self.row)?;
+        if let Some(name) = &self.name {
+            write!(f, " ({})", name)?;
+        }
+        for (i, (term, value)) in self.values.iter().enumerate() {
+            write!(f, "{} {} = {}", if i == 0 { ":" } else { "," }, term, value)?;
+        }
+        Ok(())
+    }
+}
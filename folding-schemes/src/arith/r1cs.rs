@@ -1,10 +1,10 @@
 use ark_ff::PrimeField;
-use ark_relations::r1cs::ConstraintSystem;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::Rng;
 
-use super::Arith;
-use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, SparseMatrix};
+use super::{Arith, UnsatisfiedConstraint};
+use crate::utils::vec::{mat_vec_mul, SparseMatrix};
 use crate::Error;
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -17,13 +17,18 @@ pub struct R1CS<F: PrimeField> {
 
 impl<F: PrimeField> Arith<F> for R1CS<F> {
     /// check that a R1CS structure is satisfied by a z vector. Only for testing.
+    /// If it is not, returns the first unsatisfied row together with its values of Az, Bz & Cz.
     fn check_relation(&self, z: &[F]) -> Result<(), Error> {
         let Az = mat_vec_mul(&self.A, z)?;
         let Bz = mat_vec_mul(&self.B, z)?;
         let Cz = mat_vec_mul(&self.C, z)?;
-        let AzBz = hadamard(&Az, &Bz)?;
-        if AzBz != Cz {
-            return Err(Error::NotSatisfied);
+        for (row, ((a, b), c)) in Az.into_iter().zip(Bz).zip(Cz).enumerate() {
+            if a * b != c {
+                return Err(Error::UnsatisfiedConstraint(UnsatisfiedConstraint::new(
+                    row,
+                    &[("Az", a), ("Bz", b), ("Cz", c)],
+                )));
+            }
         }
         Ok(())
     }
@@ -79,15 +84,28 @@ pub struct RelaxedR1CS<F: PrimeField> {
 
 impl<F: PrimeField> RelaxedR1CS<F> {
     /// check that a RelaxedR1CS structure is satisfied by a z vector. Only for testing.
+    /// If it is not, returns the first unsatisfied row together with its values of Az, Bz, Cz, u
+    /// and E.
     pub fn check_relation(&self, z: &[F]) -> Result<(), Error> {
         let Az = mat_vec_mul(&self.A, z)?;
         let Bz = mat_vec_mul(&self.B, z)?;
         let Cz = mat_vec_mul(&self.C, z)?;
-        let uCz = vec_scalar_mul(&Cz, &self.u);
-        let uCzE = vec_add(&uCz, &self.E)?;
-        let AzBz = hadamard(&Az, &Bz)?;
-        if AzBz != uCzE {
-            return Err(Error::NotSatisfied);
+        if self.E.len() != Cz.len() {
+            return Err(Error::NotSameLength(
+                "E.len()".to_string(),
+                self.E.len(),
+                "n_rows".to_string(),
+                Cz.len(),
+            ));
+        }
+        for (row, ((a, b), c)) in Az.into_iter().zip(Bz).zip(Cz).enumerate() {
+            let e = self.E[row];
+            if a * b != self.u * c + e {
+                return Err(Error::UnsatisfiedConstraint(UnsatisfiedConstraint::new(
+                    row,
+                    &[("Az", a), ("Bz", b), ("Cz", c), ("u", self.u), ("E", e)],
+                )));
+            }
         }
 
         Ok(())
@@ -126,6 +144,29 @@ pub fn extract_r1cs<F: PrimeField>(cs: &ConstraintSystem<F>) -> R1CS<F> {
     }
 }
 
+/// Checks that the arkworks ConstraintSystem is satisfied by its assignments, returning the same
+/// diagnostics as `R1CS::check_relation` when it is not. The diagnostics include the name of the
+/// unsatisfied constraint when the constraints were generated with a `tracing` subscriber that
+/// uses arkworks' `ConstraintLayer`, which records the namespaces of each constraint.
+pub fn check_cs_relation<F: PrimeField>(cs: ConstraintSystemRef<F>) -> Result<(), Error> {
+    cs.finalize();
+    let cs_inner = cs.borrow().ok_or(Error::NoInnerConstraintSystem)?;
+    let r1cs = extract_r1cs::<F>(&cs_inner);
+    let (w, x) = extract_w_x::<F>(&cs_inner);
+    drop(cs_inner);
+
+    match r1cs.check_relation(&[vec![F::one()], x, w].concat()) {
+        Err(Error::UnsatisfiedConstraint(mut unsatisfied)) => {
+            // without the ConstraintLayer, arkworks uses the index of the constraint as its name
+            unsatisfied.name = cs
+                .which_is_unsatisfied()?
+                .filter(|name| *name != unsatisfied.row.to_string());
+            Err(Error::UnsatisfiedConstraint(unsatisfied))
+        }
+        res => res,
+    }
+}
+
 /// extracts the witness and the public inputs from arkworks ConstraintSystem.
 pub fn extract_w_x<F: PrimeField>(cs: &ConstraintSystem<F>) -> (Vec<F>, Vec<F>) {
     (
@@ -141,6 +182,8 @@ pub mod tests {
     use crate::utils::vec::tests::{to_F_matrix, to_F_vec};
 
     use ark_pallas::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_std::{One, Zero};
 
     pub fn get_test_r1cs<F: PrimeField>() -> R1CS<F> {
         // R1CS for: x^3 + x + 5 = y (example from article
@@ -202,4 +245,54 @@ pub mod tests {
         r1cs.check_relation(&z).unwrap();
         r1cs.relax().check_relation(&z).unwrap();
     }
+
+    #[test]
+    fn test_check_relation_diagnostics() {
+        let r1cs = get_test_r1cs::<Fr>();
+        // break the x^2 value of the witness, which is first used at the row 0, x * x = x^2
+        let mut z = get_test_z::<Fr>(5);
+        z[3] = Fr::from(24_u32);
+
+        let expected = UnsatisfiedConstraint::new(
+            0,
+            &[
+                ("Az", Fr::from(5_u32)),
+                ("Bz", Fr::from(5_u32)),
+                ("Cz", Fr::from(24_u32)),
+            ],
+        );
+        match r1cs.check_relation(&z) {
+            Err(Error::UnsatisfiedConstraint(unsatisfied)) => assert_eq!(unsatisfied, expected),
+            res => panic!("expected an unsatisfied constraint, got {:?}", res),
+        }
+
+        let expected = UnsatisfiedConstraint::new(
+            0,
+            &[
+                ("Az", Fr::from(5_u32)),
+                ("Bz", Fr::from(5_u32)),
+                ("Cz", Fr::from(24_u32)),
+                ("u", Fr::one()),
+                ("E", Fr::zero()),
+            ],
+        );
+        match r1cs.relax().check_relation(&z) {
+            Err(Error::UnsatisfiedConstraint(unsatisfied)) => assert_eq!(unsatisfied, expected),
+            res => panic!("expected an unsatisfied constraint, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_check_cs_relation() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(3_u32))).unwrap();
+        let y = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(10_u32))).unwrap();
+        // the constraint x * x = x^2 at row 0 holds, but x^2 == y at row 1 does not
+        (&x * &x).enforce_equal(&y).unwrap();
+
+        match check_cs_relation(cs) {
+            Err(Error::UnsatisfiedConstraint(unsatisfied)) => assert_eq!(unsatisfied.row, 1),
+            res => panic!("expected an unsatisfied constraint, got {:?}", res),
+        }
+    }
 }
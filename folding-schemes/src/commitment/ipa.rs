@@ -32,11 +32,11 @@ use crate::Error;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Proof<C: CurveGroup> {
-    a: C::ScalarField,
-    l: Vec<C::ScalarField>,
-    r: Vec<C::ScalarField>,
-    L: Vec<C>,
-    R: Vec<C>,
+    pub(crate) a: C::ScalarField,
+    pub(crate) l: Vec<C::ScalarField>,
+    pub(crate) r: Vec<C::ScalarField>,
+    pub(crate) L: Vec<C>,
+    pub(crate) R: Vec<C>,
 }
 
 /// IPA implements the Inner Product Argument protocol following the CommitmentScheme trait. The
@@ -328,7 +328,7 @@ impl<C: CurveGroup, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
 /// Uses Halo2 approach computing $g(X) = \prod\limits_{i=0}^{k-1} (1 + u_{k - 1 - i} X^{2^i})$,
 /// taking 2^{k+1}-2.
 /// src: https://github.com/zcash/halo2/blob/81729eca91ba4755e247f49c3a72a4232864ec9e/halo2_proofs/src/poly/commitment/verifier.rs#L156
-fn build_s<F: PrimeField>(u: &[F], u_invs: &[F], k: usize) -> Result<Vec<F>, Error> {
+pub(crate) fn build_s<F: PrimeField>(u: &[F], u_invs: &[F], k: usize) -> Result<Vec<F>, Error> {
     let d: usize = 2_u64.pow(k as u32) as usize;
     let mut s: Vec<F> = vec![F::one(); d];
     for (len, (u_j, u_j_inv)) in u
@@ -388,7 +388,7 @@ fn build_s_gadget<F: PrimeField, CF: PrimeField>(
     Ok(s)
 }
 
-fn inner_prod<F: PrimeField>(a: &[F], b: &[F]) -> Result<F, Error> {
+pub(crate) fn inner_prod<F: PrimeField>(a: &[F], b: &[F]) -> Result<F, Error> {
     if a.len() != b.len() {
         return Err(Error::NotSameLength(
             "a".to_string(),
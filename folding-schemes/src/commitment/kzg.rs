@@ -20,6 +20,7 @@ use ark_std::{borrow::Cow, fmt::Debug};
 use ark_std::{One, Zero};
 use core::marker::PhantomData;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use tracing::info_span;
 
 use super::CommitmentScheme;
 use crate::transcript::Transcript;
@@ -70,7 +71,7 @@ impl<'a, C: CurveGroup> Valid for ProverKey<'a, C> {
     }
 }
 
-#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<C: CurveGroup> {
     pub eval: C::ScalarField,
     pub proof: C,
@@ -143,10 +144,12 @@ where
 
         let (num_leading_zeros, plain_coeffs) =
             skip_first_zero_coeffs_and_convert_to_bigints(&polynomial);
-        let commitment = <E::G1 as VariableBaseMSM>::msm_bigint(
-            &params.powers_of_g[num_leading_zeros..],
-            &plain_coeffs,
-        );
+        let commitment = info_span!("msm", len = plain_coeffs.len()).in_scope(|| {
+            <E::G1 as VariableBaseMSM>::msm_bigint(
+                &params.powers_of_g[num_leading_zeros..],
+                &plain_coeffs,
+            )
+        });
         Ok(commitment)
     }
 
@@ -204,10 +207,12 @@ where
         check_degree_is_too_large(witness_poly.degree(), params.powers_of_g.len())?;
         let (num_leading_zeros, witness_coeffs) =
             skip_first_zero_coeffs_and_convert_to_bigints(&witness_poly);
-        let proof = <E::G1 as VariableBaseMSM>::msm_bigint(
-            &params.powers_of_g[num_leading_zeros..],
-            &witness_coeffs,
-        );
+        let proof = info_span!("msm", len = witness_coeffs.len()).in_scope(|| {
+            <E::G1 as VariableBaseMSM>::msm_bigint(
+                &params.powers_of_g[num_leading_zeros..],
+                &witness_coeffs,
+            )
+        });
 
         Ok(Proof { eval, proof })
     }
@@ -0,0 +1,437 @@
+/// MultilinearIPA implements a multilinear polynomial commitment on top of the Inner Product
+/// Argument from [Bulletproofs](https://eprint.iacr.org/2017/1066.pdf) and
+/// [Halo](https://eprint.iacr.org/2019/1021.pdf), where the committed vector is interpreted as the
+/// evaluations over the boolean hypercube of a multilinear polynomial, and the opening proves
+/// `v = <a, eq(r, ·)>` for a given point `r`.
+///
+/// The commitments are plain Pedersen commitments (computed with the same `PedersenParams`), so
+/// commitments generated by the `Pedersen` and `IPA` schemes can be opened with this scheme.
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::Field;
+use ark_std::{cfg_iter, rand::RngCore, One, UniformRand, Zero};
+use core::marker::PhantomData;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use super::{
+    ipa::{build_s, inner_prod, Proof as IPAProof},
+    pedersen::Params as PedersenParams,
+    CommitmentScheme,
+};
+use crate::transcript::Transcript;
+use crate::utils::{
+    vec::{vec_add, vec_scalar_mul},
+    virtual_polynomial::build_eq_x_r_vec,
+};
+use crate::Error;
+
+/// (ipa proof, v=p(r), blinding factor)
+pub type Proof<C> = (
+    IPAProof<C>,
+    <C as Group>::ScalarField,
+    <C as Group>::ScalarField,
+);
+
+/// MultilinearIPA implements the CommitmentScheme trait for multilinear evaluations. The `H`
+/// parameter indicates if to use the commitment in hiding mode or not.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultilinearIPA<C: CurveGroup, const H: bool = false> {
+    _c: PhantomData<C>,
+}
+
+impl<C: CurveGroup, const H: bool> CommitmentScheme<C, H> for MultilinearIPA<C, H>
+where
+    C::ScalarField: Absorb,
+{
+    type ProverParams = PedersenParams<C>;
+    type VerifierParams = PedersenParams<C>;
+    type Proof = Proof<C>;
+    type ProverChallenge = ();
+    /// (evaluation point r, U, round challenges u)
+    type Challenge = (Vec<C::ScalarField>, C, Vec<C::ScalarField>);
+
+    fn is_hiding() -> bool {
+        if H {
+            return true;
+        }
+        false
+    }
+
+    fn setup(
+        mut rng: impl RngCore,
+        len: usize,
+    ) -> Result<(Self::ProverParams, Self::VerifierParams), Error> {
+        let generators: Vec<C::Affine> = std::iter::repeat_with(|| C::Affine::rand(&mut rng))
+            .take(len.next_power_of_two())
+            .collect();
+        let p = PedersenParams::<C> {
+            h: C::rand(&mut rng),
+            generators,
+        };
+        Ok((p.clone(), p))
+    }
+
+    fn commit(
+        params: &PedersenParams<C>,
+        a: &[C::ScalarField],
+        r: &C::ScalarField, // blinding factor
+    ) -> Result<C, Error> {
+        if params.generators.len() < a.len() {
+            return Err(Error::PedersenParamsLen(params.generators.len(), a.len()));
+        }
+        if !H && (!r.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+
+        // h⋅r + <g, a>
+        // use msm_unchecked because we already ensured at the if that lengths match
+        if !H {
+            return Ok(C::msm_unchecked(&params.generators[..a.len()], a));
+        }
+        Ok(params.h.mul(r) + C::msm_unchecked(&params.generators[..a.len()], a))
+    }
+
+    /// Opens the commitment at a random point obtained from the transcript. To open at a
+    /// specific point use `MultilinearIPA::prove_eval`.
+    fn prove(
+        params: &Self::ProverParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C,                // commitment
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        if !a.len().is_power_of_two() {
+            return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
+        }
+        transcript.absorb_nonnative(P);
+        let point = transcript.get_challenges(a.len().trailing_zeros() as usize);
+        Self::prove_eval(params, transcript, P, a, blind, &point, rng)
+    }
+
+    fn prove_with_challenge(
+        _params: &Self::ProverParams,
+        _challenge: Self::ProverChallenge,
+        _a: &[C::ScalarField], // vector
+        _blind: &C::ScalarField,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        // not supported because the prover logic computes challenges as it advances on the logic
+        Err(Error::NotSupported(
+            "MultilinearIPA::prove_with_challenge".to_string(),
+        ))
+    }
+
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        transcript.absorb_nonnative(P);
+        let point = transcript.get_challenges(proof.0.L.len());
+        Self::verify_eval(params, transcript, P, &point, proof)
+    }
+
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let (p, v, r) = (proof.0.clone(), proof.1, proof.2);
+        let (point, U, u) = challenge;
+
+        let k = p.L.len();
+        if p.R.len() != k || point.len() != k || u.len() != k {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        if !H && (!r.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        if !H && (!p.l.is_empty() || !p.r.is_empty()) {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        if H && (p.l.len() != k || p.r.len() != k) {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        let P = *P + U.mul(v); // where v=p(r)
+
+        let mut q_0 = P;
+        let mut r = r;
+
+        // compute u[i]^-1 once
+        let mut u_invs = vec![C::ScalarField::zero(); u.len()];
+        for (j, u_j) in u.iter().enumerate() {
+            u_invs[j] = u_j
+                .inverse()
+                .ok_or(Error::Other("error on computing inverse".to_string()))?;
+        }
+
+        // compute b & G from s
+        let s = build_s(&u, &u_invs, k)?;
+        // b = <s, eq(r, ·)> = \prod_j (1-r_j) u_j^-1 + r_j u_j
+        let b: C::ScalarField = point
+            .iter()
+            .zip(u.iter().zip(u_invs.iter()))
+            .map(|(r_j, (u_j, u_j_inv))| (C::ScalarField::one() - r_j) * u_j_inv + *r_j * u_j)
+            .product();
+        let d: usize = 1 << k;
+        if params.generators.len() < d {
+            return Err(Error::PedersenParamsLen(params.generators.len(), d));
+        }
+        let G = C::msm_unchecked(&params.generators, &s);
+
+        for (j, u_j) in u.iter().enumerate() {
+            let uj2 = u_j.square();
+            let uj_inv2 = u_invs[j].square();
+
+            q_0 = q_0 + p.L[j].mul(uj2) + p.R[j].mul(uj_inv2);
+            if H {
+                r = r + p.l[j] * uj2 + p.r[j] * uj_inv2;
+            }
+        }
+
+        let q_1 = if H {
+            G.mul(p.a) + params.h.mul(r) + U.mul(p.a * b)
+        } else {
+            G.mul(p.a) + U.mul(p.a * b)
+        };
+
+        if q_0 != q_1 {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        Ok(())
+    }
+}
+
+impl<C: CurveGroup, const H: bool> MultilinearIPA<C, H>
+where
+    C::ScalarField: Absorb,
+{
+    /// Proves the evaluation of the multilinear extension of `a` at the given `point`, where the
+    /// evaluations are ordered with `point[0]` being the least significant bit of the index.
+    pub fn prove_eval(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C,                // commitment
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        point: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Proof<C>, Error> {
+        if !a.len().is_power_of_two() {
+            return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
+        }
+        if a.len() != 1 << point.len() {
+            return Err(Error::NotExpectedLength(a.len(), 1 << point.len()));
+        }
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        if params.generators.len() < a.len() {
+            return Err(Error::PedersenParamsLen(params.generators.len(), a.len()));
+        }
+        let k = point.len();
+
+        // blinding factors
+        let l: Vec<C::ScalarField>;
+        let r: Vec<C::ScalarField>;
+        if H {
+            let rng = rng.ok_or(Error::MissingRandomness)?;
+            l = std::iter::repeat_with(|| C::ScalarField::rand(rng))
+                .take(k)
+                .collect();
+            r = std::iter::repeat_with(|| C::ScalarField::rand(rng))
+                .take(k)
+                .collect();
+        } else {
+            l = vec![];
+            r = vec![];
+        }
+
+        let mut a = a.to_owned();
+        let mut b = build_eq_x_r_vec(point)?;
+        let v = inner_prod(&a, &b)?;
+
+        transcript.absorb_nonnative(P);
+        transcript.absorb(&point.to_vec());
+        transcript.absorb(&v);
+        let s = transcript.get_challenge();
+        let U = C::generator().mul(s);
+
+        let mut G = params.generators[..a.len()].to_vec();
+
+        let mut L: Vec<C> = vec![C::zero(); k];
+        let mut R: Vec<C> = vec![C::zero(); k];
+
+        // u challenges
+        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
+        for j in (0..k).rev() {
+            let m = a.len() / 2;
+
+            if H {
+                L[j] = C::msm_unchecked(&G[m..], &a[..m])
+                    + params.h.mul(l[j])
+                    + U.mul(inner_prod(&a[..m], &b[m..])?);
+                R[j] = C::msm_unchecked(&G[..m], &a[m..])
+                    + params.h.mul(r[j])
+                    + U.mul(inner_prod(&a[m..], &b[..m])?);
+            } else {
+                L[j] = C::msm_unchecked(&G[m..], &a[..m]) + U.mul(inner_prod(&a[..m], &b[m..])?);
+                R[j] = C::msm_unchecked(&G[..m], &a[m..]) + U.mul(inner_prod(&a[m..], &b[..m])?);
+            }
+            // get challenge for the j-th round
+            transcript.absorb_nonnative(&L[j]);
+            transcript.absorb_nonnative(&R[j]);
+            u[j] = transcript.get_challenge();
+
+            let uj = u[j];
+            let uj_inv = u[j]
+                .inverse()
+                .ok_or(Error::Other("error on computing inverse".to_string()))?;
+
+            // a_hi * uj^-1 + a_lo * uj
+            a = vec_add(
+                &vec_scalar_mul(&a[..m], &uj),
+                &vec_scalar_mul(&a[m..], &uj_inv),
+            )?;
+            // b_lo * uj^-1 + b_hi * uj
+            b = vec_add(
+                &vec_scalar_mul(&b[..m], &uj_inv),
+                &vec_scalar_mul(&b[m..], &uj),
+            )?;
+            // G_lo * uj^-1 + G_hi * uj
+            G = cfg_iter!(G[..m])
+                .map(|e| e.into_group().mul(uj_inv))
+                .zip(cfg_iter!(G[m..]).map(|e| e.into_group().mul(uj)))
+                .map(|(a, b)| (a + b).into_affine())
+                .collect::<Vec<C::Affine>>();
+        }
+
+        if a.len() != 1 {
+            return Err(Error::NotExpectedLength(a.len(), 1));
+        }
+
+        Ok((
+            IPAProof {
+                a: a[0],
+                l,
+                r,
+                L,
+                R,
+            },
+            v,      // evaluation at the point, v=p(r)
+            *blind, // blind factor
+        ))
+    }
+
+    /// Verifies the evaluation proof generated by `MultilinearIPA::prove_eval` at the given
+    /// `point`.
+    pub fn verify_eval(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C, // commitment
+        point: &[C::ScalarField],
+        proof: &Proof<C>,
+    ) -> Result<(), Error> {
+        let k = proof.0.L.len();
+        if point.len() != k || proof.0.R.len() != k {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        transcript.absorb_nonnative(P);
+        transcript.absorb(&point.to_vec());
+        transcript.absorb(&proof.1);
+        let s = transcript.get_challenge();
+        let U = C::generator().mul(s);
+        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
+        for i in (0..k).rev() {
+            transcript.absorb_nonnative(&proof.0.L[i]);
+            transcript.absorb_nonnative(&proof.0.R[i]);
+            u[i] = transcript.get_challenge();
+        }
+        let challenge = (point.to_vec(), U, u);
+
+        Self::verify_with_challenge(params, challenge, P, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+
+    use super::*;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_mle_ipa() {
+        test_mle_ipa_opt::<false>();
+        test_mle_ipa_opt::<true>();
+    }
+    fn test_mle_ipa_opt<const hiding: bool>() {
+        let mut rng = ark_std::test_rng();
+
+        const k: usize = 4;
+        const d: usize = 1 << k;
+
+        let (params, _) = MultilinearIPA::<Projective, hiding>::setup(&mut rng, d).unwrap();
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let a: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(d)
+            .collect();
+        let r_blind: Fr = if hiding {
+            Fr::rand(&mut rng)
+        } else {
+            Fr::zero()
+        };
+        let cm = MultilinearIPA::<Projective, hiding>::commit(&params, &a, &r_blind).unwrap();
+
+        let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(k)
+            .collect();
+        let proof = MultilinearIPA::<Projective, hiding>::prove_eval(
+            &params,
+            &mut transcript_p,
+            &cm,
+            &a,
+            &r_blind,
+            &point,
+            Some(&mut rng),
+        )
+        .unwrap();
+
+        // the opened value matches the evaluation of the multilinear extension of a
+        let mle = DenseMultilinearExtension::from_evaluations_slice(k, &a);
+        assert_eq!(proof.1, mle.evaluate(&point).unwrap());
+
+        MultilinearIPA::<Projective, hiding>::verify_eval(
+            &params,
+            &mut transcript_v,
+            &cm,
+            &point,
+            &proof,
+        )
+        .unwrap();
+
+        // a wrong evaluation is rejected
+        let mut bad_proof = proof.clone();
+        bad_proof.1 += Fr::one();
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(MultilinearIPA::<Projective, hiding>::verify_eval(
+            &params,
+            &mut transcript_v,
+            &cm,
+            &point,
+            &bad_proof,
+        )
+        .is_err());
+    }
+}
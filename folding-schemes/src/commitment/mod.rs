@@ -8,7 +8,9 @@ use crate::Error;
 
 pub mod ipa;
 pub mod kzg;
+pub mod mle_ipa;
 pub mod pedersen;
+pub mod ptau;
 
 /// CommitmentScheme defines the vector commitment scheme trait. Where `H` indicates if to use the
 /// commitment in hiding mode or not.
@@ -6,6 +6,7 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::Zero;
 use ark_std::{rand::RngCore, UniformRand};
 use core::marker::PhantomData;
+use tracing::info_span;
 
 use super::CommitmentScheme;
 use crate::transcript::Transcript;
@@ -73,10 +74,12 @@ impl<C: CurveGroup, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
 
         // h⋅r + <g, v>
         // use msm_unchecked because we already ensured at the if that lengths match
+        let msm = info_span!("msm", len = v.len())
+            .in_scope(|| C::msm_unchecked(&params.generators[..v.len()], v));
         if !H {
-            return Ok(C::msm_unchecked(&params.generators[..v.len()], v));
+            return Ok(msm);
         }
-        Ok(params.h.mul(r) + C::msm_unchecked(&params.generators[..v.len()], v))
+        Ok(params.h.mul(r) + msm)
     }
 
     fn prove(
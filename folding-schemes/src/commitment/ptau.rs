@@ -0,0 +1,409 @@
+/// Loader of the KZG setup from a Powers-of-Tau transcript file (`.ptau`), in the binary format
+/// used by snarkjs and the Hermez / Perpetual Powers of Tau ceremonies, so that the KZG params do
+/// not depend on a locally sampled tau.
+///
+/// The file consists of the magic string `ptau`, the format version and the number of sections,
+/// followed by the sections, each one prefixed by its type (u32) and its size in bytes (u64):
+/// - section 1 (header): `n8` (u32), the base field modulus (`n8` bytes), `power` (u32) and
+///   `ceremonyPower` (u32)
+/// - section 2: `2^(power+1) - 1` G1 points `[tau^i]_1`
+/// - section 3: `2^power` G2 points `[tau^i]_2`
+///
+/// All the values are little-endian, and the field elements are in Montgomery form.
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{BigInt, BigInteger, PrimeField, Zero};
+use ark_poly_commit::kzg10::VerifierKey;
+use ark_std::{borrow::Cow, rand::RngCore, UniformRand};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::kzg::{ProverKey, KZG};
+use crate::Error;
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const SECTION_HEADER: u32 = 1;
+const SECTION_TAU_G1: u32 = 2;
+const SECTION_TAU_G2: u32 = 3;
+// size in bytes of the BN254 base field elements
+const N8: usize = 32;
+/// maximum `power` supported, which is the one of the largest Perpetual Powers of Tau files
+pub const MAX_PTAU_POWER: u32 = 28;
+
+impl<'a, const H: bool> KZG<'a, Bn254, H> {
+    /// setup_from_ptau returns the tuple (ProverKey, VerifierKey) for vectors of size up to `len`,
+    /// reading the powers of tau from the given ptau file. The points are checked to be in the
+    /// correct subgroup, and to be consistent powers of the same non-zero tau, which is done
+    /// through a random linear combination using the given `rng`.
+    pub fn setup_from_ptau<R: Read + Seek>(
+        mut rng: impl RngCore,
+        mut reader: R,
+        len: usize,
+    ) -> Result<(ProverKey<'a, G1Projective>, VerifierKey<Bn254>), Error> {
+        let len = len.next_power_of_two();
+        let sections = read_sections(&mut reader)?;
+
+        // header
+        seek_section(&mut reader, &sections, SECTION_HEADER)?;
+        let n8 = read_u32(&mut reader)? as usize;
+        if n8 != N8 {
+            return Err(Error::InvalidPtau(format!(
+                "field elements of {} bytes, expected {}",
+                n8, N8
+            )));
+        }
+        let mut q = [0u8; N8];
+        reader.read_exact(&mut q)?;
+        if q.to_vec() != Fq::MODULUS.to_bytes_le() {
+            return Err(Error::InvalidPtau("the curve is not BN254".to_string()));
+        }
+        let power = read_u32(&mut reader)?;
+        if power > MAX_PTAU_POWER {
+            return Err(Error::InvalidPtau(format!(
+                "power {} is above the maximum supported {}",
+                power, MAX_PTAU_POWER
+            )));
+        }
+        let n_tau_g1 = 1_usize
+            .checked_shl(power + 1)
+            .ok_or_else(|| Error::InvalidPtau(format!("power {} is too big", power)))?
+            - 1;
+        let n_tau_g2 = 1_usize << power;
+        if len + 1 > n_tau_g1 {
+            return Err(Error::InvalidPtau(format!(
+                "the file contains {} powers of tau, but {} are needed",
+                n_tau_g1,
+                len + 1
+            )));
+        }
+
+        // [tau^i]_1, for i in 0..=len
+        let size = seek_section(&mut reader, &sections, SECTION_TAU_G1)?;
+        check_section_size(SECTION_TAU_G1, size, n_tau_g1, 2 * N8)?;
+        let powers_of_g = (0..=len)
+            .map(|_| read_g1(&mut reader))
+            .collect::<Result<Vec<G1Affine>, Error>>()?;
+        // [1]_2, [tau]_2
+        let size = seek_section(&mut reader, &sections, SECTION_TAU_G2)?;
+        check_section_size(SECTION_TAU_G2, size, n_tau_g2, 4 * N8)?;
+        let h = read_g2(&mut reader)?;
+        let beta_h = read_g2(&mut reader)?;
+
+        if powers_of_g[0] != G1Affine::generator() || h != G2Affine::generator() {
+            return Err(Error::InvalidPtau(
+                "the first powers of tau are not the generators".to_string(),
+            ));
+        }
+        // tau = 0 would pass the check below, since all the powers but the first would be the
+        // identity
+        if beta_h.is_zero() || powers_of_g[1].is_zero() {
+            return Err(Error::InvalidPtau("tau is zero".to_string()));
+        }
+        // check that powers_of_g[i+1] = tau * powers_of_g[i], by checking that
+        // e(sum r_i * powers_of_g[i+1], [1]_2) == e(sum r_i * powers_of_g[i], [tau]_2) for random
+        // r_i
+        let r: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+        let lhs = G1Projective::msm_unchecked(&powers_of_g[1..], &r);
+        let rhs = G1Projective::msm_unchecked(&powers_of_g[..len], &r);
+        if Bn254::pairing(lhs, h) != Bn254::pairing(rhs, beta_h) {
+            return Err(Error::InvalidPtau(
+                "the points are not powers of the same tau".to_string(),
+            ));
+        }
+
+        let vk = VerifierKey {
+            g: powers_of_g[0],
+            // gamma_g is only used for hiding commitments, which are not supported
+            gamma_g: G1Affine::zero(),
+            h,
+            beta_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        };
+        let pk = ProverKey::<G1Projective> {
+            powers_of_g: Cow::Owned(powers_of_g),
+        };
+        Ok((pk, vk))
+    }
+}
+
+/// reads the file header and returns the position and size of each of the sections, checking
+/// that they are within the file
+fn read_sections<R: Read + Seek>(reader: &mut R) -> Result<HashMap<u32, (u64, u64)>, Error> {
+    let start = reader.stream_position()?;
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(start))?;
+
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(Error::InvalidPtau("not a ptau file".to_string()));
+    }
+    let _version = read_u32(reader)?;
+    let n_sections = read_u32(reader)?;
+
+    let mut sections = HashMap::new();
+    for _ in 0..n_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let position = reader.stream_position()?;
+        let out_of_bounds = || {
+            Error::InvalidPtau(format!(
+                "section {} is out of the file bounds",
+                section_type
+            ))
+        };
+        let offset = i64::try_from(size).map_err(|_| out_of_bounds())?;
+        if position.checked_add(size).ok_or_else(out_of_bounds)? > file_len {
+            return Err(out_of_bounds());
+        }
+        sections.insert(section_type, (position, size));
+        reader.seek(SeekFrom::Current(offset))?;
+    }
+    Ok(sections)
+}
+
+/// moves the reader to the start of the given section, returning its size
+fn seek_section<R: Read + Seek>(
+    reader: &mut R,
+    sections: &HashMap<u32, (u64, u64)>,
+    section_type: u32,
+) -> Result<u64, Error> {
+    let (position, size) = sections
+        .get(&section_type)
+        .ok_or_else(|| Error::InvalidPtau(format!("missing section {}", section_type)))?;
+    reader.seek(SeekFrom::Start(*position))?;
+    Ok(*size)
+}
+
+/// checks that the size of the given section matches the one of `n_points` points of
+/// `point_size` bytes each
+fn check_section_size(
+    section_type: u32,
+    size: u64,
+    n_points: usize,
+    point_size: usize,
+) -> Result<(), Error> {
+    let expected = n_points
+        .checked_mul(point_size)
+        .and_then(|expected| u64::try_from(expected).ok());
+    if expected != Some(size) {
+        return Err(Error::InvalidPtau(format!(
+            "section {} has {} bytes, expected {} points of {} bytes",
+            section_type, size, n_points, point_size
+        )));
+    }
+    Ok(())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// reads a base field element in Montgomery form
+fn read_fq<R: Read>(reader: &mut R) -> Result<Fq, Error> {
+    let mut limbs = [0u64; N8 / 8];
+    for limb in limbs.iter_mut() {
+        *limb = read_u64(reader)?;
+    }
+    let repr = BigInt::new(limbs);
+    if repr >= Fq::MODULUS {
+        return Err(Error::InvalidPtau(
+            "field element is not in the field".to_string(),
+        ));
+    }
+    Ok(Fq::new_unchecked(repr))
+}
+
+fn read_g1<R: Read>(reader: &mut R) -> Result<G1Affine, Error> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPtau("invalid G1 point".to_string()));
+    }
+    Ok(p)
+}
+
+fn read_g2<R: Read>(reader: &mut R) -> Result<G2Affine, Error> {
+    let x = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    let y = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPtau("invalid G2 point".to_string()));
+    }
+    Ok(p)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_ec::{CurveGroup, Group};
+    use ark_ff::Field;
+    use ark_std::test_rng;
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::commitment::CommitmentScheme;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    fn write_fq(bytes: &mut Vec<u8>, x: &Fq) {
+        for limb in x.0 .0.iter() {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    fn write_section(bytes: &mut Vec<u8>, section_type: u32, data: &[u8]) {
+        bytes.extend_from_slice(&section_type.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+    }
+
+    /// builds a ptau file for the given tau, as it would be output by a ceremony
+    fn build_ptau(tau: Fr, power: u32) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend_from_slice(&(N8 as u32).to_le_bytes());
+        header.extend_from_slice(&Fq::MODULUS.to_bytes_le());
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+
+        let mut tau_g1 = vec![];
+        for i in 0..(1_u64 << (power + 1)) - 1 {
+            let p = (G1Projective::generator() * tau.pow([i])).into_affine();
+            write_fq(&mut tau_g1, &p.x);
+            write_fq(&mut tau_g1, &p.y);
+        }
+        let mut tau_g2 = vec![];
+        for i in 0..1_u64 << power {
+            let p = (<Bn254 as Pairing>::G2::generator() * tau.pow([i])).into_affine();
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+                write_fq(&mut tau_g2, &c);
+            }
+        }
+
+        let mut bytes = PTAU_MAGIC.to_vec();
+        bytes.extend_from_slice(&1_u32.to_le_bytes()); // version
+        bytes.extend_from_slice(&3_u32.to_le_bytes()); // number of sections
+                                                       // sections are not required to be sorted
+        write_section(&mut bytes, SECTION_TAU_G2, &tau_g2);
+        write_section(&mut bytes, SECTION_HEADER, &header);
+        write_section(&mut bytes, SECTION_TAU_G1, &tau_g1);
+        bytes
+    }
+
+    #[test]
+    fn test_kzg_setup_from_ptau() {
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let ptau = build_ptau(Fr::rand(&mut rng), 4);
+
+        let n = 10;
+        let (pk, vk) = KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&ptau), n).unwrap();
+
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .collect();
+        let cm = KZG::<Bn254>::commit(&pk, &v, &Fr::zero()).unwrap();
+        let proof = KZG::<Bn254>::prove(&pk, transcript_p, &cm, &v, &Fr::zero(), None).unwrap();
+        KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof).unwrap();
+
+        // the file does not contain enough powers of tau
+        assert!(KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&ptau), 64).is_err());
+
+        // tamper one of the G1 points, replacing it by another point of the curve
+        let mut tampered = ptau.clone();
+        let tau_g2_len = 2 * 2 * N8 * 16;
+        let header_len = 4 + N8 + 4 + 4;
+        // magic, version, n_sections, and the section headers (type & size) of sections 3 & 1
+        let tau_g1_start = 4 + 4 + 4 + (12 + tau_g2_len) + (12 + header_len) + 12;
+        let p = (G1Projective::generator() * Fr::from(42_u32)).into_affine();
+        let mut p_bytes = vec![];
+        write_fq(&mut p_bytes, &p.x);
+        write_fq(&mut p_bytes, &p.y);
+        let offset = tau_g1_start + 3 * 2 * N8;
+        tampered[offset..offset + 2 * N8].copy_from_slice(&p_bytes);
+        assert!(matches!(
+            KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&tampered), n),
+            Err(Error::InvalidPtau(_))
+        ));
+
+        // tau = 0, where all the powers but the first are the identity
+        let ptau = build_ptau(Fr::zero(), 4);
+        assert!(matches!(
+            KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&ptau), n),
+            Err(Error::InvalidPtau(_))
+        ));
+    }
+
+    #[test]
+    fn test_ptau_invalid_sizes() {
+        let mut rng = test_rng();
+        let power = 2;
+        let ptau = build_ptau(Fr::rand(&mut rng), power);
+        let n = 2;
+        KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&ptau), n).unwrap();
+
+        let tau_g2_len = 2 * 2 * N8 * (1 << power);
+        // magic, version, n_sections, and the section header (type) of section 3
+        let tau_g2_size_offset = 4 + 4 + 4 + 4;
+        // the power is after the section header of section 1, and its n8 and modulus
+        let power_offset = tau_g2_size_offset + 8 + tau_g2_len + 12 + 4 + N8;
+
+        let with_u32 = |offset: usize, value: u32| {
+            let mut tampered = ptau.clone();
+            tampered[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            tampered
+        };
+        let with_u64 = |offset: usize, value: u64| {
+            let mut tampered = ptau.clone();
+            tampered[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+            tampered
+        };
+
+        // power above the supported maximum, which would overflow the number of points
+        for power in [MAX_PTAU_POWER + 1, 63, 64, u32::MAX] {
+            let tampered = with_u32(power_offset, power);
+            assert!(matches!(
+                KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&tampered), n),
+                Err(Error::InvalidPtau(_))
+            ));
+        }
+        // power not matching the size of the sections
+        let tampered = with_u32(power_offset, power + 1);
+        assert!(matches!(
+            KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&tampered), n),
+            Err(Error::InvalidPtau(_))
+        ));
+
+        // section sizes out of the file bounds, or not representable as a seek offset
+        for size in [ptau.len() as u64, i64::MAX as u64, u64::MAX] {
+            let tampered = with_u64(tau_g2_size_offset, size);
+            assert!(matches!(
+                KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&tampered), n),
+                Err(Error::InvalidPtau(_))
+            ));
+        }
+        // truncated file
+        assert!(matches!(
+            KZG::<Bn254>::setup_from_ptau(&mut rng, Cursor::new(&ptau[..ptau.len() - 1]), n),
+            Err(Error::InvalidPtau(_))
+        ));
+    }
+}
@@ -0,0 +1,407 @@
+/// This file implements periodic checkpointing of the IVC prover state, so that long folding runs
+/// can be resumed after a crash.
+///
+/// Every checkpoint consists of two files in the checkpoints directory:
+/// - `checkpoint-<step>.state`: the prover state serialized with `CanonicalSerialize`
+/// - `checkpoint-<step>.json`: the manifest, containing the `pp_hash`, the step index, the current
+///   state `z_i`, and the SHA3-256 digest of the state file
+///
+/// Both files are first written to a temporary file and then renamed, and the manifest is written
+/// after the state file, so a checkpoint is only considered once it has been fully written.
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, Compress};
+use ark_std::rand::RngCore;
+use num_bigint::BigUint;
+use sha3::{Digest, Sha3_256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use crate::frontend::FCircuit;
+use crate::{Error, FoldingScheme, StepMetrics};
+
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+const STATE_EXTENSION: &str = "state";
+const MANIFEST_EXTENSION: &str = "json";
+
+/// Checkpointable defines the methods that a FoldingScheme needs to provide in order to be
+/// checkpointed by the [`Checkpointer`].
+pub trait Checkpointable<C1: CurveGroup, C2: CurveGroup, FC>:
+    FoldingScheme<C1, C2, FC> + CanonicalSerialize
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+{
+    /// returns the hash of the public parameters that the prover state is bound to
+    fn pp_hash(&self) -> C1::ScalarField;
+
+    /// returns the number of steps folded so far
+    fn num_steps(&self) -> C1::ScalarField;
+
+    /// reconstructs the prover state serialized with `CanonicalSerialize` (in uncompressed mode),
+    /// checking that it is bound to the given params.
+    fn deserialize_state<R: Read>(
+        reader: R,
+        params: &(Self::ProverParam, Self::VerifierParam),
+        step_circuit: FC,
+    ) -> Result<Self, Error>;
+}
+
+/// Contents of the manifest file of a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest<F: PrimeField> {
+    pub pp_hash: F,
+    pub step: u64,
+    pub z_i: Vec<F>,
+    /// SHA3-256 digest of the state file
+    pub state_digest: Vec<u8>,
+}
+
+impl<F: PrimeField> Manifest<F> {
+    fn to_json(&self) -> String {
+        let z_i: Vec<String> = self
+            .z_i
+            .iter()
+            .map(|z| z.into_bigint().to_string())
+            .collect();
+        serde_json::json!({
+            "pp_hash": self.pp_hash.into_bigint().to_string(),
+            "step": self.step,
+            "z_i": z_i,
+            "state_sha3_256": to_hex(&self.state_digest),
+        })
+        .to_string()
+    }
+
+    fn from_json(s: &str) -> Result<Self, Error> {
+        let json: serde_json::Value =
+            serde_json::from_str(s).map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+        let field = |v: &serde_json::Value| -> Result<F, Error> {
+            v.as_str()
+                .and_then(|s| F::from_str(s).ok())
+                .ok_or_else(|| Error::InvalidCheckpoint("malformed field element".to_string()))
+        };
+        let missing = |key: &str| Error::InvalidCheckpoint(format!("missing '{}'", key));
+
+        let pp_hash = field(json.get("pp_hash").ok_or_else(|| missing("pp_hash"))?)?;
+        let step = json
+            .get("step")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| missing("step"))?;
+        let z_i = json
+            .get("z_i")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| missing("z_i"))?
+            .iter()
+            .map(field)
+            .collect::<Result<Vec<F>, Error>>()?;
+        let state_digest = json
+            .get("state_sha3_256")
+            .and_then(|v| v.as_str())
+            .and_then(from_hex)
+            .ok_or_else(|| missing("state_sha3_256"))?;
+        Ok(Self {
+            pp_hash,
+            step,
+            z_i,
+            state_digest,
+        })
+    }
+}
+
+/// Checkpointer wraps a FoldingScheme and writes its prover state to the given directory every
+/// `interval` steps. Use [`Checkpointer::resume`] to continue from the latest valid checkpoint.
+#[derive(Debug, Clone)]
+pub struct Checkpointer<C1, C2, FC, FS>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    FS: Checkpointable<C1, C2, FC>,
+{
+    _c1: PhantomData<C1>,
+    _c2: PhantomData<C2>,
+    _fc: PhantomData<FC>,
+    pub folding_scheme: FS,
+    dir: PathBuf,
+    interval: u64,
+}
+
+impl<C1, C2, FC, FS> Checkpointer<C1, C2, FC, FS>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    FS: Checkpointable<C1, C2, FC>,
+{
+    /// Wraps the given folding scheme, checkpointing it into `dir` every `interval` steps.
+    pub fn new(folding_scheme: FS, dir: impl AsRef<Path>, interval: usize) -> Result<Self, Error> {
+        if interval == 0 {
+            return Err(Error::CantBeZero("checkpoint interval".to_string()));
+        }
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            _c1: PhantomData,
+            _c2: PhantomData,
+            _fc: PhantomData,
+            folding_scheme,
+            dir: dir.as_ref().to_path_buf(),
+            interval: interval as u64,
+        })
+    }
+
+    /// Resumes from the latest valid checkpoint in `dir`. Checkpoints that can not be read, whose
+    /// state does not match its manifest, or that are not bound to the given params are skipped.
+    pub fn resume(
+        dir: impl AsRef<Path>,
+        interval: usize,
+        params: &(FS::ProverParam, FS::VerifierParam),
+        step_circuit: FC,
+    ) -> Result<Self, Error> {
+        let mut steps = list_checkpoints(dir.as_ref())?;
+        steps.sort_unstable_by(|a, b| b.cmp(a));
+        for step in steps {
+            if let Ok(folding_scheme) =
+                load_checkpoint::<C1, C2, FC, FS>(dir.as_ref(), step, params, step_circuit.clone())
+            {
+                return Self::new(folding_scheme, dir, interval);
+            }
+        }
+        Err(Error::MissingValue(format!(
+            "valid checkpoint in {}",
+            dir.as_ref().display()
+        )))
+    }
+
+    /// Folds a new step into the wrapped folding scheme, writing a checkpoint when the number of
+    /// folded steps is a multiple of the interval. Returns the [`StepMetrics`] of the folding
+    /// step, which do not include the time spent writing the checkpoint.
+    pub fn prove_step(
+        &mut self,
+        rng: impl RngCore,
+        external_inputs: Vec<C1::ScalarField>,
+        other_instances: Option<FS::MultiCommittedInstanceWithWitness>,
+    ) -> Result<StepMetrics, Error> {
+        let metrics = self
+            .folding_scheme
+            .prove_step(rng, external_inputs, other_instances)?;
+        if field_to_u64(self.folding_scheme.num_steps())? % self.interval == 0 {
+            self.checkpoint()?;
+        }
+        Ok(metrics)
+    }
+
+    /// Writes a checkpoint of the current prover state, returning the path of its manifest.
+    pub fn checkpoint(&self) -> Result<PathBuf, Error> {
+        let step = field_to_u64(self.folding_scheme.num_steps())?;
+
+        let mut state = vec![];
+        self.folding_scheme
+            .serialize_with_mode(&mut state, Compress::No)?;
+        let manifest = Manifest {
+            pp_hash: self.folding_scheme.pp_hash(),
+            step,
+            z_i: self.folding_scheme.state(),
+            state_digest: Sha3_256::digest(&state).to_vec(),
+        };
+
+        write_atomic(
+            &self.dir,
+            &checkpoint_path(&self.dir, step, STATE_EXTENSION),
+            &state,
+        )?;
+        let manifest_path = checkpoint_path(&self.dir, step, MANIFEST_EXTENSION);
+        write_atomic(&self.dir, &manifest_path, manifest.to_json().as_bytes())?;
+        Ok(manifest_path)
+    }
+
+    /// Returns the wrapped folding scheme.
+    pub fn into_inner(self) -> FS {
+        self.folding_scheme
+    }
+}
+
+/// Reads and validates the checkpoint at the given step.
+fn load_checkpoint<C1, C2, FC, FS>(
+    dir: &Path,
+    step: u64,
+    params: &(FS::ProverParam, FS::VerifierParam),
+    step_circuit: FC,
+) -> Result<FS, Error>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    FS: Checkpointable<C1, C2, FC>,
+{
+    let manifest = fs::read_to_string(checkpoint_path(dir, step, MANIFEST_EXTENSION))?;
+    let manifest = Manifest::<C1::ScalarField>::from_json(&manifest)?;
+    let state = fs::read(checkpoint_path(dir, step, STATE_EXTENSION))?;
+    if manifest.step != step || Sha3_256::digest(&state).as_slice() != manifest.state_digest {
+        return Err(Error::InvalidCheckpoint(format!(
+            "state of step {} does not match its manifest",
+            step
+        )));
+    }
+
+    let folding_scheme = FS::deserialize_state(state.as_slice(), params, step_circuit)?;
+    if folding_scheme.pp_hash() != manifest.pp_hash
+        || field_to_u64(folding_scheme.num_steps())? != manifest.step
+        || folding_scheme.state() != manifest.z_i
+    {
+        return Err(Error::InvalidCheckpoint(format!(
+            "state of step {} does not match its manifest",
+            step
+        )));
+    }
+    Ok(folding_scheme)
+}
+
+/// Returns the steps of the checkpoints (with manifest) found in the given directory.
+fn list_checkpoints(dir: &Path) -> Result<Vec<u64>, Error> {
+    let mut steps = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(MANIFEST_EXTENSION) {
+            continue;
+        }
+        let step = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_prefix(CHECKPOINT_PREFIX))
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(step) = step {
+            steps.push(step);
+        }
+    }
+    Ok(steps)
+}
+
+fn checkpoint_path(dir: &Path, step: u64, extension: &str) -> PathBuf {
+    dir.join(format!("{}{:020}.{}", CHECKPOINT_PREFIX, step, extension))
+}
+
+/// Writes the data into a temporary file which is then renamed into `path`, so that `path` either
+/// contains the full data or is not modified.
+fn write_atomic(dir: &Path, path: &Path, data: &[u8]) -> Result<(), Error> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    // persist the rename. Opening a directory is not supported in all platforms, so this is done
+    // in a best-effort basis.
+    if let Ok(dir) = File::open(dir) {
+        dir.sync_all().ok();
+    }
+    Ok(())
+}
+
+fn field_to_u64<F: PrimeField>(x: F) -> Result<u64, Error> {
+    u64::try_from(&BigUint::from(x.into_bigint())).map_err(|_| Error::MaxStep)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    type N = Nova<
+        Projective,
+        GVar,
+        Projective2,
+        GVar2,
+        CubicFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+    type CP = Checkpointer<Projective, Projective2, CubicFCircuit<Fr>, N>;
+
+    #[test]
+    fn test_manifest_json() {
+        let manifest = Manifest::<Fr> {
+            pp_hash: Fr::from(42_u32),
+            step: 7,
+            z_i: vec![Fr::from(3_u32), -Fr::from(1_u32)],
+            state_digest: vec![0, 1, 254, 255],
+        };
+        assert_eq!(Manifest::from_json(&manifest.to_json()).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_checkpoint_and_resume() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+        let nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)]).unwrap();
+
+        let dir = std::env::temp_dir().join("sonobe-test-checkpoint-and-resume");
+        fs::remove_dir_all(&dir).ok();
+        let mut checkpointer = CP::new(nova, &dir, 2).unwrap();
+        for _ in 0..5 {
+            let metrics = checkpointer.prove_step(&mut rng, vec![], None).unwrap();
+            assert!(metrics.total > std::time::Duration::ZERO);
+        }
+        assert_eq!(list_checkpoints(&dir).unwrap().len(), 2);
+
+        // resumes from the latest checkpoint, at step 4
+        let resumed = CP::resume(&dir, 2, &nova_params, F_circuit).unwrap();
+        assert_eq!(resumed.folding_scheme.i, Fr::from(4_u32));
+
+        // corrupt the latest checkpoint, resume should fall back to the one at step 2
+        let mut state = fs::read(checkpoint_path(&dir, 4, STATE_EXTENSION)).unwrap();
+        state.truncate(state.len() / 2);
+        fs::write(checkpoint_path(&dir, 4, STATE_EXTENSION), state).unwrap();
+        let mut resumed = CP::resume(&dir, 2, &nova_params, F_circuit).unwrap();
+        assert_eq!(resumed.folding_scheme.i, Fr::from(2_u32));
+
+        // continue proving from the resumed state
+        for _ in 0..3 {
+            resumed.prove_step(&mut rng, vec![], None).unwrap();
+        }
+        let nova = resumed.into_inner();
+        let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
+        N::verify(
+            nova_params.1,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            nova.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
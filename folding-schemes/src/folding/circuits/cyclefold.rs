@@ -19,6 +19,7 @@ use ark_std::fmt::Debug;
 use ark_std::rand::RngCore;
 use ark_std::Zero;
 use core::{borrow::Borrow, marker::PhantomData};
+use tracing::info_span;
 
 use super::{nonnative::uint::NonNativeUintVar, CF2};
 use crate::arith::r1cs::{extract_w_x, R1CS};
@@ -307,6 +308,18 @@ impl<C: CurveGroup, GC: CurveVar<C, CF2<C>>> CycleFoldCircuit<C, GC> {
             x: None,
         }
     }
+    /// Same as `empty`, but for the case in which the scalars used to fold the points are
+    /// `r_bits_len` bits long instead of `N_BITS_RO` (eg. in ProtoGalaxy the points are folded by
+    /// full field elements).
+    pub fn empty_with_r_bits_len(n_points: usize, r_bits_len: usize) -> Self {
+        Self {
+            _gc: PhantomData,
+            n_points,
+            r_bits: Some(vec![vec![false; r_bits_len]; n_points - 1]),
+            points: None,
+            x: None,
+        }
+    }
 }
 impl<C, GC> ConstraintSynthesizer<CF2<C>> for CycleFoldCircuit<C, GC>
 where
@@ -380,7 +393,7 @@ where
 }
 
 /// Folds the given cyclefold circuit and its instances. This method is abstracted from any folding
-/// scheme struct because it is used both by Nova & HyperNova's CycleFold.
+/// scheme struct because it is used by Nova, HyperNova & ProtoGalaxy's CycleFold.
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_arguments)]
 pub fn fold_cyclefold_circuit<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool>(
@@ -421,11 +434,14 @@ where
     for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
     for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
 {
-    let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-    cf_circuit.generate_constraints(cs2.clone())?;
+    let (cf_w_i, cf_x_i) =
+        info_span!("cf_witness_generation").in_scope(|| -> Result<_, Error> {
+            let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
+            cf_circuit.generate_constraints(cs2.clone())?;
 
-    let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-    let (cf_w_i, cf_x_i) = extract_w_x::<C1::BaseField>(&cs2);
+            let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+            Ok(extract_w_x::<C1::BaseField>(&cs2))
+        })?;
     if cf_x_i != cf_u_i_x {
         return Err(Error::NotEqual);
     }
@@ -437,15 +453,18 @@ where
     let cf_w_i = Witness::<C2>::new::<H>(cf_w_i.clone(), cf_r1cs.A.n_rows, &mut rng);
     let cf_u_i: CommittedInstance<C2> = cf_w_i.commit::<CS2, H>(&cf_cs_params, cf_x_i.clone())?;
 
-    // compute T* and cmT* for CycleFoldCircuit
-    let (cf_T, cf_cmT) = NIFS::<C2, CS2, H>::compute_cyclefold_cmT(
-        &cf_cs_params,
-        &cf_r1cs,
-        &cf_w_i,
-        &cf_u_i,
-        &cf_W_i,
-        &cf_U_i,
-    )?;
+    // compute T* and cmT* for CycleFoldCircuit, blinding cmT* when H is set
+    let (cf_T, cf_rT, cf_cmT) = info_span!("cf_cross_term").in_scope(|| {
+        NIFS::<C2, CS2, H>::compute_cmT_with_blinding(
+            &cf_cs_params,
+            &cf_r1cs,
+            &cf_w_i,
+            &cf_u_i,
+            &cf_W_i,
+            &cf_U_i,
+            &mut rng,
+        )
+    })?;
 
     let cf_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_native(
         transcript,
@@ -457,8 +476,8 @@ where
     let cf_r_Fq = C1::BaseField::from_bigint(BigInteger::from_bits_le(&cf_r_bits))
         .expect("cf_r_bits out of bounds");
 
-    let (cf_W_i1, cf_U_i1) = NIFS::<C2, CS2, H>::fold_instances(
-        cf_r_Fq, &cf_W_i, &cf_U_i, &cf_w_i, &cf_u_i, &cf_T, cf_cmT,
+    let (cf_W_i1, cf_U_i1) = NIFS::<C2, CS2, H>::fold_instances_with_blinding(
+        cf_r_Fq, &cf_W_i, &cf_U_i, &cf_w_i, &cf_u_i, &cf_T, cf_rT, cf_cmT,
     )?;
     Ok((cf_w_i, cf_u_i, cf_W_i1, cf_U_i1, cf_cmT, cf_r_Fq))
 }
@@ -0,0 +1,631 @@
+/// This file implements the onchain (Ethereum's EVM) decider circuit. For non-ethereum use cases,
+/// other more efficient approaches can be used.
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_poly::Polynomial;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    groups::GroupOpsBounds,
+    prelude::CurveVar,
+    ToConstraintFieldGadget,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::Zero;
+use core::{borrow::Borrow, marker::PhantomData};
+
+use super::{
+    cccs::CCCS,
+    circuits::{CCCSVar, LCCCSVar, NIMFSGadget, ProofVar},
+    lcccs::LCCCS,
+    nimfs::{NIMFSProof, NIMFS},
+    HyperNova, Witness,
+};
+use crate::arith::{ccs::CCS, r1cs::R1CS};
+use crate::commitment::{pedersen::Params as PedersenParams, CommitmentScheme};
+use crate::folding::circuits::{nonnative::affine::NonNativeAffineVar, CF1, CF2};
+use crate::folding::nova::{
+    decider_eth_circuit::evaluate_gadget, CommittedInstance, Witness as NovaWitness,
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{Transcript, TranscriptVar};
+use crate::utils::{
+    gadgets::{MatrixGadget, SparseMatrixVar},
+    vec::poly_from_vec,
+};
+use crate::Error;
+
+/// In-circuit representation of the Witness associated to the LCCCS & CCCS instances.
+#[derive(Debug, Clone)]
+pub struct WitnessVar<F: PrimeField> {
+    pub w: Vec<FpVar<F>>,
+    pub r_w: FpVar<F>,
+}
+
+impl<F: PrimeField> AllocVar<Witness<F>, F> for WitnessVar<F> {
+    fn new_variable<T: Borrow<Witness<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+
+            let w: Vec<FpVar<F>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().w.clone()), mode)?;
+            let r_w = FpVar::<F>::new_variable(cs.clone(), || Ok(val.borrow().r_w), mode)?;
+
+            Ok(Self { w, r_w })
+        })
+    }
+}
+
+/// CCSMatricesVar contains the matrices 'M' of the CCS without the rest of CCS parameters.
+#[derive(Debug, Clone)]
+pub struct CCSMatricesVar<F: PrimeField> {
+    // we only need native representation, so the constraint field==F
+    pub M: Vec<SparseMatrixVar<F, F, FpVar<F>>>,
+}
+
+impl<F: PrimeField> AllocVar<CCS<F>, F> for CCSMatricesVar<F> {
+    fn new_variable<T: Borrow<CCS<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let M: Vec<SparseMatrixVar<F, F, FpVar<F>>> = val
+                .borrow()
+                .M
+                .iter()
+                .map(|M_j| SparseMatrixVar::new_variable(cs.clone(), || Ok(M_j.clone()), mode))
+                .collect::<Result<_, SynthesisError>>()?;
+            Ok(Self { M })
+        })
+    }
+}
+
+/// Gadget that checks the LCCCS relation in-circuit, compatible with the native
+/// `LCCCS.check_relation`.
+pub struct LCCCSCheckerGadget {}
+impl LCCCSCheckerGadget {
+    /// Checks that `v_j == \tilde{M_j z}(r_x)` for each `j`, where `z=[u, x, w]`. The multilinear
+    /// extension of `M_j z` is evaluated at `r_x` by computing the table of `eq(r_x, y)` for all
+    /// `y \in {0,1}^s`, following the same variables order as `dense_vec_to_dense_mle`.
+    pub fn check<F: PrimeField>(
+        s: usize,
+        ccs_mat: CCSMatricesVar<F>,
+        z: Vec<FpVar<F>>,
+        // LCCCS values
+        r_x: Vec<FpVar<F>>,
+        v: Vec<FpVar<F>>,
+    ) -> Result<(), SynthesisError> {
+        if r_x.len() != s || v.len() != ccs_mat.M.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // eq_table[y] = \prod_k (r_x[k] * y_k + (1-r_x[k]) * (1-y_k)), where y_k is the k-th bit of
+        // y in little-endian
+        let mut eq_table = vec![FpVar::<F>::one()];
+        for r_k in r_x.iter() {
+            let (lo, hi): (Vec<FpVar<F>>, Vec<FpVar<F>>) = eq_table
+                .iter()
+                .map(|b| {
+                    let tmp = b * r_k;
+                    (b - &tmp, tmp)
+                })
+                .unzip();
+            eq_table = [lo, hi].concat();
+        }
+
+        for (M_j, v_j) in ccs_mat.M.iter().zip(v.iter()) {
+            let Mz = M_j.mul_vector(&z)?;
+            if Mz.len() > eq_table.len() {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            // the padding of Mz to 2^s is zeroes, so it does not affect the sum
+            let computed_v_j: FpVar<F> = Mz
+                .iter()
+                .zip(eq_table.iter())
+                .map(|(Mz_y, eq_y)| Mz_y * eq_y)
+                .sum();
+            computed_v_j.enforce_equal(v_j)?;
+        }
+        Ok(())
+    }
+}
+
+/// Circuit that implements the in-circuit checks needed for the HyperNova's onchain (Ethereum's
+/// EVM) verification.
+#[derive(Clone, Debug)]
+pub struct DeciderEthCircuit<C1, GC1, C2, GC2, CS1, CS2, const H: bool = false>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    _c1: PhantomData<C1>,
+    _gc1: PhantomData<GC1>,
+    _c2: PhantomData<C2>,
+    _gc2: PhantomData<GC2>,
+    _cs1: PhantomData<CS1>,
+    _cs2: PhantomData<CS2>,
+
+    /// E vector's length of the CycleFold instance witness
+    pub cf_E_len: usize,
+    /// CCS of the Augmented Function circuit
+    pub ccs: CCS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    /// CycleFold PedersenParams over C2
+    pub cf_pedersen_params: PedersenParams<C2>,
+    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    /// public params hash
+    pub pp_hash: Option<C1::ScalarField>,
+    pub i: Option<CF1<C1>>,
+    /// initial state
+    pub z_0: Option<Vec<C1::ScalarField>>,
+    /// current i-th state
+    pub z_i: Option<Vec<C1::ScalarField>>,
+    /// HyperNova instances
+    pub U_i: Option<LCCCS<C1>>,
+    pub u_i: Option<CCCS<C1>>,
+    pub W_i1: Option<Witness<C1::ScalarField>>,
+    /// NIMFS proof of the last fold, U_{i+1} = NIMFS.V(U_i, u_i, nimfs_proof), which is verified
+    /// in-circuit
+    pub nimfs_proof: Option<NIMFSProof<C1>>,
+    /// folding challenge of the last fold, used by the verifier to compute U_{i+1}.C
+    pub rho: Option<C1::ScalarField>,
+    /// CycleFold running instance
+    pub cf_U_i: Option<CommittedInstance<C2>>,
+    pub cf_W_i: Option<NovaWitness<C2>>,
+
+    /// KZG challenge & evaluation of the U_{i+1}.C opening
+    pub kzg_challenge: Option<C1::ScalarField>,
+    pub eval_W: Option<C1::ScalarField>,
+}
+
+impl<C1, GC1, C2, GC2, CS1, CS2, const H: bool> DeciderEthCircuit<C1, GC1, C2, GC2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    CS1: CommitmentScheme<C1, H>,
+    // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
+    CS2: CommitmentScheme<C2, H, ProverParams = PedersenParams<C2>>,
+    <C1 as Group>::ScalarField: Absorb,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+{
+    pub fn from_hypernova<FC: FCircuit<C1::ScalarField>>(
+        hn: HyperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>,
+    ) -> Result<Self, Error> {
+        // compute the U_{i+1}, W_{i+1}, by folding the last running & incoming instances
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&hn.poseidon_config);
+        transcript.absorb(&hn.pp_hash);
+        let (nimfs_proof, U_i1, W_i1, rho_powers) =
+            NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+                &mut transcript,
+                &hn.ccs,
+                &[hn.U_i.clone()],
+                &[hn.u_i.clone()],
+                &[hn.W_i.clone()],
+                &[hn.w_i.clone()],
+            )?;
+
+        // sanity check: check the folded instance relation
+        #[cfg(test)]
+        U_i1.check_relation(&hn.ccs, &W_i1)?;
+
+        // compute the KZG challenge used as input in the circuit. U_{i+1}.C is fully determined
+        // by U_i.C, u_i.C and rho, which have already been absorbed by the transcript.
+        let kzg_challenge = KZGChallengeGadget::<C1>::get_challenge_native(&mut transcript);
+
+        // get KZG evals
+        let mut W = W_i1.w.clone();
+        W.extend(
+            std::iter::repeat(C1::ScalarField::zero())
+                .take(W_i1.w.len().next_power_of_two() - W_i1.w.len()),
+        );
+        let p_W = poly_from_vec(W.to_vec())?;
+        let eval_W = p_W.evaluate(&kzg_challenge);
+
+        Ok(Self {
+            _c1: PhantomData,
+            _gc1: PhantomData,
+            _c2: PhantomData,
+            _gc2: PhantomData,
+            _cs1: PhantomData,
+            _cs2: PhantomData,
+
+            cf_E_len: hn.cf_W_i.E.len(),
+            ccs: hn.ccs,
+            cf_r1cs: hn.cf_r1cs,
+            cf_pedersen_params: hn.cf_cs_params,
+            poseidon_config: hn.poseidon_config,
+            pp_hash: Some(hn.pp_hash),
+            i: Some(hn.i),
+            z_0: Some(hn.z_0),
+            z_i: Some(hn.z_i),
+            U_i: Some(hn.U_i),
+            u_i: Some(hn.u_i),
+            W_i1: Some(W_i1),
+            nimfs_proof: Some(nimfs_proof),
+            rho: Some(rho_powers[0]),
+            cf_U_i: Some(hn.cf_U_i),
+            cf_W_i: Some(hn.cf_W_i),
+            kzg_challenge: Some(kzg_challenge),
+            eval_W: Some(eval_W),
+        })
+    }
+}
+
+impl<C1, GC1, C2, GC2, CS1, CS2> ConstraintSynthesizer<CF1<C1>>
+    for DeciderEthCircuit<C1, GC1, C2, GC2, CS1, CS2>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>>,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    CS1: CommitmentScheme<C1>,
+    CS2: CommitmentScheme<C2>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'b> &'b GC2: GroupOpsBounds<'b, C2, GC2>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        let ccs_matrices = CCSMatricesVar::<CF1<C1>>::new_constant(cs.clone(), self.ccs.clone())?;
+
+        let pp_hash = FpVar::<CF1<C1>>::new_input(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i =
+            FpVar::<CF1<C1>>::new_input(cs.clone(), || Ok(self.i.unwrap_or_else(CF1::<C1>::zero)))?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_input(cs.clone(), || {
+            Ok(self.z_0.unwrap_or(vec![CF1::<C1>::zero()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_input(cs.clone(), || {
+            Ok(self.z_i.unwrap_or(vec![CF1::<C1>::zero()]))
+        })?;
+
+        let U_dummy_native = LCCCS::<C1>::dummy(self.ccs.l, self.ccs.t, self.ccs.s);
+        let u_dummy_native = CCCS::<C1>::dummy(self.ccs.l);
+        let w_dummy_native = Witness::<C1::ScalarField>::dummy(&self.ccs);
+        let nimfs_proof_dummy = NIMFSProof::<C1>::dummy(&self.ccs, 1, 1);
+
+        let U_i_native = self.U_i.unwrap_or(U_dummy_native);
+        let u_i_native = self.u_i.unwrap_or(u_dummy_native);
+
+        // the commitments of U_i & u_i are public inputs, so the verifier can compute
+        // U_{i+1}.C = U_i.C + rho * u_i.C, while the rest of the values of the instances are
+        // witnesses which are checked in-circuit
+        let U_i = LCCCSVar::<C1> {
+            C: NonNativeAffineVar::new_input(cs.clone(), || Ok(U_i_native.C))?,
+            u: FpVar::new_witness(cs.clone(), || Ok(U_i_native.u))?,
+            x: Vec::new_witness(cs.clone(), || Ok(U_i_native.x.clone()))?,
+            r_x: Vec::new_witness(cs.clone(), || Ok(U_i_native.r_x.clone()))?,
+            v: Vec::new_witness(cs.clone(), || Ok(U_i_native.v.clone()))?,
+        };
+        let u_i = CCCSVar::<C1> {
+            C: NonNativeAffineVar::new_input(cs.clone(), || Ok(u_i_native.C))?,
+            x: Vec::new_witness(cs.clone(), || Ok(u_i_native.x.clone()))?,
+        };
+        // here (U_i1, W_i1) = NIMFS.P( (U_i,W_i), (u_i,w_i))
+        let W_i1 = WitnessVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.W_i1.unwrap_or(w_dummy_native.clone()))
+        })?;
+        let nimfs_proof = ProofVar::<C1>::new_witness(cs.clone(), || {
+            Ok(self.nimfs_proof.unwrap_or(nimfs_proof_dummy))
+        })?;
+
+        // allocate the inputs for the check 5
+        let kzg_challenge = FpVar::<CF1<C1>>::new_input(cs.clone(), || {
+            Ok(self.kzg_challenge.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let eval_W = FpVar::<CF1<C1>>::new_input(cs.clone(), || {
+            Ok(self.eval_W.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+        transcript.absorb(&pp_hash)?;
+
+        // 1.a u_i.x[0] == H(i, z_0, z_i, U_i)
+        let (u_i_x, _) = U_i.clone().hash(
+            &sponge,
+            pp_hash.clone(),
+            i.clone(),
+            z_0.clone(),
+            z_i.clone(),
+        )?;
+        (u_i.x[0]).enforce_equal(&u_i_x)?;
+
+        #[cfg(feature = "light-test")]
+        println!("[WARNING]: Running with the 'light-test' feature, skipping the big part of the DeciderEthCircuit.\n           Only for testing purposes.");
+
+        // The following two checks (and their respective allocations) are disabled for normal
+        // tests since they take several millions of constraints and would take several minutes
+        // (and RAM) to run the test. It is active by default, and not active only when
+        // 'light-test' feature is used.
+        #[cfg(not(feature = "light-test"))]
+        {
+            // imports here instead of at the top of the file, so we avoid having multiple
+            // `#[cfg(not(test))]`
+            use crate::commitment::pedersen::PedersenGadget;
+            use crate::folding::{
+                circuits::{
+                    cyclefold::CycleFoldCommittedInstanceVar, nonnative::uint::NonNativeUintVar,
+                },
+                nova::decider_eth_circuit::{CycleFoldWitnessVar, R1CSVar, RelaxedR1CSGadget},
+            };
+            use ark_r1cs_std::ToBitsGadget;
+
+            let cf_u_dummy_native = CommittedInstance::<C2>::dummy(self.cf_r1cs.l);
+            let w_dummy_native =
+                NovaWitness::<C2>::dummy(self.cf_r1cs.A.n_cols - 1 - self.cf_r1cs.l, self.cf_E_len);
+            let cf_U_i = CycleFoldCommittedInstanceVar::<C2, GC2>::new_witness(cs.clone(), || {
+                Ok(self.cf_U_i.unwrap_or_else(|| cf_u_dummy_native.clone()))
+            })?;
+            let cf_W_i = CycleFoldWitnessVar::<C2>::new_witness(cs.clone(), || {
+                Ok(self.cf_W_i.unwrap_or(w_dummy_native.clone()))
+            })?;
+
+            // 1.b u_i.x[1] == H(cf_U_i)
+            let (cf_u_i_x, _) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+            (u_i.x[1]).enforce_equal(&cf_u_i_x)?;
+
+            // 2. check Pedersen commitments of cf_U_i.{cmE, cmW}
+            let H = GC2::new_constant(cs.clone(), self.cf_pedersen_params.h)?;
+            let G = Vec::<GC2>::new_constant(cs.clone(), self.cf_pedersen_params.generators)?;
+            let cf_W_i_E_bits: Result<Vec<Vec<Boolean<CF1<C1>>>>, SynthesisError> =
+                cf_W_i.E.iter().map(|E_i| E_i.to_bits_le()).collect();
+            let cf_W_i_W_bits: Result<Vec<Vec<Boolean<CF1<C1>>>>, SynthesisError> =
+                cf_W_i.W.iter().map(|W_i| W_i.to_bits_le()).collect();
+
+            let computed_cmE = PedersenGadget::<C2, GC2>::commit(
+                H.clone(),
+                G.clone(),
+                cf_W_i_E_bits?,
+                cf_W_i.rE.to_bits_le()?,
+            )?;
+            cf_U_i.cmE.enforce_equal(&computed_cmE)?;
+            let computed_cmW =
+                PedersenGadget::<C2, GC2>::commit(H, G, cf_W_i_W_bits?, cf_W_i.rW.to_bits_le()?)?;
+            cf_U_i.cmW.enforce_equal(&computed_cmW)?;
+
+            let cf_r1cs =
+                R1CSVar::<C1::BaseField, CF1<C1>, NonNativeUintVar<CF1<C1>>>::new_witness(
+                    cs.clone(),
+                    || Ok(self.cf_r1cs.clone()),
+                )?;
+
+            // 3. check RelaxedR1CS of cf_U_i
+            let cf_z_U = [vec![cf_U_i.u.clone()], cf_U_i.x.to_vec(), cf_W_i.W.to_vec()].concat();
+            RelaxedR1CSGadget::check_nonnative(cf_r1cs, cf_W_i.E, cf_U_i.u.clone(), cf_z_U)?;
+        }
+
+        // 4.a compute NIMFS.V, obtaining the folded U_{i+1} values except for U_{i+1}.C, which
+        // is computed by the verifier outside of the circuit from the public inputs
+        let (U_i1, rho_bits) = NIMFSGadget::<C1>::verify(
+            cs.clone(),
+            &self.ccs,
+            &mut transcript,
+            &[U_i],
+            &[u_i],
+            nimfs_proof,
+            Boolean::TRUE,
+        )?;
+
+        // 5.a compute the KZG challenge, which needs to be done after the NIMFS.V in order to
+        // match the native transcript
+        let incircuit_challenge = KZGChallengeGadget::<C1>::get_challenge_gadget(&mut transcript)?;
+
+        // 4.b check the LCCCS relation of U_{i+1}, which includes checking the evaluations v_j
+        // derived from the sum-check
+        let z_U1: Vec<FpVar<CF1<C1>>> =
+            [vec![U_i1.u.clone()], U_i1.x.to_vec(), W_i1.w.to_vec()].concat();
+        LCCCSCheckerGadget::check(self.ccs.s, ccs_matrices, z_U1, U_i1.r_x, U_i1.v)?;
+
+        // 5.b check KZG challenge
+        incircuit_challenge.enforce_equal(&kzg_challenge)?;
+
+        // 6. check eval_W==p_W(c_W), which together with the KZG proof verified outside of the
+        // circuit opens the commitment U_{i+1}.C to W_{i+1}
+        let mut W = W_i1.w;
+        W.resize(W.len().next_power_of_two(), FpVar::zero());
+        let incircuit_eval_W = evaluate_gadget::<CF1<C1>>(W, incircuit_challenge)?;
+        incircuit_eval_W.enforce_equal(&eval_W)?;
+
+        // 7. check the NIMFS.V challenge matches the one from the public input (so we avoid the
+        // verifier computing it)
+        let rho_Fr = Boolean::le_bits_to_fp_var(&rho_bits[0])?;
+        let rho = FpVar::<CF1<C1>>::new_input(cs.clone(), || {
+            Ok(self.rho.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        rho_Fr.enforce_equal(&rho)?;
+
+        Ok(())
+    }
+}
+
+/// Gadget that computes the KZG challenge, also offers the rust native implementation compatible
+/// with the gadget.
+pub struct KZGChallengeGadget<C: CurveGroup> {
+    _c: PhantomData<C>,
+}
+impl<C> KZGChallengeGadget<C>
+where
+    C: CurveGroup,
+    C::ScalarField: PrimeField,
+    <C as CurveGroup>::BaseField: PrimeField,
+    C::ScalarField: Absorb,
+{
+    pub fn get_challenge_native<T: Transcript<C::ScalarField>>(
+        transcript: &mut T,
+    ) -> C::ScalarField {
+        transcript.get_challenge()
+    }
+    // compatible with the native get_challenge_native
+    pub fn get_challenge_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        transcript: &mut T,
+    ) -> Result<FpVar<C::ScalarField>, SynthesisError> {
+        transcript.get_challenge()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use super::*;
+    use crate::arith::ccs::tests::{get_test_ccs, get_test_z};
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_lcccs_checker_gadget() {
+        let mut rng = test_rng();
+        let n_rows = 2_u32.pow(5) as usize;
+        let n_cols = 2_u32.pow(5) as usize;
+        let r1cs = R1CS::<Fr>::rand(&mut rng, n_rows, n_cols);
+        let ccs = CCS::from_r1cs(r1cs);
+        let z: Vec<Fr> = (0..n_cols).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, ccs.n - ccs.l - 1).unwrap();
+
+        let (lcccs, _) = ccs
+            .to_lcccs::<_, Projective, Pedersen<Projective>, false>(&mut rng, &pedersen_params, &z)
+            .unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // CCS's (sparse) matrices are constants in the circuit
+        let ccs_mat = CCSMatricesVar::<Fr>::new_constant(cs.clone(), ccs.clone()).unwrap();
+        let zVar = Vec::<FpVar<Fr>>::new_input(cs.clone(), || Ok(z)).unwrap();
+        let r_xVar = Vec::<FpVar<Fr>>::new_input(cs.clone(), || Ok(lcccs.r_x)).unwrap();
+        let vVar = Vec::<FpVar<Fr>>::new_input(cs.clone(), || Ok(lcccs.v)).unwrap();
+
+        LCCCSCheckerGadget::check(ccs.s, ccs_mat, zVar, r_xVar, vVar).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_lcccs_checker_gadget_wrong_v() {
+        let mut rng = test_rng();
+        let ccs = get_test_ccs::<Fr>();
+        let z = get_test_z(3);
+
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, ccs.n - ccs.l - 1).unwrap();
+        let (mut lcccs, _) = ccs
+            .to_lcccs::<_, Projective, Pedersen<Projective>, false>(&mut rng, &pedersen_params, &z)
+            .unwrap();
+        lcccs.v[0] += Fr::from(1_u32);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let ccs_mat = CCSMatricesVar::<Fr>::new_constant(cs.clone(), ccs.clone()).unwrap();
+        let zVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z)).unwrap();
+        let r_xVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(lcccs.r_x)).unwrap();
+        let vVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(lcccs.v)).unwrap();
+
+        LCCCSCheckerGadget::check(ccs.s, ccs_mat, zVar, r_xVar, vVar).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_decider_circuit() {
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let z_0 = vec![Fr::from(3_u32)];
+
+        const MU: usize = 1;
+        const NU: usize = 1;
+
+        type HN = HyperNova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >::new(poseidon_config, F_circuit);
+        let hypernova_params = HN::preprocess(&mut rng, &(prep_param, MU, NU)).unwrap();
+
+        // generate a HyperNova instance and do two steps of it, so the running instance is not
+        // the dummy one
+        let mut hypernova = HN::init(&hypernova_params, F_circuit, z_0.clone()).unwrap();
+        hypernova
+            .prove_step(&mut rng, vec![], Some((vec![], vec![])))
+            .unwrap();
+        hypernova
+            .prove_step(&mut rng, vec![], Some((vec![], vec![])))
+            .unwrap();
+
+        let ivc_v = hypernova.clone();
+        let (running_instance, incoming_instance, cyclefold_instance) = ivc_v.instances();
+        HN::verify(
+            hypernova_params.1, // verifier_params
+            z_0,
+            ivc_v.z_i,
+            ivc_v.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+
+        // load the DeciderEthCircuit from the generated HyperNova instance
+        let decider_circuit = DeciderEthCircuit::<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >::from_hypernova(hypernova)
+        .unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // generate the constraints and check that are satisfied by the inputs
+        decider_circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -6,16 +6,22 @@ use ark_crypto_primitives::sponge::{
 use ark_ec::{CurveGroup, Group};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::RngCore;
 use ark_std::{One, Zero};
 use core::marker::PhantomData;
 use std::fmt::Debug;
+use std::time::Instant;
+use tracing::info_span;
 
 pub mod cccs;
 pub mod circuits;
 use circuits::AugmentedFCircuit;
+pub mod decider_eth;
+pub mod decider_eth_circuit;
 pub mod lcccs;
 pub mod nimfs;
+pub mod serialize;
 pub mod utils;
 use cccs::CCCS;
 use lcccs::LCCCS;
@@ -32,18 +38,18 @@ use crate::folding::nova::{
     Witness as NovaWitness,
 };
 use crate::frontend::FCircuit;
-use crate::utils::{get_cm_coordinates, pp_hash};
+use crate::utils::{get_cm_coordinates, pp_hash, timed};
 use crate::Error;
 use crate::{
     arith::{
         ccs::CCS,
         r1cs::{extract_w_x, R1CS},
     },
-    FoldingScheme, MultiFolding,
+    FoldingScheme, MultiFolding, StepMetrics,
 };
 
 /// Witness for the LCCCS & CCCS, containing the w vector, and the r_w used as randomness in the Pedersen commitment.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Witness<F: PrimeField> {
     pub w: Vec<F>,
     pub r_w: F,
@@ -160,6 +166,26 @@ where
     pub cf_U_i: CommittedInstance<C2>,
 }
 
+/// IVCProof contains all the values needed to verify the HyperNova+CycleFold IVC, so that it can
+/// be serialized and verified through `FoldingScheme::verify_proof`.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1: CurveGroup, C2: CurveGroup> {
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// number of steps
+    pub i: C1::ScalarField,
+    /// HyperNova instances
+    pub W_i: Witness<C1::ScalarField>,
+    pub U_i: LCCCS<C1>,
+    pub w_i: Witness<C1::ScalarField>,
+    pub u_i: CCCS<C1>,
+    /// CycleFold running instance
+    pub cf_W_i: NovaWitness<C2>,
+    pub cf_U_i: CommittedInstance<C2>,
+}
+
 impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> MultiFolding<C1, C2, FC>
     for HyperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
 where
@@ -363,6 +389,7 @@ where
     type MultiCommittedInstanceWithWitness =
         (Vec<Self::RunningInstance>, Vec<Self::IncomingInstance>);
     type CFInstance = (CommittedInstance<C2>, NovaWitness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
 
     fn preprocess(
         mut rng: impl RngCore,
@@ -500,12 +527,16 @@ where
     }
 
     /// Implements IVC.P of HyperNova+CycleFold
+    #[tracing::instrument(skip_all, name = "hypernova_prove_step")]
     fn prove_step(
         &mut self,
         mut rng: impl RngCore,
         external_inputs: Vec<C1::ScalarField>,
         other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
-    ) -> Result<(), Error> {
+    ) -> Result<StepMetrics, Error> {
+        let start = Instant::now();
+        let mut metrics = StepMetrics::default();
+
         // ensure that commitments are blinding if user has specified so.
 
         if H {
@@ -583,9 +614,14 @@ where
         i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
         let i_usize: usize = usize::from_le_bytes(i_bytes);
 
-        let z_i1 = self
-            .F
-            .step_native(i_usize, self.z_i.clone(), external_inputs.clone())?;
+        let z_i1 = timed(
+            info_span!("step_native"),
+            &mut metrics.witness_generation,
+            || {
+                self.F
+                    .step_native(i_usize, self.z_i.clone(), external_inputs.clone())
+            },
+        )?;
 
         // u_{i+1}.x[1] = H(cf_U_{i+1})
         let cf_u_i1_x: C1::ScalarField;
@@ -642,14 +678,16 @@ where
             transcript_p.absorb(&self.pp_hash);
             let (rho_powers, nimfs_proof);
             (nimfs_proof, U_i1, W_i1, rho_powers) =
-                NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
-                    &mut transcript_p,
-                    &self.ccs,
-                    &[vec![self.U_i.clone()], Us.clone()].concat(),
-                    &[vec![self.u_i.clone()], us.clone()].concat(),
-                    &[vec![self.W_i.clone()], Ws].concat(),
-                    &[vec![self.w_i.clone()], ws].concat(),
-                )?;
+                timed(info_span!("nimfs"), &mut metrics.folding, || {
+                    NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+                        &mut transcript_p,
+                        &self.ccs,
+                        &[vec![self.U_i.clone()], Us.clone()].concat(),
+                        &[vec![self.u_i.clone()], us.clone()].concat(),
+                        &[vec![self.W_i.clone()], Ws].concat(),
+                        &[vec![self.w_i.clone()], ws].concat(),
+                    )
+                })?;
 
             // sanity check: check the folded instance relation
             #[cfg(test)]
@@ -715,18 +753,20 @@ where
             };
 
             let (_cf_w_i, cf_u_i, cf_W_i1, cf_U_i1, cf_cmT, _) =
-                fold_cyclefold_circuit::<C1, GC1, C2, GC2, FC, CS1, CS2, H>(
-                    self.mu + self.nu,
-                    &mut transcript_p,
-                    self.cf_r1cs.clone(),
-                    self.cf_cs_params.clone(),
-                    self.pp_hash,
-                    self.cf_W_i.clone(), // CycleFold running instance witness
-                    self.cf_U_i.clone(), // CycleFold running instance
-                    cf_u_i_x,
-                    cf_circuit,
-                    &mut rng,
-                )?;
+                timed(info_span!("cyclefold"), &mut metrics.cyclefold, || {
+                    fold_cyclefold_circuit::<C1, GC1, C2, GC2, FC, CS1, CS2, H>(
+                        self.mu + self.nu,
+                        &mut transcript_p,
+                        self.cf_r1cs.clone(),
+                        self.cf_cs_params.clone(),
+                        self.pp_hash,
+                        self.cf_W_i.clone(), // CycleFold running instance witness
+                        self.cf_U_i.clone(), // CycleFold running instance
+                        cf_u_i_x,
+                        cf_circuit,
+                        &mut rng,
+                    )
+                })?;
 
             cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, self.pp_hash);
 
@@ -764,12 +804,18 @@ where
             self.cf_U_i = cf_U_i1;
         }
 
-        let (cs, _) = augmented_f_circuit.compute_cs_ccs()?;
+        let (r1cs_w_i1, r1cs_x_i1) = timed(
+            info_span!("witness_generation"),
+            &mut metrics.witness_generation,
+            || -> Result<_, Error> {
+                let (cs, _) = augmented_f_circuit.compute_cs_ccs()?;
 
-        #[cfg(test)]
-        assert!(cs.is_satisfied()?);
+                #[cfg(test)]
+                assert!(cs.is_satisfied()?);
 
-        let (r1cs_w_i1, r1cs_x_i1) = extract_w_x::<C1::ScalarField>(&cs); // includes 1 and public inputs
+                Ok(extract_w_x::<C1::ScalarField>(&cs)) // includes 1 and public inputs
+            },
+        )?;
 
         let r1cs_z = [
             vec![C1::ScalarField::one()],
@@ -779,9 +825,10 @@ where
         .concat();
         // compute committed instances, w_{i+1}, u_{i+1}, which will be used as w_i, u_i, so we
         // assign them directly to w_i, u_i.
-        let (u_i, w_i) = self
-            .ccs
-            .to_cccs::<_, C1, CS1, H>(&mut rng, &self.cs_params, &r1cs_z)?;
+        let (u_i, w_i) = timed(info_span!("commitment"), &mut metrics.commitment, || {
+            self.ccs
+                .to_cccs::<_, C1, CS1, H>(&mut rng, &self.cs_params, &r1cs_z)
+        })?;
         self.u_i = u_i.clone();
         self.w_i = w_i.clone();
 
@@ -800,13 +847,18 @@ where
             self.u_i.check_relation(&self.ccs, &self.w_i)?;
         }
 
-        Ok(())
+        metrics.total = start.elapsed();
+        Ok(metrics)
     }
 
     fn state(&self) -> Vec<C1::ScalarField> {
         self.z_i.clone()
     }
 
+    fn num_constraints(&self) -> (usize, usize) {
+        (self.ccs.m, self.cf_r1cs.A.n_rows)
+    }
+
     fn instances(
         &self,
     ) -> (
@@ -873,6 +925,32 @@ where
 
         Ok(())
     }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        IVCProof {
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            i: self.i,
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn verify_proof(vp: Self::VerifierParam, proof: &Self::IVCProof) -> Result<(), Error> {
+        Self::verify(
+            vp,
+            proof.z_0.clone(),
+            proof.z_i.clone(),
+            proof.i,
+            (proof.U_i.clone(), proof.W_i.clone()),
+            (proof.u_i.clone(), proof.w_i.clone()),
+            (proof.cf_U_i.clone(), proof.cf_W_i.clone()),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -987,6 +1065,19 @@ mod tests {
         }
         assert_eq!(Fr::from(num_steps as u32), hypernova.i);
 
+        // check that the IVCProof verifies after a serialization roundtrip
+        let ivc_proof = hypernova.ivc_proof();
+        let mut ivc_proof_bytes = vec![];
+        ivc_proof
+            .serialize_compressed(&mut ivc_proof_bytes)
+            .unwrap();
+        let ivc_proof =
+            IVCProof::<Projective, Projective2>::deserialize_compressed(&ivc_proof_bytes[..])
+                .unwrap();
+        type HN<CS1, CS2, const H: bool> =
+            HyperNova<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, H>;
+        HN::<CS1, CS2, H>::verify_proof(hypernova_params.1.clone(), &ivc_proof).unwrap();
+
         let (running_instance, incoming_instance, cyclefold_instance) = hypernova.instances();
         HyperNova::<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, H>::verify(
             hypernova_params.1, // verifier_params
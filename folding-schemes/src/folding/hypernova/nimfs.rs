@@ -21,6 +21,7 @@ use crate::Error;
 
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use tracing::info_span;
 
 /// NIMFSProof defines a multifolding proof
 #[derive(Clone, Debug)]
@@ -260,7 +261,8 @@ where
         let g = compute_g(ccs, running_instances, &z_lcccs, &z_cccs, gamma, &beta)?;
 
         // Step 3: Run the sumcheck prover
-        let sumcheck_proof = IOPSumCheck::<C::ScalarField, T>::prove(&g, transcript)
+        let sumcheck_proof = info_span!("sum_check")
+            .in_scope(|| IOPSumCheck::<C::ScalarField, T>::prove(&g, transcript))
             .map_err(|err| Error::SumCheckProveError(err.to_string()))?;
 
         // Step 2: dig into the sumcheck and extract r_x_prime
@@ -0,0 +1,520 @@
+use ark_crypto_primitives::sponge::{poseidon::PoseidonConfig, Absorb};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    groups::{CurveVar, GroupOpsBounds},
+    ToConstraintFieldGadget,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate, Write,
+};
+use std::marker::PhantomData;
+
+use super::{
+    circuits::AugmentedFCircuit, HyperNova, ProverParams, VerifierParams, Witness, CCCS, LCCCS,
+};
+use crate::arith::{ccs::CCS, r1cs::R1CS};
+use crate::folding::{
+    checkpoint::Checkpointable,
+    circuits::{cyclefold::CycleFoldCircuit, CF2},
+    nova::{get_r1cs_from_cs, CommittedInstance, Witness as NovaWitness},
+    params::{check_cs_params, ParamsHeader},
+};
+use crate::{commitment::CommitmentScheme, frontend::FCircuit, utils::pp_hash, Error};
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> CanonicalSerialize
+    for HyperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), SerializationError> {
+        self.pp_hash.serialize_with_mode(&mut writer, compress)?;
+        self.i.serialize_with_mode(&mut writer, compress)?;
+        self.z_0.serialize_with_mode(&mut writer, compress)?;
+        self.z_i.serialize_with_mode(&mut writer, compress)?;
+        self.W_i.serialize_with_mode(&mut writer, compress)?;
+        self.U_i.serialize_with_mode(&mut writer, compress)?;
+        self.w_i.serialize_with_mode(&mut writer, compress)?;
+        self.u_i.serialize_with_mode(&mut writer, compress)?;
+        self.cf_W_i.serialize_with_mode(&mut writer, compress)?;
+        self.cf_U_i.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.pp_hash.serialized_size(compress)
+            + self.i.serialized_size(compress)
+            + self.z_0.serialized_size(compress)
+            + self.z_i.serialized_size(compress)
+            + self.W_i.serialized_size(compress)
+            + self.U_i.serialized_size(compress)
+            + self.w_i.serialized_size(compress)
+            + self.u_i.serialized_size(compress)
+            + self.cf_W_i.serialized_size(compress)
+            + self.cf_U_i.serialized_size(compress)
+    }
+}
+
+// Note that we can't derive or implement `CanonicalDeserialize` directly.
+// This is because `CurveVar` notably does not implement the `Sync` trait.
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> HyperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    /// Reconstructs the HyperNova prover state serialized with `CanonicalSerialize`, so that
+    /// proving can be resumed from it. The CCS and the CycleFold R1CS are recomputed from the
+    /// given `F` circuit and prover params, and the `pp_hash` recomputed from them and the
+    /// verifier params must match the one stored with the state.
+    pub fn deserialize_hypernova<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        prover_params: ProverParams<C1, C2, CS1, CS2, H>,
+        verifier_params: &VerifierParams<C1, C2, CS1, CS2, H>,
+        F: FC,
+    ) -> Result<Self, SerializationError> {
+        let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let i = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let z_0 = Vec::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let z_i = Vec::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let W_i =
+            Witness::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let U_i = LCCCS::<C1>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let w_i =
+            Witness::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let u_i = CCCS::<C1>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_W_i = NovaWitness::<C2>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_U_i =
+            CommittedInstance::<C2>::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        let ProverParams {
+            poseidon_config,
+            cs_params,
+            cf_cs_params,
+            ccs,
+            mu,
+            nu,
+        } = prover_params;
+
+        let augmented_f_circuit =
+            AugmentedFCircuit::<C1, C2, GC2, FC>::empty(&poseidon_config, F.clone(), ccs, mu, nu)
+                .map_err(|_| SerializationError::InvalidData)?;
+        let ccs = augmented_f_circuit.ccs;
+
+        let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty(mu + nu);
+        let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)
+            .map_err(|_| SerializationError::InvalidData)?;
+
+        let expected_pp_hash = pp_hash::<C1, C2, CS1, CS2, H>(
+            &ccs,
+            &cf_r1cs,
+            &verifier_params.cs_vp,
+            &verifier_params.cf_cs_vp,
+            &poseidon_config,
+        )
+        .map_err(|_| SerializationError::InvalidData)?;
+        if pp_hash != expected_pp_hash {
+            return Err(SerializationError::InvalidData);
+        }
+
+        Ok(HyperNova {
+            _gc1: PhantomData,
+            _c2: PhantomData,
+            _gc2: PhantomData,
+            ccs,
+            cf_r1cs,
+            poseidon_config,
+            cs_params,
+            cf_cs_params,
+            F,
+            pp_hash,
+            mu,
+            nu,
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    CS1::ProverParams: CanonicalSerialize,
+    CS2::ProverParams: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), SerializationError> {
+        self.cs_params.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_params
+            .serialize_with_mode(&mut writer, compress)?;
+        self.ccs.serialize_with_mode(&mut writer, compress)?;
+        self.mu.serialize_with_mode(&mut writer, compress)?;
+        self.nu.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.cs_params.serialized_size(compress)
+            + self.cf_cs_params.serialized_size(compress)
+            + self.ccs.serialized_size(compress)
+            + self.mu.serialized_size(compress)
+            + self.nu.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    CS1::ProverParams: CanonicalSerialize + CanonicalDeserialize,
+    CS2::ProverParams: CanonicalSerialize + CanonicalDeserialize,
+{
+    /// Deserializes the prover params. The `PoseidonConfig` is not serialized, so it has to be
+    /// provided.
+    pub fn deserialize_prover_params<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    ) -> Result<Self, SerializationError> {
+        let cs_params = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_params =
+            CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let ccs =
+            Option::<CCS<C1::ScalarField>>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let nu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(ProverParams {
+            poseidon_config: poseidon_config.clone(),
+            cs_params,
+            cf_cs_params,
+            ccs,
+            mu,
+            nu,
+        })
+    }
+
+    /// Exports the prover params, prefixed by a [`ParamsHeader`] binding them to the given
+    /// `pp_hash` (obtained from `VerifierParams::pp_hash`) and FCircuit.
+    pub fn export<W: Write, FC: FCircuit<C1::ScalarField>>(
+        &self,
+        mut writer: W,
+        pp_hash: C1::ScalarField,
+        F_circuit: &FC,
+    ) -> Result<(), Error> {
+        ParamsHeader::new(pp_hash, F_circuit)?.write(&mut writer)?;
+        Ok(self.serialize_uncompressed(writer)?)
+    }
+
+    /// Imports the prover params exported with [`ProverParams::export`], checking that they were
+    /// generated for the given FCircuit, that they are bound to the `pp_hash` of the given
+    /// verifier params, and that their commitment schemes' params match the verifier ones.
+    pub fn import<R: std::io::prelude::Read, FC: FCircuit<C1::ScalarField>>(
+        mut reader: R,
+        F_circuit: &FC,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+    ) -> Result<Self, Error>
+    where
+        <C1 as Group>::ScalarField: Absorb,
+        <C2 as Group>::ScalarField: Absorb,
+    {
+        let header = ParamsHeader::read(&mut reader, F_circuit)?;
+        header.check_pp_hash(vp.pp_hash()?)?;
+        let pp = Self::deserialize_prover_params(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            &vp.poseidon_config,
+        )?;
+        check_cs_params::<C1, CS1, H>(&pp.cs_params, &vp.cs_vp, vp.ccs.n - vp.ccs.l - 1)?;
+        check_cs_params::<C2, CS2, H>(
+            &pp.cf_cs_params,
+            &vp.cf_cs_vp,
+            vp.cf_r1cs.A.n_cols - vp.cf_r1cs.l - 1,
+        )?;
+        Ok(pp)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), SerializationError> {
+        self.ccs.serialize_with_mode(&mut writer, compress)?;
+        self.cf_r1cs.serialize_with_mode(&mut writer, compress)?;
+        self.cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.ccs.serialized_size(compress)
+            + self.cf_r1cs.serialized_size(compress)
+            + self.cs_vp.serialized_size(compress)
+            + self.cf_cs_vp.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Deserializes the verifier params. The `PoseidonConfig` is not serialized, so it has to be
+    /// provided.
+    pub fn deserialize_verifier_params<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    ) -> Result<Self, SerializationError> {
+        let ccs = CCS::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_r1cs =
+            R1CS::<C2::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(VerifierParams {
+            poseidon_config: poseidon_config.clone(),
+            ccs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    /// Exports the verifier params, prefixed by a [`ParamsHeader`] binding them to their
+    /// `pp_hash` and to the given FCircuit.
+    pub fn export<W: Write, FC: FCircuit<C1::ScalarField>>(
+        &self,
+        mut writer: W,
+        F_circuit: &FC,
+    ) -> Result<(), Error> {
+        ParamsHeader::new(self.pp_hash()?, F_circuit)?.write(&mut writer)?;
+        Ok(self.serialize_uncompressed(writer)?)
+    }
+
+    /// Imports the verifier params exported with [`VerifierParams::export`], checking that they
+    /// were generated for the given FCircuit and that their `pp_hash` matches the exported one.
+    pub fn import<R: std::io::prelude::Read, FC: FCircuit<C1::ScalarField>>(
+        mut reader: R,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        F_circuit: &FC,
+    ) -> Result<Self, Error> {
+        let header = ParamsHeader::read(&mut reader, F_circuit)?;
+        let vp = Self::deserialize_verifier_params(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            poseidon_config,
+        )?;
+        header.check_pp_hash(vp.pp_hash()?)?;
+        Ok(vp)
+    }
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Checkpointable<C1, C2, FC>
+    for HyperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
+
+    fn num_steps(&self) -> C1::ScalarField {
+        self.i
+    }
+
+    fn deserialize_state<R: std::io::prelude::Read>(
+        reader: R,
+        params: &(Self::ProverParam, Self::VerifierParam),
+        step_circuit: FC,
+    ) -> Result<Self, Error> {
+        // deserialize_hypernova already checks the stored pp_hash against the given params
+        Ok(Self::deserialize_hypernova(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            params.0.clone(),
+            &params.1,
+            step_circuit,
+        )?)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{constraints::GVar, Fr, G1Projective as Projective};
+    use ark_grumpkin::{constraints::GVar as GVar2, Projective as Projective2};
+    use ark_serialize::{CanonicalSerialize, Compress, Validate};
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_serde_hypernova() {
+        type HN = HyperNova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F_circuit);
+        let (pp, vp) = HN::preprocess(&mut rng, &(prep_param, 1, 1)).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut hypernova = HN::init(&(pp.clone(), vp.clone()), F_circuit, z_0).unwrap();
+        for _ in 0..3 {
+            hypernova
+                .prove_step(&mut rng, vec![], Some((vec![], vec![])))
+                .unwrap();
+        }
+
+        // serialize the prover state and the params
+        let mut hypernova_bytes = vec![];
+        hypernova
+            .serialize_with_mode(&mut hypernova_bytes, Compress::No)
+            .unwrap();
+        let mut pp_bytes = vec![];
+        pp.serialize_with_mode(&mut pp_bytes, Compress::No).unwrap();
+        let mut vp_bytes = vec![];
+        vp.serialize_with_mode(&mut vp_bytes, Compress::No).unwrap();
+
+        // reload them
+        let pp_deserialized = ProverParams::deserialize_prover_params(
+            pp_bytes.as_slice(),
+            Compress::No,
+            Validate::No,
+            &poseidon_config,
+        )
+        .unwrap();
+        let vp_deserialized = VerifierParams::deserialize_verifier_params(
+            vp_bytes.as_slice(),
+            Compress::No,
+            Validate::No,
+            &poseidon_config,
+        )
+        .unwrap();
+        assert_eq!(vp.pp_hash().unwrap(), vp_deserialized.pp_hash().unwrap());
+
+        let mut hypernova_deserialized = HN::deserialize_hypernova(
+            hypernova_bytes.as_slice(),
+            Compress::No,
+            Validate::No,
+            pp_deserialized.clone(),
+            &vp_deserialized,
+            F_circuit,
+        )
+        .unwrap();
+        assert_eq!(hypernova.i, hypernova_deserialized.i);
+        assert_eq!(hypernova.ccs, hypernova_deserialized.ccs);
+        assert_eq!(hypernova.U_i, hypernova_deserialized.U_i);
+
+        // a state whose pp_hash does not match the params is rejected
+        let mut tampered_bytes = vec![];
+        let mut tampered = hypernova.clone();
+        tampered.pp_hash += Fr::from(1_u32);
+        tampered
+            .serialize_with_mode(&mut tampered_bytes, Compress::No)
+            .unwrap();
+        assert!(HN::deserialize_hypernova(
+            tampered_bytes.as_slice(),
+            Compress::No,
+            Validate::No,
+            pp_deserialized,
+            &vp_deserialized,
+            F_circuit,
+        )
+        .is_err());
+
+        // resume proving from the reloaded state
+        for _ in 0..2 {
+            hypernova_deserialized
+                .prove_step(&mut rng, vec![], Some((vec![], vec![])))
+                .unwrap();
+        }
+        let (running_instance, incoming_instance, cyclefold_instance) =
+            hypernova_deserialized.instances();
+        HN::verify(
+            vp_deserialized,
+            hypernova_deserialized.z_0.clone(),
+            hypernova_deserialized.state(),
+            hypernova_deserialized.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
+}
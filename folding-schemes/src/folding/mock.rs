@@ -0,0 +1,364 @@
+/// This file implements [`MockFolding`], a [`FoldingScheme`] that does not fold anything, and
+/// which is meant to be used while developing and testing new `FCircuit`s.
+///
+/// At each step it runs both `step_native` and `generate_step_constraints` of the `FCircuit`,
+/// checking that the generated constraints are satisfied and that the native and in-circuit next
+/// states match, without computing any commitment nor CycleFold instance. This allows to iterate
+/// on an `FCircuit` without running the (expensive) `preprocess` of the actual folding schemes.
+///
+/// Notice that it does not provide any soundness nor succinctness: the 'instances' are just the
+/// external inputs of each step, which the verifier uses to recompute the state natively.
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::fmt::Debug;
+use ark_std::rand::RngCore;
+use ark_std::Zero;
+use core::marker::PhantomData;
+use std::time::Instant;
+use tracing::info_span;
+
+use crate::arith::r1cs::check_cs_relation;
+use crate::frontend::FCircuit;
+use crate::utils::timed;
+use crate::Error;
+use crate::{FoldingScheme, StepMetrics};
+
+/// IVCProof of the [`MockFolding`], contains the initial & current states and the external inputs
+/// used at each step, so that the verifier can recompute the current state.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<F: PrimeField> {
+    /// initial state
+    pub z_0: Vec<F>,
+    /// current i-th state
+    pub z_i: Vec<F>,
+    /// number of steps
+    pub i: F,
+    /// external inputs used at each of the i steps
+    pub external_inputs: Vec<Vec<F>>,
+}
+
+/// Mock folding scheme, which only checks the `FCircuit` at each step. See the module
+/// documentation for more details.
+#[derive(Clone, Debug)]
+pub struct MockFolding<C1, C2, FC>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    FC: FCircuit<C1::ScalarField>,
+{
+    _c2: PhantomData<C2>,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// number of constraints of the F circuit
+    pub num_constraints: usize,
+    /// number of steps
+    pub i: usize,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// external inputs used at each of the i steps
+    pub external_inputs: Vec<Vec<C1::ScalarField>>,
+}
+
+impl<C1, C2, FC> FoldingScheme<C1, C2, FC> for MockFolding<C1, C2, FC>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    FC: FCircuit<C1::ScalarField>,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    type PreprocessorParam = FC;
+    type ProverParam = ();
+    type VerifierParam = FC;
+    type RunningInstance = Vec<Vec<C1::ScalarField>>;
+    type IncomingInstance = ();
+    type MultiCommittedInstanceWithWitness = ();
+    type CFInstance = ();
+    type IVCProof = IVCProof<C1::ScalarField>;
+
+    fn preprocess(
+        _rng: impl RngCore,
+        prep_param: &Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        Ok(((), prep_param.clone()))
+    }
+
+    fn init(
+        _params: &(Self::ProverParam, Self::VerifierParam),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        if z_0.len() != F.state_len() {
+            return Err(Error::NotSameLength(
+                "F.state_len()".to_string(),
+                F.state_len(),
+                "z_0.len()".to_string(),
+                z_0.len(),
+            ));
+        }
+
+        // generate the constraints of the F circuit with dummy values to obtain their number
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        let z_i = Vec::<FpVar<C1::ScalarField>>::new_witness(cs.clone(), || {
+            Ok(vec![C1::ScalarField::zero(); F.state_len()])
+        })?;
+        let external_inputs = Vec::<FpVar<C1::ScalarField>>::new_witness(cs.clone(), || {
+            Ok(vec![C1::ScalarField::zero(); F.external_inputs_len()])
+        })?;
+        F.generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+        cs.finalize();
+
+        Ok(Self {
+            _c2: PhantomData,
+            num_constraints: cs.num_constraints(),
+            F,
+            i: 0,
+            z_0: z_0.clone(),
+            z_i: z_0,
+            external_inputs: vec![],
+        })
+    }
+
+    #[tracing::instrument(skip_all, name = "mock_prove_step")]
+    fn prove_step(
+        &mut self,
+        _rng: impl RngCore,
+        external_inputs: Vec<C1::ScalarField>,
+        other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<StepMetrics, Error> {
+        let start = Instant::now();
+        let mut metrics = StepMetrics::default();
+
+        if other_instances.is_some() {
+            return Err(Error::NoMultiInstances);
+        }
+        if external_inputs.len() != self.F.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "F.external_inputs_len()".to_string(),
+                self.F.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+        if self.i == usize::MAX {
+            return Err(Error::MaxStep);
+        }
+
+        let z_i1 = timed(
+            info_span!("witness_generation"),
+            &mut metrics.witness_generation,
+            || -> Result<Vec<C1::ScalarField>, Error> {
+                let z_i1 = self
+                    .F
+                    .step_native(self.i, self.z_i.clone(), external_inputs.clone())?;
+
+                let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+                let z_i_var = Vec::<FpVar<C1::ScalarField>>::new_witness(cs.clone(), || {
+                    Ok(self.z_i.clone())
+                })?;
+                let external_inputs_var =
+                    Vec::<FpVar<C1::ScalarField>>::new_witness(cs.clone(), || {
+                        Ok(external_inputs.clone())
+                    })?;
+                let z_i1_var = self.F.generate_step_constraints(
+                    cs.clone(),
+                    self.i,
+                    z_i_var,
+                    external_inputs_var,
+                )?;
+                check_cs_relation(cs)?;
+
+                // check that the in-circuit next state matches the native one
+                if z_i1_var.len() != z_i1.len() {
+                    return Err(Error::NotSameLength(
+                        "z_{i+1} (native)".to_string(),
+                        z_i1.len(),
+                        "z_{i+1} (in-circuit)".to_string(),
+                        z_i1_var.len(),
+                    ));
+                }
+                let z_i1_in_circuit = z_i1_var
+                    .iter()
+                    .map(|z| z.value())
+                    .collect::<Result<Vec<_>, _>>()?;
+                if z_i1_in_circuit != z_i1 {
+                    return Err(Error::NotEqual);
+                }
+                Ok(z_i1)
+            },
+        )?;
+
+        self.z_i = z_i1;
+        self.external_inputs.push(external_inputs);
+        self.i += 1;
+
+        metrics.total = start.elapsed();
+        Ok(metrics)
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn num_constraints(&self) -> (usize, usize) {
+        (self.num_constraints, 0)
+    }
+
+    fn instances(
+        &self,
+    ) -> (
+        Self::RunningInstance,
+        Self::IncomingInstance,
+        Self::CFInstance,
+    ) {
+        (self.external_inputs.clone(), (), ())
+    }
+
+    /// Recomputes natively the state from `z_0` using the external inputs contained in the
+    /// running instance, checking that it matches `z_i`.
+    fn verify(
+        vp: Self::VerifierParam,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        num_steps: C1::ScalarField,
+        running_instance: Self::RunningInstance,
+        _incoming_instance: Self::IncomingInstance,
+        _cyclefold_instance: Self::CFInstance,
+    ) -> Result<(), Error> {
+        if num_steps != C1::ScalarField::from(running_instance.len() as u64) {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let mut state = z_0;
+        for (i, external_inputs) in running_instance.into_iter().enumerate() {
+            state = vp.step_native(i, state, external_inputs)?;
+        }
+        if state != z_i {
+            return Err(Error::IVCVerificationFail);
+        }
+        Ok(())
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        IVCProof {
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            i: C1::ScalarField::from(self.i as u64),
+            external_inputs: self.external_inputs.clone(),
+        }
+    }
+
+    fn verify_proof(vp: Self::VerifierParam, proof: &Self::IVCProof) -> Result<(), Error> {
+        Self::verify(
+            vp,
+            proof.z_0.clone(),
+            proof.z_i.clone(),
+            proof.i,
+            proof.external_inputs.clone(),
+            (),
+            (),
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::fields::FieldVar;
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_std::{test_rng, One};
+
+    use crate::frontend::tests::CubicFCircuit;
+
+    type MF<FC> = MockFolding<Projective, Projective2, FC>;
+
+    /// FCircuit whose native step does not match its constraints, used to check that the mismatch
+    /// is detected by the MockFolding.
+    #[derive(Clone, Copy, Debug)]
+    pub struct WrongNativeFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for WrongNativeFCircuit<F> {
+        type Params = ();
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn external_inputs_len(&self) -> usize {
+            0
+        }
+        fn step_native(
+            &self,
+            _i: usize,
+            z_i: Vec<F>,
+            _external_inputs: Vec<F>,
+        ) -> Result<Vec<F>, Error> {
+            Ok(vec![z_i[0] + F::from(2_u32)])
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Vec<FpVar<F>>,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(vec![&z_i[0] + FpVar::<F>::one()])
+        }
+    }
+
+    #[test]
+    fn test_mock_folding() {
+        let mut rng = test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let params = MF::<CubicFCircuit<Fr>>::preprocess(&mut rng, &F_circuit).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut mock = MF::<CubicFCircuit<Fr>>::init(&params, F_circuit, z_0.clone()).unwrap();
+        // x^3 takes two multiplication constraints, the additions are linear combinations
+        assert_eq!(mock.num_constraints(), (2, 0));
+
+        let num_steps: usize = 3;
+        for _ in 0..num_steps {
+            mock.prove_step(&mut rng, vec![], None).unwrap();
+        }
+
+        let mut expected = z_0;
+        for i in 0..num_steps {
+            expected = F_circuit.step_native(i, expected, vec![]).unwrap();
+        }
+        assert_eq!(mock.state(), expected);
+
+        let proof = mock.ivc_proof();
+        MF::<CubicFCircuit<Fr>>::verify_proof(params.1.clone(), &proof).unwrap();
+
+        // a proof with a wrong state should not verify
+        let mut wrong_proof = proof.clone();
+        wrong_proof.z_i = vec![Fr::one()];
+        assert!(MF::<CubicFCircuit<Fr>>::verify_proof(params.1, &wrong_proof).is_err());
+    }
+
+    #[test]
+    fn test_mock_folding_native_mismatch() {
+        let mut rng = test_rng();
+        let F_circuit = WrongNativeFCircuit::<Fr>::new(()).unwrap();
+        let params = MF::<WrongNativeFCircuit<Fr>>::preprocess(&mut rng, &F_circuit).unwrap();
+
+        let mut mock =
+            MF::<WrongNativeFCircuit<Fr>>::init(&params, F_circuit, vec![Fr::one()]).unwrap();
+        assert!(matches!(
+            mock.prove_step(&mut rng, vec![], None),
+            Err(Error::NotEqual)
+        ));
+        // the state is not updated when the step fails
+        assert_eq!(mock.state(), vec![Fr::one()]);
+    }
+}
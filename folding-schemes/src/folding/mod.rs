@@ -1,4 +1,8 @@
+pub mod checkpoint;
 pub mod circuits;
 pub mod hypernova;
+pub mod mock;
 pub mod nova;
+pub mod params;
 pub mod protogalaxy;
+pub mod supernova;
@@ -0,0 +1,437 @@
+/// This file implements native batch folding on top of Nova+CycleFold: the IVC states computed
+/// by independent provers are turned into leaves, and any two batch proofs are merged by folding
+/// their running Nova instances together (and their running CycleFold instances together), so
+/// that a long computation can be split across machines and combined in a binary tree.
+///
+/// Scope: this is not proof-carrying data, and tree-shaped PCD is not implemented. PCD would need
+/// a merge step proven in-circuit: an augmented circuit that verifies the folding of the two
+/// running instances, with CycleFold for the commitment operations. Since the instances folded at
+/// a merge would come both from leaves and from other merges, leaves and merges would also need
+/// to be proven with the same circuit, which the Nova augmented circuit does not support.
+/// Instead, the merge step is done natively. So while the witness of a batch proof stays constant
+/// size, its statement keeps the instances of every leaf and the cross-terms commitments of every
+/// merge, thus both the proof size and the verifier work grow linearly with the number of leaves
+/// (with a single Relaxed R1CS check for each curve).
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb, CryptographicSponge};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_std::{One, Zero};
+
+use super::{
+    nifs::NIFS,
+    traits::NovaR1CS,
+    zk::{cf_pp_hash, challenge},
+    CommittedInstance, Nova, ProverParams, VerifierParams, Witness,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::CF2;
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::Error;
+
+/// BatchLeaf is the statement of a single IVC run, whose running instance `U_i` and incoming
+/// instance `u_i` are folded into the leaf's running instance.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BatchLeaf<C1: CurveGroup, C2: CurveGroup> {
+    pub z_0: Vec<C1::ScalarField>,
+    pub z_i: Vec<C1::ScalarField>,
+    pub num_steps: C1::ScalarField,
+    pub U_i: CommittedInstance<C1>,
+    pub u_i: CommittedInstance<C1>,
+    /// commitment to the cross-terms of folding U_i with u_i
+    pub cmT: C1,
+    pub cf_U_i: CommittedInstance<C2>,
+}
+
+/// BatchTree is the statement of a batch proof, in which each node merges the running instances
+/// of its two children.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BatchTree<C1: CurveGroup, C2: CurveGroup> {
+    Leaf(BatchLeaf<C1, C2>),
+    Merge {
+        left: Box<BatchTree<C1, C2>>,
+        right: Box<BatchTree<C1, C2>>,
+        /// commitment to the cross-terms of folding the two Nova running instances
+        cmT: C1,
+        /// commitment to the cross-terms of folding the two CycleFold running instances
+        cf_cmT: C2,
+    },
+}
+
+impl<C1: CurveGroup, C2: CurveGroup> BatchTree<C1, C2> {
+    /// returns the leaves of the tree, from left to right
+    pub fn leaves(&self) -> Vec<&BatchLeaf<C1, C2>> {
+        match self {
+            BatchTree::Leaf(leaf) => vec![leaf],
+            BatchTree::Merge { left, right, .. } => [left.leaves(), right.leaves()].concat(),
+        }
+    }
+}
+
+/// BatchProof contains the tree of statements together with the running instances resulting from
+/// folding all of them, and their witnesses.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BatchProof<C1: CurveGroup, C2: CurveGroup> {
+    pub tree: BatchTree<C1, C2>,
+    pub U: CommittedInstance<C1>,
+    pub W: Witness<C1>,
+    pub cf_U: CommittedInstance<C2>,
+    pub cf_W: Witness<C2>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    /// Returns the batch leaf of the current IVC state, which can then be merged with the leaves of
+    /// other provers through [`BatchProof::merge`].
+    pub fn batch_leaf(&self) -> Result<BatchProof<C1, C2>, Error> {
+        if self.i.is_zero() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        // fold the running instance with the incoming one
+        let (T, cmT) = self.compute_cmT()?;
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let r = challenge::<C1, _>(&mut transcript, self.pp_hash, &self.U_i, &self.u_i, cmT)?;
+        let (W, U) = NIFS::<C1, CS1, H>::fold_instances(
+            r, &self.W_i, &self.U_i, &self.w_i, &self.u_i, &T, cmT,
+        )?;
+
+        Ok(BatchProof {
+            tree: BatchTree::Leaf(BatchLeaf {
+                z_0: self.z_0.clone(),
+                z_i: self.z_i.clone(),
+                num_steps: self.i,
+                U_i: self.U_i.clone(),
+                u_i: self.u_i.clone(),
+                cmT,
+                cf_U_i: self.cf_U_i.clone(),
+            }),
+            U,
+            W,
+            cf_U: self.cf_U_i.clone(),
+            cf_W: self.cf_W_i.clone(),
+        })
+    }
+}
+
+impl<C1, C2> BatchProof<C1, C2>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// Merges two batch proofs computed with the same parameters, by folding their running Nova
+    /// instances and their running CycleFold instances. The statement of `left` is placed before
+    /// the one of `right`.
+    pub fn merge<CS1, CS2, const H: bool>(
+        pp: &ProverParams<C1, C2, CS1, CS2, H>,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+        left: Self,
+        right: Self,
+    ) -> Result<Self, Error>
+    where
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+    {
+        let pp_hash = vp.pp_hash()?;
+
+        // fold the Nova running instances
+        let (T, cmT) = NIFS::<C1, CS1, H>::compute_cmT(
+            &pp.cs_pp, &vp.r1cs, &left.W, &left.U, &right.W, &right.U,
+        )?;
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let r = challenge::<C1, _>(&mut transcript, pp_hash, &left.U, &right.U, cmT)?;
+        let (W, U) =
+            NIFS::<C1, CS1, H>::fold_instances(r, &left.W, &left.U, &right.W, &right.U, &T, cmT)?;
+
+        // fold the CycleFold running instances
+        let (cf_T, cf_cmT) = NIFS::<C2, CS2, H>::compute_cyclefold_cmT(
+            &pp.cf_cs_pp,
+            &vp.cf_r1cs,
+            &left.cf_W,
+            &left.cf_U,
+            &right.cf_W,
+            &right.cf_U,
+        )?;
+        let cf_r = cf_challenge::<C1, C2>(pp_hash, &left.cf_U, &right.cf_U, cf_cmT)?;
+        let (cf_W, cf_U) = NIFS::<C2, CS2, H>::fold_instances(
+            cf_r,
+            &left.cf_W,
+            &left.cf_U,
+            &right.cf_W,
+            &right.cf_U,
+            &cf_T,
+            cf_cmT,
+        )?;
+
+        Ok(Self {
+            tree: BatchTree::Merge {
+                left: Box::new(left.tree),
+                right: Box::new(right.tree),
+                cmT,
+                cf_cmT,
+            },
+            U,
+            W,
+            cf_U,
+            cf_W,
+        })
+    }
+
+    /// Verifies the batch proof: each leaf is checked to be a valid IVC output, and the running
+    /// instances obtained by folding the whole tree are checked to be satisfied. The returned
+    /// leaves contain the `(z_0, z_i, num_steps)` of each IVC run, which the caller is expected to
+    /// check against the computation being proven. Analogously to `FoldingScheme::verify`, this
+    /// method does not check the commitments openings.
+    pub fn verify<CS1, CS2, const H: bool>(
+        &self,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+    ) -> Result<Vec<&BatchLeaf<C1, C2>>, Error>
+    where
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+    {
+        let pp_hash = vp.pp_hash()?;
+        let (U, cf_U) = fold_tree::<C1, C2, CS1, CS2, H>(vp, pp_hash, &self.tree)?;
+        if U != self.U || cf_U != self.cf_U {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check RelaxedR1CS satisfiability of the folded instances
+        vp.r1cs.check_relaxed_instance_relation(&self.W, &U)?;
+        vp.cf_r1cs
+            .check_relaxed_instance_relation(&self.cf_W, &cf_U)?;
+
+        Ok(self.tree.leaves())
+    }
+
+    /// Verifies the batch proof of a computation that has been split in consecutive chunks, one per
+    /// leaf: additionally to [`BatchProof::verify`], it checks that the leaves are chained (the
+    /// initial state of each leaf is the last state of the previous one), starting at `z_0` and
+    /// ending at `z_n`. Returns the total number of steps.
+    pub fn verify_chain<CS1, CS2, const H: bool>(
+        &self,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+        z_0: &[C1::ScalarField],
+        z_n: &[C1::ScalarField],
+    ) -> Result<C1::ScalarField, Error>
+    where
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+    {
+        let leaves = self.verify(vp)?;
+
+        let mut z = z_0;
+        let mut num_steps = C1::ScalarField::zero();
+        for leaf in leaves {
+            if leaf.z_0 != z {
+                return Err(Error::IVCVerificationFail);
+            }
+            z = &leaf.z_i;
+            num_steps += leaf.num_steps;
+        }
+        if z != z_n {
+            return Err(Error::IVCVerificationFail);
+        }
+        Ok(num_steps)
+    }
+}
+
+/// recomputes the running instances (Nova and CycleFold) resulting from folding the given tree,
+/// checking the leaves' IVC outputs
+fn fold_tree<C1, C2, CS1, CS2, const H: bool>(
+    vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+    pp_hash: C1::ScalarField,
+    tree: &BatchTree<C1, C2>,
+) -> Result<(CommittedInstance<C1>, CommittedInstance<C2>), Error>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    match tree {
+        BatchTree::Leaf(leaf) => {
+            if leaf.num_steps.is_zero() {
+                return Err(Error::NotEnoughSteps);
+            }
+            if leaf.u_i.x.len() != 2 || leaf.U_i.x.len() != 2 {
+                return Err(Error::IVCVerificationFail);
+            }
+            let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+            // u_i.X[0] == H(i, z_0, z_i, U_i)
+            let expected_u_i_x = leaf.U_i.hash(
+                &sponge,
+                pp_hash,
+                leaf.num_steps,
+                leaf.z_0.clone(),
+                leaf.z_i.clone(),
+            );
+            if expected_u_i_x != leaf.u_i.x[0] {
+                return Err(Error::IVCVerificationFail);
+            }
+            // u_i.X[1] == H(cf_U_i)
+            let expected_cf_u_i_x = leaf.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+            if expected_cf_u_i_x != leaf.u_i.x[1] {
+                return Err(Error::IVCVerificationFail);
+            }
+            // check u_i.cmE==0, u_i.u==1 (=u_i is a un-relaxed instance)
+            if !leaf.u_i.cmE.is_zero() || !leaf.u_i.u.is_one() {
+                return Err(Error::IVCVerificationFail);
+            }
+
+            let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+            let r = challenge::<C1, _>(&mut transcript, pp_hash, &leaf.U_i, &leaf.u_i, leaf.cmT)?;
+            let U = NIFS::<C1, CS1, H>::verify(r, &leaf.U_i, &leaf.u_i, &leaf.cmT);
+            Ok((U, leaf.cf_U_i.clone()))
+        }
+        BatchTree::Merge {
+            left,
+            right,
+            cmT,
+            cf_cmT,
+        } => {
+            let (U_l, cf_U_l) = fold_tree::<C1, C2, CS1, CS2, H>(vp, pp_hash, left)?;
+            let (U_r, cf_U_r) = fold_tree::<C1, C2, CS1, CS2, H>(vp, pp_hash, right)?;
+
+            let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+            let r = challenge::<C1, _>(&mut transcript, pp_hash, &U_l, &U_r, *cmT)?;
+            let U = NIFS::<C1, CS1, H>::verify(r, &U_l, &U_r, cmT);
+
+            let cf_r = cf_challenge::<C1, C2>(pp_hash, &cf_U_l, &cf_U_r, *cf_cmT)?;
+            let cf_U = NIFS::<C2, CS2, H>::verify(cf_r, &cf_U_l, &cf_U_r, cf_cmT);
+            Ok((U, cf_U))
+        }
+    }
+}
+
+/// computes the challenge used to fold two CycleFold running instances natively
+fn cf_challenge<C1, C2>(
+    pp_hash: C1::ScalarField,
+    cf_U_1: &CommittedInstance<C2>,
+    cf_U_2: &CommittedInstance<C2>,
+    cf_cmT: C2,
+) -> Result<C2::ScalarField, Error>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C2 as Group>::ScalarField: Absorb,
+{
+    let mut cf_transcript =
+        PoseidonSponge::<C2::ScalarField>::new(&poseidon_canonical_config::<C2::ScalarField>());
+    challenge::<C2, _>(
+        &mut cf_transcript,
+        cf_pp_hash::<C1, C2>(pp_hash),
+        cf_U_1,
+        cf_U_2,
+        cf_cmT,
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_batch_merge() {
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        // split the computation in three chunks, each one proven by a different prover, where each
+        // prover starts from the last state of the previous chunk
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut z = z_0.clone();
+        let mut leaves = vec![];
+        for n_steps in [2, 3, 1] {
+            let mut nova = N::init(&(pp.clone(), vp.clone()), F_circuit, z.clone()).unwrap();
+            for _ in 0..n_steps {
+                nova.prove_step(&mut rng, vec![], None).unwrap();
+            }
+            let leaf = nova.batch_leaf().unwrap();
+            leaf.verify(&vp).unwrap();
+            z = nova.z_i.clone();
+            leaves.push(leaf);
+        }
+        let z_n = z;
+
+        // merge them in a tree: ((leaf_0, leaf_1), leaf_2)
+        let node = BatchProof::merge(&pp, &vp, leaves[0].clone(), leaves[1].clone()).unwrap();
+        let root = BatchProof::merge(&pp, &vp, node, leaves[2].clone()).unwrap();
+        assert_eq!(root.tree.leaves().len(), 3);
+        let num_steps = root.verify_chain(&vp, &z_0, &z_n).unwrap();
+        assert_eq!(num_steps, Fr::from(6_u32));
+
+        // wrong final state
+        assert!(root.verify_chain(&vp, &z_0, &z_0).is_err());
+
+        // the leaves in the wrong order do not chain
+        let node = BatchProof::merge(&pp, &vp, leaves[1].clone(), leaves[0].clone()).unwrap();
+        let wrong_root = BatchProof::merge(&pp, &vp, node, leaves[2].clone()).unwrap();
+        wrong_root.verify(&vp).unwrap();
+        assert!(wrong_root.verify_chain(&vp, &z_0, &z_n).is_err());
+
+        // tampered leaf statement
+        let mut wrong_root = root.clone();
+        if let BatchTree::Merge { right, .. } = &mut wrong_root.tree {
+            if let BatchTree::Leaf(leaf) = right.as_mut() {
+                leaf.z_i = z_0.clone();
+            }
+        }
+        assert!(wrong_root.verify(&vp).is_err());
+
+        // tampered folded witness
+        let mut wrong_root = root;
+        wrong_root.W.W[0] += Fr::one();
+        assert!(wrong_root.verify(&vp).is_err());
+    }
+}
@@ -243,6 +243,20 @@ pub struct AugmentedFCircuit<
     pub F: FC,              // F circuit
     pub x: Option<CF1<C1>>, // public input (u_{i+1}.x[0])
 
+    // multi-instances folding: the extra instances are folded sequentially into the folding of
+    // U_i and u_i, first the running ones (Us) and then the incoming ones (us)
+    pub mu: usize, // number of running instances to be folded, including U_i
+    pub nu: usize, // number of incoming instances to be folded, including u_i
+    pub Us: Option<Vec<CommittedInstance<C1>>>,
+    pub us: Option<Vec<CommittedInstance<C1>>>,
+    // cross-terms commitments of folding each of the extra instances
+    pub cmTs: Option<Vec<C1>>,
+    // cmT + r * Us[k].cmE, for each of the extra running instances
+    pub Us_cmTE: Option<Vec<C1>>,
+    // cmE & cmW of the intermediate folded instances, into which the extra instances are folded
+    pub U_acc_cmE: Option<Vec<C1>>,
+    pub U_acc_cmW: Option<Vec<C1>>,
+
     // cyclefold verifier on C1
     // Here 'cf1, cf2' are for each of the CycleFold circuits, corresponding to the fold of cmW and
     // cmE respectively
@@ -251,6 +265,9 @@ pub struct AugmentedFCircuit<
     pub cf_U_i: Option<CommittedInstance<C2>>, // input
     pub cf1_cmT: Option<C2>,
     pub cf2_cmT: Option<C2>,
+    // CycleFold instances and cross-terms commitments for the extra instances' commitments
+    pub cf_us_cmW: Option<Vec<C2>>,
+    pub cf_cmTs: Option<Vec<C2>>,
     pub cf_x: Option<CF1<C1>>, // public input (u_{i+1}.x[1])
 }
 
@@ -259,7 +276,12 @@ impl<C1: CurveGroup, C2: CurveGroup, GC2: CurveVar<C2, CF2<C2>>, FC: FCircuit<CF
 where
     for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
 {
-    pub fn empty(poseidon_config: &PoseidonConfig<CF1<C1>>, F_circuit: FC) -> Self {
+    pub fn empty(
+        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        F_circuit: FC,
+        mu: usize,
+        nu: usize,
+    ) -> Self {
         Self {
             _gc2: PhantomData,
             poseidon_config: poseidon_config.clone(),
@@ -276,15 +298,32 @@ where
             cmT: None,
             F: F_circuit,
             x: None,
+            mu,
+            nu,
+            Us: None,
+            us: None,
+            cmTs: None,
+            Us_cmTE: None,
+            U_acc_cmE: None,
+            U_acc_cmW: None,
             // cyclefold values
             cf1_u_i_cmW: None,
             cf2_u_i_cmW: None,
             cf_U_i: None,
             cf1_cmT: None,
             cf2_cmT: None,
+            cf_us_cmW: None,
+            cf_cmTs: None,
             cf_x: None,
         }
     }
+
+    /// number of CycleFold instances needed to fold the extra instances' commitments: two for each
+    /// incoming instance (cmW and cmE), and three for each running instance, since its cmE is
+    /// folded in two steps (cmT + r * cmE, and then U.cmE + r * (cmT + r * cmE)).
+    pub fn n_extra_cf_instances(mu: usize, nu: usize) -> usize {
+        3 * (mu - 1) + 2 * (nu - 1)
+    }
 }
 
 impl<C1, C2, GC2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, GC2, FC>
@@ -337,12 +376,58 @@ where
         let cmT =
             NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.cmT.unwrap_or_else(C1::zero)))?;
 
+        // extra instances for the multi-instances folding
+        let n_extra = self.mu - 1 + self.nu - 1;
+        let Us = Vec::<CommittedInstanceVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.Us.unwrap_or(vec![u_dummy.clone(); self.mu - 1]))
+        })?;
+        let us = self
+            .us
+            .unwrap_or(vec![u_dummy.clone(); self.nu - 1])
+            .into_iter()
+            .map(|u| {
+                Ok(CommittedInstanceVar {
+                    // us[k].cmE = cm(0)
+                    cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+                    // us[k].u = 1
+                    u: FpVar::one(),
+                    cmW: NonNativeAffineVar::new_witness(cs.clone(), || Ok(u.cmW))?,
+                    x: Vec::new_witness(cs.clone(), || Ok(u.x))?,
+                })
+            })
+            .collect::<Result<Vec<CommittedInstanceVar<C1>>, SynthesisError>>()?;
+        let cmTs = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.cmTs.unwrap_or(vec![C1::zero(); n_extra]))
+        })?;
+        let Us_cmTE = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.Us_cmTE.unwrap_or(vec![C1::zero(); self.mu - 1]))
+        })?;
+        // cmE & cmW of the result of each of the folds, where the last one is U_{i+1}
+        let U_acc_cmE = [
+            Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+                Ok(self.U_acc_cmE.unwrap_or(vec![C1::zero(); n_extra]))
+            })?,
+            vec![U_i1_cmE],
+        ]
+        .concat();
+        let U_acc_cmW = [
+            Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+                Ok(self.U_acc_cmW.unwrap_or(vec![C1::zero(); n_extra]))
+            })?,
+            vec![U_i1_cmW],
+        ]
+        .concat();
+
         let cf_u_dummy = CommittedInstance::dummy(cf_io_len(NOVA_CF_N_POINTS));
         let cf_U_i = CycleFoldCommittedInstanceVar::<C2, GC2>::new_witness(cs.clone(), || {
             Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
         })?;
         let cf1_cmT = GC2::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
         let cf2_cmT = GC2::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+        let n_extra_cf = Self::n_extra_cf_instances(self.mu, self.nu);
+        let cf_cmTs = Vec::<GC2>::new_witness(cs.clone(), || {
+            Ok(self.cf_cmTs.unwrap_or(vec![C2::zero(); n_extra_cf]))
+        })?;
 
         // `sponge` is for digest computation.
         let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
@@ -407,8 +492,66 @@ where
         // respectively.
         // The correctness of them will be checked on the other curve.
         let mut U_i1 = NIFSGadget::<C1>::fold_committed_instance(r, U_i.clone(), u_i.clone())?;
-        U_i1.cmE = U_i1_cmE;
-        U_i1.cmW = U_i1_cmW;
+        U_i1.cmE = U_acc_cmE[0].clone();
+        U_i1.cmW = U_acc_cmW[0].clone();
+
+        // points folded in each of the CycleFold circuits, as [r, p_0, p_1, p_folded]
+        let cf_points_x = |r: &NonNativeUintVar<CF1<C1>>,
+                           p_0: &NonNativeAffineVar<C1>,
+                           p_1: &NonNativeAffineVar<C1>,
+                           p_folded: &NonNativeAffineVar<C1>| {
+            vec![
+                r.clone(),
+                p_0.x.clone(),
+                p_0.y.clone(),
+                p_1.x.clone(),
+                p_1.y.clone(),
+                p_folded.x.clone(),
+                p_folded.y.clone(),
+            ]
+        };
+        let mut cf_xs = vec![
+            cf_points_x(&r_nonnat, &U_i.cmW, &u_i.cmW, &U_i1.cmW),
+            cf_points_x(&r_nonnat, &U_i.cmE, &cmT, &U_i1.cmE),
+        ];
+
+        // P.3.b fold the extra instances, obtaining the final U_{i+1}. As above, the cmE & cmW
+        // of the folded instances are checked in the CycleFold circuits.
+        let extra_instances = Us
+            .into_iter()
+            .zip(Us_cmTE.into_iter().map(Some))
+            .chain(us.into_iter().zip(core::iter::repeat(None)));
+        for (k, (u_k, cmTE_k)) in extra_instances.enumerate() {
+            let r_k_bits = ChallengeGadget::<C1>::get_challenge_gadget(
+                &mut transcript,
+                pp_hash.clone(),
+                U_i1.to_sponge_field_elements()?,
+                u_k.clone(),
+                cmTs[k].clone(),
+            )?;
+            let r_k = Boolean::le_bits_to_fp_var(&r_k_bits)?;
+            let r_k_nonnat = {
+                let mut bits = r_k_bits;
+                bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                NonNativeUintVar::from(&bits)
+            };
+            let mut U_k1 =
+                NIFSGadget::<C1>::fold_committed_instance(r_k, U_i1.clone(), u_k.clone())?;
+            U_k1.cmE = U_acc_cmE[k + 1].clone();
+            U_k1.cmW = U_acc_cmW[k + 1].clone();
+
+            cf_xs.push(cf_points_x(&r_k_nonnat, &U_i1.cmW, &u_k.cmW, &U_k1.cmW));
+            match cmTE_k {
+                // incoming instance: U_k1.cmE = U_i1.cmE + r_k * cmT_k
+                None => cf_xs.push(cf_points_x(&r_k_nonnat, &U_i1.cmE, &cmTs[k], &U_k1.cmE)),
+                // running instance: U_k1.cmE = U_i1.cmE + r_k * (cmT_k + r_k * u_k.cmE)
+                Some(cmTE_k) => {
+                    cf_xs.push(cf_points_x(&r_k_nonnat, &cmTs[k], &u_k.cmE, &cmTE_k));
+                    cf_xs.push(cf_points_x(&r_k_nonnat, &U_i1.cmE, &cmTE_k, &U_k1.cmE));
+                }
+            }
+            U_i1 = U_k1;
+        }
 
         // P.4.a compute and check the first output of F'
         // Base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{\bot})
@@ -431,91 +574,65 @@ where
         x.enforce_equal(&is_basecase.select(&u_i1_x_base, &u_i1_x)?)?;
 
         // CycleFold part
-        // C.1. Compute cf1_u_i.x and cf2_u_i.x
-        let cfW_x = vec![
-            r_nonnat.clone(),
-            U_i.cmW.x,
-            U_i.cmW.y,
-            u_i.cmW.x,
-            u_i.cmW.y,
-            U_i1.cmW.x,
-            U_i1.cmW.y,
-        ];
-        let cfE_x = vec![
-            r_nonnat, U_i.cmE.x, U_i.cmE.y, cmT.x, cmT.y, U_i1.cmE.x, U_i1.cmE.y,
-        ];
-
-        // ensure that cf1_u & cf2_u have as public inputs the cmW & cmE from main instances U_i,
-        // u_i, U_i+1 coordinates of the commitments
-        // C.2. Construct `cf1_u_i` and `cf2_u_i`
-        let cf1_u_i = CycleFoldCommittedInstanceVar {
-            // cf1_u_i.cmE = 0
-            cmE: GC2::zero(),
-            // cf1_u_i.u = 1
-            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf1_u_i.cmW is provided by the prover as witness
-            cmW: GC2::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf1_u_i.x is computed in step 1
-            x: cfW_x,
-        };
-        let cf2_u_i = CycleFoldCommittedInstanceVar {
-            // cf2_u_i.cmE = 0
-            cmE: GC2::zero(),
-            // cf2_u_i.u = 1
-            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf2_u_i.cmW is provided by the prover as witness
-            cmW: GC2::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf2_u_i.x is computed in step 1
-            x: cfE_x,
-        };
-
-        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
-        // by folding cf2_u_i & cf1_U_{i+1}.
-
-        // compute cf1_r = H(cf1_u_i, cf_U_i, cf1_cmT)
-        // cf_r_bits is denoted by rho* in the paper.
-        let cf1_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
-            &mut transcript,
-            pp_hash.clone(),
-            cf_U_i_vec,
-            cf1_u_i.clone(),
-            cf1_cmT.clone(),
-        )?;
-        // Convert cf1_r_bits to a `NonNativeFieldVar`
-        let cf1_r_nonnat = {
-            let mut bits = cf1_r_bits.clone();
-            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-            NonNativeUintVar::from(&bits)
-        };
-        // Fold cf1_u_i & cf_U_i into cf1_U_{i+1}
-        let cf1_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
-            cf1_r_bits,
-            cf1_r_nonnat,
-            cf1_cmT,
-            cf_U_i,
-            cf1_u_i,
-        )?;
-
-        // same for cf2_r:
-        let cf2_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
-            &mut transcript,
-            pp_hash.clone(),
-            cf1_U_i1.to_native_sponge_field_elements()?,
-            cf2_u_i.clone(),
-            cf2_cmT.clone(),
-        )?;
-        let cf2_r_nonnat = {
-            let mut bits = cf2_r_bits.clone();
-            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-            NonNativeUintVar::from(&bits)
-        };
-        let cf_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
-            cf2_r_bits,
-            cf2_r_nonnat,
-            cf2_cmT,
-            cf1_U_i1, // the output from NIFS.V(cf1_r, cf_U, cfE_u)
-            cf2_u_i,
-        )?;
+        // C.1. The public inputs of the CycleFold instances (cf1_u_i.x, cf2_u_i.x, and the ones of
+        // the extra instances) have been computed above, ensuring that they contain the
+        // coordinates of the commitments of the main instances U_i, u_i, U_{i+1}.
+        // C.2. Construct the CycleFold instances, whose cmW are provided by the prover as witness
+        let cf_us_cmW = [
+            vec![self.cf1_u_i_cmW, self.cf2_u_i_cmW],
+            self.cf_us_cmW
+                .map(|cmWs| cmWs.into_iter().map(Some).collect())
+                .unwrap_or(vec![None; n_extra_cf]),
+        ]
+        .concat();
+        let cf_cmTs = [vec![cf1_cmT, cf2_cmT], cf_cmTs].concat();
+
+        // C.3. nifs.verify, obtains cf_U_{i+1} by folding sequentially each of the CycleFold
+        // instances into cf_U_i (in Nova without extra instances, cf1_u_i & cf_U_i are folded
+        // into cf1_U_{i+1}, and then cf2_u_i & cf1_U_{i+1} into cf_U_{i+1}).
+        let mut cf_U_i1 = cf_U_i;
+        let mut cf_U_i1_vec = cf_U_i_vec;
+        for (j, ((cf_x_j, cf_cmW_j), cf_cmT_j)) in
+            cf_xs.into_iter().zip(cf_us_cmW).zip(cf_cmTs).enumerate()
+        {
+            let cf_u_j = CycleFoldCommittedInstanceVar {
+                // cf_u_j.cmE = 0
+                cmE: GC2::zero(),
+                // cf_u_j.u = 1
+                u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+                // cf_u_j.cmW is provided by the prover as witness
+                cmW: GC2::new_witness(cs.clone(), || Ok(cf_cmW_j.unwrap_or(C2::zero())))?,
+                // cf_u_j.x is computed in step 1
+                x: cf_x_j,
+            };
+            if j > 0 {
+                cf_U_i1_vec = cf_U_i1.to_native_sponge_field_elements()?;
+            }
+
+            // compute cf_r = H(cf_u_j, cf_U, cf_cmT_j)
+            // cf_r_bits is denoted by rho* in the paper.
+            let cf_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
+                &mut transcript,
+                pp_hash.clone(),
+                cf_U_i1_vec.clone(),
+                cf_u_j.clone(),
+                cf_cmT_j.clone(),
+            )?;
+            // Convert cf_r_bits to a `NonNativeFieldVar`
+            let cf_r_nonnat = {
+                let mut bits = cf_r_bits.clone();
+                bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                NonNativeUintVar::from(&bits)
+            };
+            // Fold cf_u_j into cf_U
+            cf_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
+                cf_r_bits,
+                cf_r_nonnat,
+                cf_cmT_j,
+                cf_U_i1,
+                cf_u_j,
+            )?;
+        }
 
         // Back to Primary Part
         // P.4.b compute and check the second output of F'
@@ -0,0 +1,365 @@
+/// This file implements the offchain decider. It does not require any trusted setup nor a
+/// decider circuit: the last fold is done natively by the verifier, and the satisfiability of the
+/// resulting Relaxed R1CS instance (and of the CycleFold instance) is proven with the
+/// Spartan-style argument from `spartan.rs`.
+///
+/// The multilinear openings use the MultilinearIPA, so the Decider works with both `Pedersen` and
+/// `IPA` commitments. Instantiating Nova with `IPA` commitments over the Pallas/Vesta cycle gives a
+/// fully transparent IVC and Decider, which require neither pairings nor a trusted setup.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb, CryptographicSponge};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::{One, Zero};
+use core::marker::PhantomData;
+use tracing::info_span;
+
+use super::{
+    circuits::ChallengeGadget,
+    nifs::NIFS,
+    spartan::{self, Proof as SpartanProof},
+    CommittedInstance, Nova,
+};
+use crate::commitment::{pedersen::Params as PedersenParams, CommitmentScheme};
+use crate::folding::circuits::CF2;
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::Error;
+use crate::{Decider as DeciderTrait, FoldingScheme};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Proof<C1: CurveGroup, C2: CurveGroup> {
+    /// proof of the Relaxed R1CS satisfiability of U_{i+1}
+    spartan_proof: SpartanProof<C1>,
+    /// proof of the Relaxed R1CS satisfiability of the CycleFold running instance
+    cf_spartan_proof: SpartanProof<C2>,
+    // cmT is the value for the last fold, U_{i+1}=NIFS.V(r, U_i, u_i, cmT), where r is recomputed
+    // by the verifier
+    cmT: C1,
+    cf_U_i: CommittedInstance<C2>,
+}
+
+/// Offchain Decider, which uses a transparent Spartan-style argument instead of a zkSNARK
+#[derive(Clone, Debug)]
+pub struct Decider<C1, GC1, C2, GC2, FC, CS1, CS2, FS> {
+    _c1: PhantomData<C1>,
+    _gc1: PhantomData<GC1>,
+    _c2: PhantomData<C2>,
+    _gc2: PhantomData<GC2>,
+    _fc: PhantomData<FC>,
+    _cs1: PhantomData<CS1>,
+    _cs2: PhantomData<CS2>,
+    _fs: PhantomData<FS>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, FS> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, GC1, C2, GC2, FC, CS1, CS2, FS>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    // the multilinear openings are done with the MultilinearIPA, which works over Pedersen
+    // commitments
+    CS1: CommitmentScheme<
+        C1,
+        ProverParams = PedersenParams<C1>,
+        VerifierParams = PedersenParams<C1>,
+    >,
+    CS2: CommitmentScheme<
+        C2,
+        ProverParams = PedersenParams<C2>,
+        VerifierParams = PedersenParams<C2>,
+    >,
+    FS: FoldingScheme<C1, C2, FC>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'b> &'b GC1: GroupOpsBounds<'b, C1, GC1>,
+    for<'b> &'b GC2: GroupOpsBounds<'b, C2, GC2>,
+    // constrain FS into Nova, since this is a Decider specifically for Nova
+    Nova<C1, GC1, C2, GC2, FC, CS1, CS2, false>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+{
+    type PreprocessorParam = (FS::ProverParam, FS::VerifierParam);
+    /// ProverParam = (cs_pp, cf_cs_pp)
+    type ProverParam = (PedersenParams<C1>, PedersenParams<C2>);
+    type Proof = Proof<C1, C2>;
+    /// VerifierParam = (pp_hash, nova::VerifierParams)
+    type VerifierParam = (
+        C1::ScalarField,
+        crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>,
+    );
+    type PublicInput = Vec<C1::ScalarField>;
+    type CommittedInstance = CommittedInstance<C1>;
+
+    fn preprocess(
+        _rng: impl RngCore + CryptoRng,
+        prep_param: &Self::PreprocessorParam,
+        _fs: FS,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        // get the FoldingScheme prover & verifier params from Nova
+        #[allow(clippy::type_complexity)]
+        let nova_pp: <Nova<C1, GC1, C2, GC2, FC, CS1, CS2, false> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::ProverParam = prep_param.0.clone().into();
+        #[allow(clippy::type_complexity)]
+        let nova_vp: <Nova<C1, GC1, C2, GC2, FC, CS1, CS2, false> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::VerifierParam = prep_param.1.clone().into();
+        let pp_hash = nova_vp.pp_hash()?;
+
+        let pp = (nova_pp.cs_pp, nova_pp.cf_cs_pp);
+        let vp = (pp_hash, nova_vp);
+        Ok((pp, vp))
+    }
+
+    #[tracing::instrument(skip_all, name = "nova_decider_prove")]
+    fn prove(
+        _rng: impl RngCore + CryptoRng,
+        pp: Self::ProverParam,
+        folding_scheme: FS,
+    ) -> Result<Self::Proof, Error> {
+        let (cs_pp, cf_cs_pp) = pp;
+        let nova: Nova<C1, GC1, C2, GC2, FC, CS1, CS2, false> = folding_scheme.into();
+
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+
+        // compute the U_{i+1}, W_{i+1}
+        let (T, cmT) = info_span!("cross_term").in_scope(|| {
+            NIFS::<C1, CS1>::compute_cmT(
+                &nova.cs_pp,
+                &nova.r1cs,
+                &nova.w_i,
+                &nova.u_i,
+                &nova.W_i,
+                &nova.U_i,
+            )
+        })?;
+        let r_bits = ChallengeGadget::<C1>::get_challenge_native(
+            &mut transcript,
+            nova.pp_hash,
+            nova.U_i.clone(),
+            nova.u_i.clone(),
+            cmT,
+        );
+        let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let (W_i1, U_i1) = NIFS::<C1, CS1>::fold_instances(
+            r_Fr, &nova.W_i, &nova.U_i, &nova.w_i, &nova.u_i, &T, cmT,
+        )?;
+
+        let spartan_proof = info_span!("spartan").in_scope(|| {
+            spartan::prove::<C1, _>(&cs_pp, &mut transcript, &nova.r1cs, &U_i1, &W_i1)
+        })?;
+
+        // the CycleFold instance lives on C2, so its argument uses a transcript over C2::ScalarField
+        let mut cf_transcript =
+            PoseidonSponge::<C2::ScalarField>::new(&poseidon_canonical_config::<C2::ScalarField>());
+        let cf_spartan_proof = info_span!("cf_spartan").in_scope(|| {
+            spartan::prove::<C2, _>(
+                &cf_cs_pp,
+                &mut cf_transcript,
+                &nova.cf_r1cs,
+                &nova.cf_U_i,
+                &nova.cf_W_i,
+            )
+        })?;
+
+        Ok(Self::Proof {
+            spartan_proof,
+            cf_spartan_proof,
+            cmT,
+            cf_U_i: nova.cf_U_i,
+        })
+    }
+
+    fn verify(
+        vp: Self::VerifierParam,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        running_instance: &Self::CommittedInstance,
+        incoming_instance: &Self::CommittedInstance,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        let (pp_hash, nova_vp) = vp;
+        let (U_i, u_i) = (running_instance, incoming_instance);
+
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&nova_vp.poseidon_config);
+
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        if U_i.hash(&sponge, pp_hash, i, z_0, z_i) != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        if proof.cf_U_i.hash_cyclefold(&sponge, pp_hash) != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // check u_i.cmE==0, u_i.u==1 (=u_i is a un-relaxed instance)
+        if !u_i.cmE.is_zero() || !u_i.u.is_one() {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // compute U = U_{i+1} = NIFS.V(U_i, u_i, cmT)
+        let mut transcript = sponge;
+        let r_bits = ChallengeGadget::<C1>::get_challenge_native(
+            &mut transcript,
+            pp_hash,
+            U_i.clone(),
+            u_i.clone(),
+            proof.cmT,
+        );
+        let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let U = NIFS::<C1, CS1>::verify(r_Fr, U_i, u_i, &proof.cmT);
+
+        spartan::verify::<C1, _>(
+            &nova_vp.cs_vp,
+            &mut transcript,
+            &nova_vp.r1cs,
+            &U,
+            &proof.spartan_proof,
+        )?;
+
+        let mut cf_transcript =
+            PoseidonSponge::<C2::ScalarField>::new(&poseidon_canonical_config::<C2::ScalarField>());
+        spartan::verify::<C2, _>(
+            &nova_vp.cf_cs_vp,
+            &mut cf_transcript,
+            &nova_vp.cf_r1cs,
+            &proof.cf_U_i,
+            &proof.cf_spartan_proof,
+        )?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+    use std::time::Instant;
+
+    use super::*;
+    use crate::commitment::{ipa::IPA, pedersen::Pedersen};
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::tests::CubicFCircuit;
+
+    #[test]
+    fn test_decider() {
+        // Pedersen commitments on both curves
+        test_decider_opt::<Pedersen<Projective>, Pedersen<Projective2>>();
+        // IPA commitments on both curves, the transparent setting over the Pallas/Vesta cycle
+        test_decider_opt::<IPA<Projective>, IPA<Projective2>>();
+    }
+
+    // test_decider allowing to choose the CommitmentSchemes
+    fn test_decider_opt<CS1, CS2>()
+    where
+        CS1: CommitmentScheme<
+            Projective,
+            ProverParams = PedersenParams<Projective>,
+            VerifierParams = PedersenParams<Projective>,
+        >,
+        CS2: CommitmentScheme<
+            Projective2,
+            ProverParams = PedersenParams<Projective2>,
+            VerifierParams = PedersenParams<Projective2>,
+        >,
+    {
+        // use Nova as FoldingScheme
+        type N<CS1, CS2> =
+            Nova<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, false>;
+        type D<CS1, CS2> = Decider<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            CS1,
+            CS2,
+            N<CS1, CS2>, // here we define the FoldingScheme to use
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::<CS1, CS2>::preprocess(&mut rng, &prep_param).unwrap();
+
+        let mut nova = N::<CS1, CS2>::init(&nova_params, F_circuit, z_0.clone()).unwrap();
+        nova.prove_step(&mut rng, vec![], None).unwrap();
+        nova.prove_step(&mut rng, vec![], None).unwrap(); // do a 2nd step
+
+        let mut rng = rand::rngs::OsRng;
+
+        // prepare the Decider prover & verifier params
+        let (decider_pp, decider_vp) =
+            D::<CS1, CS2>::preprocess(&mut rng, &nova_params, nova.clone()).unwrap();
+
+        // decider proof generation
+        let start = Instant::now();
+        let proof = D::<CS1, CS2>::prove(rng, decider_pp, nova.clone()).unwrap();
+        println!("Decider prove, {:?}", start.elapsed());
+
+        // decider proof verification
+        let start = Instant::now();
+        let verified = D::<CS1, CS2>::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+        println!("Decider verify, {:?}", start.elapsed());
+
+        // a wrong z_i is rejected
+        let wrong_z_i = vec![nova.z_i[0] + Fr::one()];
+        assert!(D::<CS1, CS2>::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            wrong_z_i,
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .is_err());
+
+        // a wrong running instance is rejected
+        let mut wrong_U_i = nova.U_i.clone();
+        wrong_U_i.u += Fr::one();
+        assert!(D::<CS1, CS2>::verify(
+            decider_vp, nova.i, nova.z_0, nova.z_i, &wrong_U_i, &nova.u_i, &proof,
+        )
+        .is_err());
+    }
+}
@@ -5,10 +5,14 @@ use ark_ec::{AffineRepr, CurveGroup, Group};
 use ark_ff::{BigInteger, PrimeField};
 use ark_groth16::Groth16;
 use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use ark_snark::SNARK;
 use ark_std::rand::{CryptoRng, RngCore};
 use ark_std::{One, Zero};
 use core::marker::PhantomData;
+use tracing::info_span;
 
 pub use super::decider_eth_circuit::{DeciderEthCircuit, KZGChallengesGadget};
 use super::{nifs::NIFS, CommittedInstance, Nova};
@@ -40,6 +44,88 @@ where
     kzg_challenges: [C1::ScalarField; 2],
 }
 
+// Note that `CanonicalSerialize` & `CanonicalDeserialize` can not be derived for `Proof`, since
+// `CommitmentScheme::Proof` is not bounded by them, so the bound is added in the impls instead.
+impl<C1, CS1, S> CanonicalSerialize for Proof<C1, CS1, S>
+where
+    C1: CurveGroup,
+    CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
+    CS1::Proof: CanonicalSerialize,
+    S: SNARK<C1::ScalarField>,
+{
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.snark_proof
+            .serialize_with_mode(&mut writer, compress)?;
+        self.kzg_proofs[0].serialize_with_mode(&mut writer, compress)?;
+        self.kzg_proofs[1].serialize_with_mode(&mut writer, compress)?;
+        self.cmT.serialize_with_mode(&mut writer, compress)?;
+        self.r.serialize_with_mode(&mut writer, compress)?;
+        self.kzg_challenges[0].serialize_with_mode(&mut writer, compress)?;
+        self.kzg_challenges[1].serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.snark_proof.serialized_size(compress)
+            + self.kzg_proofs[0].serialized_size(compress)
+            + self.kzg_proofs[1].serialized_size(compress)
+            + self.cmT.serialized_size(compress)
+            + self.r.serialized_size(compress)
+            + self.kzg_challenges[0].serialized_size(compress)
+            + self.kzg_challenges[1].serialized_size(compress)
+    }
+}
+
+impl<C1, CS1, S> Valid for Proof<C1, CS1, S>
+where
+    C1: CurveGroup,
+    CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
+    CS1::Proof: Valid,
+    S: SNARK<C1::ScalarField>,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.snark_proof.check()?;
+        self.kzg_proofs[0].check()?;
+        self.kzg_proofs[1].check()?;
+        self.cmT.check()?;
+        self.r.check()?;
+        self.kzg_challenges[0].check()?;
+        self.kzg_challenges[1].check()
+    }
+}
+
+impl<C1, CS1, S> CanonicalDeserialize for Proof<C1, CS1, S>
+where
+    C1: CurveGroup,
+    CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
+    CS1::Proof: CanonicalDeserialize,
+    S: SNARK<C1::ScalarField>,
+{
+    fn deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let snark_proof = S::Proof::deserialize_with_mode(&mut reader, compress, validate)?;
+        let kzg_proof_W = CS1::Proof::deserialize_with_mode(&mut reader, compress, validate)?;
+        let kzg_proof_E = CS1::Proof::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cmT = C1::deserialize_with_mode(&mut reader, compress, validate)?;
+        let r = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let challenge_W = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let challenge_E = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self {
+            snark_proof,
+            kzg_proofs: [kzg_proof_W, kzg_proof_E],
+            cmT,
+            r,
+            kzg_challenges: [challenge_W, challenge_E],
+        })
+    }
+}
+
 /// Onchain Decider, for ethereum use cases
 #[derive(Clone, Debug)]
 pub struct Decider<C1, GC1, C2, GC2, FC, CS1, CS2, S, FS> {
@@ -88,9 +174,13 @@ where
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
 {
     type PreprocessorParam = (FS::ProverParam, FS::VerifierParam);
+    /// ProverParam = (snark::pk, commitment_scheme::pk). It implements `CanonicalSerialize` &
+    /// `CanonicalDeserialize` (when `CS1::ProverParams` does, as for KZG), so it can be stored and
+    /// reused to avoid running the SNARK's circuit-specific setup at each `preprocess` call.
     type ProverParam = (S::ProvingKey, CS1::ProverParams);
     type Proof = Proof<C1, CS1, S>;
-    /// VerifierParam = (pp_hash, snark::vk, commitment_scheme::vk)
+    /// VerifierParam = (pp_hash, snark::vk, commitment_scheme::vk), which implements
+    /// `CanonicalSerialize` & `CanonicalDeserialize`.
     type VerifierParam = (C1::ScalarField, S::VerifyingKey, CS1::VerifierParams);
     type PublicInput = Vec<C1::ScalarField>;
     type CommittedInstance = CommittedInstance<C1>;
@@ -126,6 +216,7 @@ where
         Ok((pp, vp))
     }
 
+    #[tracing::instrument(skip_all, name = "nova_decider_eth_prove")]
     fn prove(
         mut rng: impl RngCore + CryptoRng,
         pp: Self::ProverParam,
@@ -133,11 +224,12 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, GC1, C2, GC2, CS1, CS2>::from_nova::<FC>(
-            folding_scheme.into(),
-        )?;
+        let circuit = info_span!("decider_circuit").in_scope(|| {
+            DeciderEthCircuit::<C1, GC1, C2, GC2, CS1, CS2>::from_nova::<FC>(folding_scheme.into())
+        })?;
 
-        let snark_proof = S::prove(&snark_pk, circuit.clone(), &mut rng)
+        let snark_proof = info_span!("snark_prove")
+            .in_scope(|| S::prove(&snark_pk, circuit.clone(), &mut rng))
             .map_err(|e| Error::Other(e.to_string()))?;
 
         let cmT = circuit.cmT.unwrap();
@@ -154,6 +246,7 @@ where
             .ok_or(Error::MissingValue("kzg_c_E".to_string()))?;
 
         // generate KZG proofs
+        let _kzg_span = info_span!("kzg_proofs").entered();
         let U_cmW_proof = CS1::prove_with_challenge(
             &cs_pk,
             challenge_W,
@@ -295,7 +388,7 @@ pub fn prepare_calldata(
     .concat())
 }
 
-fn point_to_eth_format<C: AffineRepr>(p: C) -> Result<Vec<u8>, Error>
+pub(crate) fn point_to_eth_format<C: AffineRepr>(p: C) -> Result<Vec<u8>, Error>
 where
     C::BaseField: PrimeField,
 {
@@ -305,7 +398,7 @@ where
 
     Ok([x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()].concat())
 }
-fn point2_to_eth_format(p: ark_bn254::G2Affine) -> Result<Vec<u8>, Error> {
+pub(crate) fn point2_to_eth_format(p: ark_bn254::G2Affine) -> Result<Vec<u8>, Error> {
     let zero_point = (&ark_bn254::Fq2::zero(), &ark_bn254::Fq2::zero());
     let (x, y) = p.xy().unwrap_or(zero_point);
 
@@ -354,6 +447,10 @@ pub mod tests {
             Groth16<Bn254>, // here we define the Snark to use in the decider
             N,              // here we define the FoldingScheme to use
         >;
+        type DPP = <D as DeciderTrait<Projective, Projective2, CubicFCircuit<Fr>, N>>::ProverParam;
+        type DVP =
+            <D as DeciderTrait<Projective, Projective2, CubicFCircuit<Fr>, N>>::VerifierParam;
+        type DProof = <D as DeciderTrait<Projective, Projective2, CubicFCircuit<Fr>, N>>::Proof;
 
         let mut rng = ark_std::test_rng();
         let poseidon_config = poseidon_canonical_config::<Fr>();
@@ -377,15 +474,44 @@ pub mod tests {
         // prepare the Decider prover & verifier params
         let (decider_pp, decider_vp) = D::preprocess(&mut rng, &nova_params, nova.clone()).unwrap();
 
+        // store the Decider prover & verifier params, and load them back, as it would be done when
+        // reusing them in a different process instead of running the preprocess again
+        let mut decider_pp_bytes = vec![];
+        decider_pp
+            .serialize_compressed(&mut decider_pp_bytes)
+            .unwrap();
+        let mut decider_vp_bytes = vec![];
+        decider_vp
+            .serialize_compressed(&mut decider_vp_bytes)
+            .unwrap();
+        let decider_pp = DPP::deserialize_compressed(decider_pp_bytes.as_slice()).unwrap();
+        let decider_vp = DVP::deserialize_compressed(decider_vp_bytes.as_slice()).unwrap();
+
         // decider proof generation
         let start = Instant::now();
         let proof = D::prove(rng, decider_pp, nova.clone()).unwrap();
         println!("Decider prove, {:?}", start.elapsed());
 
+        // serialize & deserialize the proof
+        let mut proof_bytes = vec![];
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        let proof_deserialized = DProof::deserialize_compressed(proof_bytes.as_slice()).unwrap();
+        let mut proof_deserialized_bytes = vec![];
+        proof_deserialized
+            .serialize_compressed(&mut proof_deserialized_bytes)
+            .unwrap();
+        assert_eq!(proof_bytes, proof_deserialized_bytes);
+
         // decider proof verification
         let start = Instant::now();
         let verified = D::verify(
-            decider_vp, nova.i, nova.z_0, nova.z_i, &nova.U_i, &nova.u_i, &proof,
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i,
+            &nova.u_i,
+            &proof_deserialized,
         )
         .unwrap();
         assert!(verified);
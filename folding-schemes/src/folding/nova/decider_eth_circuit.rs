@@ -539,8 +539,7 @@ where
 
 /// Interpolates the polynomial from the given vector, and then returns it's evaluation at the
 /// given point.
-#[allow(unused)] // unused while check 7 is disabled
-fn evaluate_gadget<F: PrimeField>(
+pub(crate) fn evaluate_gadget<F: PrimeField>(
     v: Vec<FpVar<F>>,
     point: FpVar<F>,
 ) -> Result<FpVar<F>, SynthesisError> {
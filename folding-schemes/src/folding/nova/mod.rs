@@ -13,6 +13,8 @@ use ark_std::fmt::Debug;
 use ark_std::rand::RngCore;
 use ark_std::{One, UniformRand, Zero};
 use core::marker::PhantomData;
+use std::time::Instant;
+use tracing::info_span;
 
 use crate::commitment::CommitmentScheme;
 use crate::folding::circuits::cyclefold::{fold_cyclefold_circuit, CycleFoldCircuit};
@@ -21,25 +23,29 @@ use crate::frontend::FCircuit;
 use crate::transcript::{AbsorbNonNative, Transcript};
 use crate::utils::vec::is_zero_vec;
 use crate::Error;
-use crate::FoldingScheme;
 use crate::{
     arith::r1cs::{extract_r1cs, extract_w_x, R1CS},
-    utils::{get_cm_coordinates, pp_hash},
+    utils::{get_cm_coordinates, pp_hash, timed},
 };
+use crate::{FoldingScheme, MultiFolding, StepMetrics};
 
+pub mod batch;
 pub mod circuits;
+pub mod decider;
 pub mod decider_eth;
 pub mod decider_eth_circuit;
 pub mod nifs;
 pub mod serialize;
+pub mod spartan;
 pub mod traits;
+pub mod zk;
 use circuits::{AugmentedFCircuit, ChallengeGadget};
 use nifs::NIFS;
 use traits::NovaR1CS;
 
 /// Number of points to be folded in the CycleFold circuit, in Nova's case, this is a fixed amount:
 /// 2 points to be folded.
-const NOVA_CF_N_POINTS: usize = 2_usize;
+pub(crate) const NOVA_CF_N_POINTS: usize = 2_usize;
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CommittedInstance<C: CurveGroup> {
@@ -231,6 +237,10 @@ where
     pub cs_vp: Option<CS1::VerifierParams>,
     pub cf_cs_pp: Option<CS2::ProverParams>,
     pub cf_cs_vp: Option<CS2::VerifierParams>,
+    /// number of running & incoming instances to be folded at each step (including the main
+    /// ones), by default 1 & 1, ie. no multi-instances folding
+    pub mu: usize,
+    pub nu: usize,
 }
 
 impl<C1, C2, FC, CS1, CS2, const H: bool> PreprocessorParam<C1, C2, FC, CS1, CS2, H>
@@ -249,6 +259,8 @@ where
             cs_vp: None,
             cf_cs_pp: None,
             cf_cs_vp: None,
+            mu: 1,
+            nu: 1,
         }
     }
 }
@@ -264,6 +276,8 @@ where
     pub poseidon_config: PoseidonConfig<C1::ScalarField>,
     pub cs_pp: CS1::ProverParams,
     pub cf_cs_pp: CS2::ProverParams,
+    pub mu: usize,
+    pub nu: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -279,6 +293,10 @@ where
     pub cf_r1cs: R1CS<C2::ScalarField>,
     pub cs_vp: CS1::VerifierParams,
     pub cf_cs_vp: CS2::VerifierParams,
+    /// number of running & incoming instances folded at each step, which the AugmentedFCircuit
+    /// (and thus `r1cs`) depends on
+    pub mu: usize,
+    pub nu: usize,
 }
 
 impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
@@ -330,6 +348,8 @@ where
     pub F: FC,
     /// public params hash
     pub pp_hash: C1::ScalarField,
+    pub mu: usize, // number of running instances to be folded
+    pub nu: usize, // number of incoming instances to be folded
     pub i: C1::ScalarField,
     /// initial state
     pub z_0: Vec<C1::ScalarField>,
@@ -346,6 +366,184 @@ where
     pub cf_U_i: CommittedInstance<C2>,
 }
 
+/// IVCProof contains all the values needed to verify the Nova+CycleFold IVC, so that it can be
+/// serialized and verified through `FoldingScheme::verify_proof`.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1: CurveGroup, C2: CurveGroup> {
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// number of steps
+    pub i: C1::ScalarField,
+    /// Nova instances
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    /// CycleFold running instance
+    pub cf_W_i: Witness<C2>,
+    pub cf_U_i: CommittedInstance<C2>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> MultiFolding<C1, C2, FC>
+    for Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
+    type MultiInstance = (Vec<Self::RunningInstance>, Vec<Self::IncomingInstance>);
+
+    /// Creates a new running instance for the given state, which satisfies the Nova relaxed R1CS.
+    /// Notice that a fresh instance (u=1, cmE=0) is also a valid relaxed instance. This method can
+    /// be used to generate the 'other' running instances to be folded in the multi-folding step.
+    fn new_running_instance(
+        &self,
+        rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<Self::RunningInstance, Error> {
+        let (U_i, W_i) = self.new_instance_generic(rng, state, external_inputs)?;
+
+        #[cfg(test)]
+        self.r1cs.check_relaxed_instance_relation(&W_i, &U_i)?;
+
+        Ok((U_i, W_i))
+    }
+
+    /// Creates a new incoming instance for the given state, which satisfies the Nova R1CS. This
+    /// method can be used to generate the 'other' incoming instances to be folded in the
+    /// multi-folding step.
+    fn new_incoming_instance(
+        &self,
+        rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<Self::IncomingInstance, Error> {
+        let (u_i, w_i) = self.new_instance_generic(rng, state, external_inputs)?;
+
+        #[cfg(test)]
+        self.r1cs.check_instance_relation(&w_i, &u_i)?;
+
+        Ok((u_i, w_i))
+    }
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    /// internal helper for new_running_instance & new_incoming_instance methods, runs the
+    /// AugmentedFCircuit at the base case for the given state and returns the committed instance
+    /// together with its witness.
+    fn new_instance_generic(
+        &self,
+        mut rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<(CommittedInstance<C1>, Witness<C1>), Error> {
+        // prepare the initial dummy instances
+        let (_, U_i) = self.r1cs.dummy_instance();
+        let (_, cf_U_i) = self.cf_r1cs.dummy_instance();
+        let Us = vec![U_i.clone(); self.mu - 1];
+        let us = vec![U_i.clone(); self.nu - 1];
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+
+        let z_i1 = self
+            .F
+            .step_native(0, state.clone(), external_inputs.clone())?;
+
+        // compute u_{i+1}.x, where at the base case U_{i+1} is the dummy instance
+        let u_i1_x = U_i.hash(
+            &sponge,
+            self.pp_hash,
+            C1::ScalarField::one(), // i+1, where i=0
+            self.z_0.clone(),
+            z_i1,
+        );
+        let cf_u_i1_x = cf_U_i.hash_cyclefold(&sponge, self.pp_hash);
+
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, GC2, FC> {
+            _gc2: PhantomData,
+            poseidon_config: self.poseidon_config.clone(),
+            pp_hash: Some(self.pp_hash),
+            i: Some(C1::ScalarField::zero()),
+            i_usize: Some(0),
+            z_0: Some(self.z_0.clone()),
+            z_i: Some(state),
+            external_inputs: Some(external_inputs),
+            u_i_cmW: Some(U_i.cmW),
+            U_i: Some(U_i.clone()),
+            U_i1_cmE: Some(U_i.cmE),
+            U_i1_cmW: Some(U_i.cmW),
+            cmT: None,
+            F: self.F.clone(),
+            x: Some(u_i1_x),
+            mu: self.mu,
+            nu: self.nu,
+            Us: Some(Us),
+            us: Some(us),
+            cmTs: None,
+            Us_cmTE: None,
+            U_acc_cmE: None,
+            U_acc_cmW: None,
+            // cyclefold values
+            cf1_u_i_cmW: None,
+            cf2_u_i_cmW: None,
+            cf_U_i: None,
+            cf1_cmT: None,
+            cf2_cmT: None,
+            cf_us_cmW: None,
+            cf_cmTs: None,
+            cf_x: Some(cf_u_i1_x),
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        augmented_F_circuit.generate_constraints(cs.clone())?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x::<C1::ScalarField>(&cs);
+        if x[0] != u_i1_x || x[1] != cf_u_i1_x {
+            return Err(Error::NotEqual);
+        }
+
+        let w = Witness::<C1>::new::<H>(w, self.r1cs.A.n_rows, &mut rng);
+        let u = w.commit::<CS1, H>(&self.cs_pp, x)?;
+        Ok((u, w))
+    }
+}
+
 impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
     for Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
 where
@@ -369,39 +567,45 @@ where
     type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
     type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
     type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
-    type MultiCommittedInstanceWithWitness = ();
+    type MultiCommittedInstanceWithWitness = (
+        Vec<(CommittedInstance<C1>, Witness<C1>)>,
+        Vec<(CommittedInstance<C1>, Witness<C1>)>,
+    );
     type CFInstance = (CommittedInstance<C2>, Witness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
 
     fn preprocess(
         mut rng: impl RngCore,
         prep_param: &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        let (r1cs, cf_r1cs) =
-            get_r1cs::<C1, GC1, C2, GC2, FC>(&prep_param.poseidon_config, prep_param.F.clone())?;
-
-        // if cs params exist, use them, if not, generate new ones
-        let cs_pp: CS1::ProverParams;
-        let cs_vp: CS1::VerifierParams;
-        let cf_cs_pp: CS2::ProverParams;
-        let cf_cs_vp: CS2::VerifierParams;
-        if prep_param.cs_pp.is_some()
-            && prep_param.cf_cs_pp.is_some()
-            && prep_param.cs_vp.is_some()
-            && prep_param.cf_cs_vp.is_some()
-        {
-            cs_pp = prep_param.clone().cs_pp.unwrap();
-            cs_vp = prep_param.clone().cs_vp.unwrap();
-            cf_cs_pp = prep_param.clone().cf_cs_pp.unwrap();
-            cf_cs_vp = prep_param.clone().cf_cs_vp.unwrap();
-        } else {
-            (cs_pp, cs_vp) = CS1::setup(&mut rng, r1cs.A.n_rows)?;
-            (cf_cs_pp, cf_cs_vp) = CS2::setup(&mut rng, cf_r1cs.A.n_rows)?;
+        if prep_param.mu < 1 || prep_param.nu < 1 {
+            return Err(Error::CantBeZero("mu,nu".to_string()));
         }
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, GC1, C2, GC2, FC>(
+            &prep_param.poseidon_config,
+            prep_param.F.clone(),
+            prep_param.mu,
+            prep_param.nu,
+        )?;
+
+        // if cs params exist, use them, if not, generate new ones. The params of each curve are
+        // handled independently, so that for example the KZG params can be loaded from a ceremony
+        // (see `KZG::setup_from_ptau`) while the CycleFold ones are generated here.
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(&mut rng, r1cs.A.n_rows)?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(&mut rng, cf_r1cs.A.n_rows)?,
+        };
 
         let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
             poseidon_config: prep_param.poseidon_config.clone(),
             cs_pp: cs_pp.clone(),
             cf_cs_pp: cf_cs_pp.clone(),
+            mu: prep_param.mu,
+            nu: prep_param.nu,
         };
         let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
             poseidon_config: prep_param.poseidon_config.clone(),
@@ -409,6 +613,8 @@ where
             cf_r1cs,
             cs_vp,
             cf_cs_vp,
+            mu: prep_param.mu,
+            nu: prep_param.nu,
         };
 
         Ok((prover_params, verifier_params))
@@ -426,8 +632,15 @@ where
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
 
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, GC2, FC>::empty(&pp.poseidon_config, F.clone());
+        if pp.mu < 1 || pp.nu < 1 {
+            return Err(Error::CantBeZero("mu,nu".to_string()));
+        }
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, GC2, FC>::empty(
+            &pp.poseidon_config,
+            F.clone(),
+            pp.mu,
+            pp.nu,
+        );
         let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty(NOVA_CF_N_POINTS);
 
         augmented_F_circuit.generate_constraints(cs.clone())?;
@@ -460,6 +673,8 @@ where
             cf_cs_pp: pp.cf_cs_pp.clone(),
             F,
             pp_hash,
+            mu: pp.mu,
+            nu: pp.nu,
             i: C1::ScalarField::zero(),
             z_0: z_0.clone(),
             z_i: z_0,
@@ -473,14 +688,19 @@ where
         })
     }
 
-    /// Implements IVC.P of Nova+CycleFold
+    /// Implements IVC.P of Nova+CycleFold. When `mu` or `nu` are greater than 1, `other_instances`
+    /// is required at every step, as in HyperNova. The extra instances are folded from the second
+    /// step on: at the base case (i=0) they are checked against `mu` and `nu` and then ignored.
+    #[tracing::instrument(skip_all, name = "nova_prove_step")]
     fn prove_step(
         &mut self,
         mut rng: impl RngCore,
         external_inputs: Vec<C1::ScalarField>,
-        // Nova does not support multi-instances folding
-        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
-    ) -> Result<(), Error> {
+        other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<StepMetrics, Error> {
+        let start = Instant::now();
+        let mut metrics = StepMetrics::default();
+
         // ensure that commitments are blinding if user has specified so.
         if H && self.i >= C1::ScalarField::one() {
             let blinding_commitments = if self.i == C1::ScalarField::one() {
@@ -503,9 +723,35 @@ where
 
         let augmented_F_circuit: AugmentedFCircuit<C1, C2, GC2, FC>;
 
-        // Nova does not support (by design) multi-instances folding
-        if _other_instances.is_some() {
-            return Err(Error::NoMultiInstances);
+        // recall, mu & nu is the number of all the running & incoming instances respectively,
+        // including U_i and u_i which are not part of the 'other_instances', hence the +1 in the
+        // couple of following checks.
+        let (Us_with_w, us_with_w) = match other_instances {
+            Some(other_instances) => other_instances,
+            None if self.mu == 1 && self.nu == 1 => (vec![], vec![]),
+            None => return Err(Error::MissingOtherInstances),
+        };
+        if Us_with_w.len() + 1 != self.mu {
+            return Err(Error::NotSameLength(
+                "other_instances.running.len()".to_string(),
+                Us_with_w.len(),
+                "nova.mu".to_string(),
+                self.mu,
+            ));
+        }
+        if us_with_w.len() + 1 != self.nu {
+            return Err(Error::NotSameLength(
+                "other_instances.incoming.len()".to_string(),
+                us_with_w.len(),
+                "nova.nu".to_string(),
+                self.nu,
+            ));
+        }
+        if us_with_w
+            .iter()
+            .any(|(u, _)| !u.cmE.is_zero() || !u.u.is_one())
+        {
+            return Err(Error::R1CSUnrelaxedFail);
         }
 
         if self.z_i.len() != self.F.state_len() {
@@ -532,12 +778,28 @@ where
         i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
         let i_usize: usize = usize::from_le_bytes(i_bytes);
 
-        let z_i1 = self
-            .F
-            .step_native(i_usize, self.z_i.clone(), external_inputs.clone())?;
-
-        // compute T and cmT for AugmentedFCircuit
-        let (T, cmT) = self.compute_cmT()?;
+        let z_i1 = timed(
+            info_span!("step_native"),
+            &mut metrics.witness_generation,
+            || {
+                self.F
+                    .step_native(i_usize, self.z_i.clone(), external_inputs.clone())
+            },
+        )?;
+
+        // compute T and cmT for AugmentedFCircuit. When H is set, cmT is blinded, since it is
+        // folded into the cmE of U_{i+1}
+        let (T, rT, cmT) = timed(info_span!("cross_term"), &mut metrics.folding, || {
+            NIFS::<C1, CS1, H>::compute_cmT_with_blinding(
+                &self.cs_pp,
+                &self.r1cs,
+                &self.w_i,
+                &self.u_i,
+                &self.W_i,
+                &self.U_i,
+                &mut rng,
+            )
+        })?;
 
         // r_bits is the r used to the RLC of the F' instances
         let r_bits = ChallengeGadget::<C1>::get_challenge_native(
@@ -549,14 +811,82 @@ where
         );
         let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
             .ok_or(Error::OutOfBounds)?;
-        let r_Fq = C1::BaseField::from_bigint(BigInteger::from_bits_le(&r_bits))
-            .ok_or(Error::OutOfBounds)?;
 
         // fold Nova instances
-        let (W_i1, U_i1): (Witness<C1>, CommittedInstance<C1>) =
-            NIFS::<C1, CS1, H>::fold_instances(
-                r_Fr, &self.W_i, &self.U_i, &self.w_i, &self.u_i, &T, cmT,
-            )?;
+        let (mut W_i1, mut U_i1): (Witness<C1>, CommittedInstance<C1>) =
+            timed(info_span!("fold_instances"), &mut metrics.folding, || {
+                NIFS::<C1, CS1, H>::fold_instances_with_blinding(
+                    r_Fr, &self.W_i, &self.U_i, &self.w_i, &self.u_i, &T, rT, cmT,
+                )
+            })?;
+
+        // points folded by each of the CycleFold circuits, as (r_bits, p_0, p_1, p_folded), where
+        // p_folded = p_0 + r * p_1
+        let mut cf_points: Vec<(Vec<bool>, C1, C1, C1)> = vec![
+            (r_bits.clone(), self.U_i.cmW, self.u_i.cmW, U_i1.cmW),
+            (r_bits, self.U_i.cmE, cmT, U_i1.cmE),
+        ];
+
+        // fold the extra instances sequentially into U_{i+1}. At the base case they are not
+        // folded, since the circuit outputs the hash of the dummy running instance.
+        let (Us, _): (Vec<CommittedInstance<C1>>, Vec<Witness<C1>>) =
+            Us_with_w.iter().cloned().unzip();
+        let (us, _): (Vec<CommittedInstance<C1>>, Vec<Witness<C1>>) =
+            us_with_w.iter().cloned().unzip();
+        let (mut cmTs, mut Us_cmTE, mut U_acc_cmE, mut U_acc_cmW) =
+            (vec![], vec![], vec![], vec![]);
+        if self.i != C1::ScalarField::zero() {
+            let extra_instances = Us_with_w
+                .iter()
+                .map(|U_k| (U_k, true))
+                .chain(us_with_w.iter().map(|u_k| (u_k, false)));
+            for ((U_k, W_k), is_running) in extra_instances {
+                let (T_k, rT_k, cmT_k) =
+                    timed(info_span!("cross_term"), &mut metrics.folding, || {
+                        NIFS::<C1, CS1, H>::compute_cmT_with_blinding(
+                            &self.cs_pp,
+                            &self.r1cs,
+                            &W_i1,
+                            &U_i1,
+                            W_k,
+                            U_k,
+                            &mut rng,
+                        )
+                    })?;
+                let r_k_bits = ChallengeGadget::<C1>::get_challenge_native(
+                    &mut transcript,
+                    self.pp_hash,
+                    U_i1.clone(),
+                    U_k.clone(),
+                    cmT_k,
+                );
+                let r_k = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_k_bits))
+                    .ok_or(Error::OutOfBounds)?;
+                let (W_k1, U_k1) =
+                    timed(info_span!("fold_instances"), &mut metrics.folding, || {
+                        NIFS::<C1, CS1, H>::fold_instances_with_blinding(
+                            r_k, &W_i1, &U_i1, W_k, U_k, &T_k, rT_k, cmT_k,
+                        )
+                    })?;
+
+                cf_points.push((r_k_bits.clone(), U_i1.cmW, U_k.cmW, U_k1.cmW));
+                if is_running {
+                    // U_k1.cmE = U_i1.cmE + r_k * (cmT_k + r_k * U_k.cmE)
+                    let cmTE_k = cmT_k + U_k.cmE.mul(r_k);
+                    cf_points.push((r_k_bits.clone(), cmT_k, U_k.cmE, cmTE_k));
+                    cf_points.push((r_k_bits, U_i1.cmE, cmTE_k, U_k1.cmE));
+                    Us_cmTE.push(cmTE_k);
+                } else {
+                    // U_k1.cmE = U_i1.cmE + r_k * cmT_k
+                    cf_points.push((r_k_bits, U_i1.cmE, cmT_k, U_k1.cmE));
+                }
+                cmTs.push(cmT_k);
+                U_acc_cmE.push(U_i1.cmE);
+                U_acc_cmW.push(U_i1.cmW);
+
+                (W_i1, U_i1) = (W_k1, U_k1);
+            }
+        }
 
         // folded instance output (public input, x)
         // u_{i+1}.x[0] = H(i+1, z_0, z_{i+1}, U_{i+1})
@@ -589,11 +919,21 @@ where
                 cmT: Some(cmT),
                 F: self.F.clone(),
                 x: Some(u_i1_x),
+                mu: self.mu,
+                nu: self.nu,
+                Us: None, // = dummy
+                us: None, // = dummy
+                cmTs: None,
+                Us_cmTE: None,
+                U_acc_cmE: None,
+                U_acc_cmW: None,
                 cf1_u_i_cmW: None,
                 cf2_u_i_cmW: None,
                 cf_U_i: None,
                 cf1_cmT: None,
                 cf2_cmT: None,
+                cf_us_cmW: None,
+                cf_cmTs: None,
                 cf_x: Some(cf_u_i1_x),
             };
 
@@ -601,57 +941,50 @@ where
             NIFS::<C1, CS1, H>::verify_folded_instance(r_Fr, &self.U_i, &self.u_i, &U_i1, &cmT)?;
         } else {
             // CycleFold part:
-            // get the vector used as public inputs 'x' in the CycleFold circuit
-            // cyclefold circuit for cmW
-            let cfW_u_i_x = [
-                vec![r_Fq],
-                get_cm_coordinates(&self.U_i.cmW),
-                get_cm_coordinates(&self.u_i.cmW),
-                get_cm_coordinates(&U_i1.cmW),
-            ]
-            .concat();
-            // cyclefold circuit for cmE
-            let cfE_u_i_x = [
-                vec![r_Fq],
-                get_cm_coordinates(&self.U_i.cmE),
-                get_cm_coordinates(&cmT),
-                get_cm_coordinates(&U_i1.cmE),
-            ]
-            .concat();
-
-            let cfW_circuit = CycleFoldCircuit::<C1, GC1> {
-                _gc: PhantomData,
-                n_points: NOVA_CF_N_POINTS,
-                r_bits: Some(vec![r_bits.clone()]),
-                points: Some(vec![self.U_i.clone().cmW, self.u_i.clone().cmW]),
-                x: Some(cfW_u_i_x.clone()),
-            };
-            let cfE_circuit = CycleFoldCircuit::<C1, GC1> {
-                _gc: PhantomData,
-                n_points: NOVA_CF_N_POINTS,
-                r_bits: Some(vec![r_bits.clone()]),
-                points: Some(vec![self.U_i.clone().cmE, cmT]),
-                x: Some(cfE_u_i_x.clone()),
-            };
-
-            // fold self.cf_U_i + cfW_U -> folded running with cfW
-            let (_cfW_w_i, cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT, _) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
-                cfW_u_i_x,
-                cfW_circuit,
-                &mut rng,
-            )?;
-            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
-            let (_cfE_w_i, cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT, _) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                cfW_W_i1,
-                cfW_U_i1.clone(),
-                cfE_u_i_x,
-                cfE_circuit,
-                &mut rng,
-            )?;
+            // fold sequentially each of the CycleFold instances into the CycleFold running
+            // instance. Without extra instances, these are the instances of the circuits for cmW
+            // and cmE, ie. self.cf_U_i + cfW_U -> folded running with cfW, and then
+            // [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
+            let (mut cf_W_i1, mut cf_U_i1) = (self.cf_W_i.clone(), self.cf_U_i.clone());
+            let (mut cf_us_cmW, mut cf_cmTs) = (vec![], vec![]);
+            for (r_bits_j, p_0, p_1, p_folded) in cf_points {
+                let r_Fq_j = C1::BaseField::from_bigint(BigInteger::from_bits_le(&r_bits_j))
+                    .ok_or(Error::OutOfBounds)?;
+                // get the vector used as public inputs 'x' in the CycleFold circuit
+                let cf_u_i_x = [
+                    vec![r_Fq_j],
+                    get_cm_coordinates(&p_0),
+                    get_cm_coordinates(&p_1),
+                    get_cm_coordinates(&p_folded),
+                ]
+                .concat();
+                let cf_circuit = CycleFoldCircuit::<C1, GC1> {
+                    _gc: PhantomData,
+                    n_points: NOVA_CF_N_POINTS,
+                    r_bits: Some(vec![r_bits_j]),
+                    points: Some(vec![p_0, p_1]),
+                    x: Some(cf_u_i_x.clone()),
+                };
+
+                let (_cf_w_j, cf_u_j, cf_W_j1, cf_U_j1, cf_cmT_j, _) =
+                    timed(info_span!("cyclefold"), &mut metrics.cyclefold, || {
+                        self.fold_cyclefold_circuit(
+                            &mut transcript,
+                            cf_W_i1, // CycleFold running instance witness
+                            cf_U_i1, // CycleFold running instance
+                            cf_u_i_x,
+                            cf_circuit,
+                            &mut rng,
+                        )
+                    })?;
+
+                #[cfg(test)]
+                self.cf_r1cs.check_instance_relation(&_cf_w_j, &cf_u_j)?;
+
+                cf_us_cmW.push(cf_u_j.cmW);
+                cf_cmTs.push(cf_cmT_j);
+                (cf_W_i1, cf_U_i1) = (cf_W_j1, cf_U_j1);
+            }
 
             cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, self.pp_hash);
 
@@ -671,12 +1004,22 @@ where
                 cmT: Some(cmT),
                 F: self.F.clone(),
                 x: Some(u_i1_x),
+                mu: self.mu,
+                nu: self.nu,
+                Us: Some(Us),
+                us: Some(us),
+                cmTs: Some(cmTs),
+                Us_cmTE: Some(Us_cmTE),
+                U_acc_cmE: Some(U_acc_cmE),
+                U_acc_cmW: Some(U_acc_cmW),
                 // cyclefold values
-                cf1_u_i_cmW: Some(cfW_u_i.cmW),
-                cf2_u_i_cmW: Some(cfE_u_i.cmW),
+                cf1_u_i_cmW: Some(cf_us_cmW[0]),
+                cf2_u_i_cmW: Some(cf_us_cmW[1]),
                 cf_U_i: Some(self.cf_U_i.clone()),
-                cf1_cmT: Some(cfW_cmT),
-                cf2_cmT: Some(cf_cmT),
+                cf1_cmT: Some(cf_cmTs[0]),
+                cf2_cmT: Some(cf_cmTs[1]),
+                cf_us_cmW: Some(cf_us_cmW[2..].to_vec()),
+                cf_cmTs: Some(cf_cmTs[2..].to_vec()),
                 cf_x: Some(cf_u_i1_x),
             };
 
@@ -684,23 +1027,25 @@ where
             self.cf_U_i = cf_U_i1;
 
             #[cfg(test)]
-            {
-                self.cf_r1cs.check_instance_relation(&_cfW_w_i, &cfW_u_i)?;
-                self.cf_r1cs.check_instance_relation(&_cfE_w_i, &cfE_u_i)?;
-                self.cf_r1cs
-                    .check_relaxed_instance_relation(&self.cf_W_i, &self.cf_U_i)?;
-            }
+            self.cf_r1cs
+                .check_relaxed_instance_relation(&self.cf_W_i, &self.cf_U_i)?;
         }
 
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        let (w_i1, x_i1) = timed(
+            info_span!("witness_generation"),
+            &mut metrics.witness_generation,
+            || -> Result<_, Error> {
+                let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
 
-        augmented_F_circuit.generate_constraints(cs.clone())?;
+                augmented_F_circuit.generate_constraints(cs.clone())?;
 
-        #[cfg(test)]
-        assert!(cs.is_satisfied().unwrap());
+                #[cfg(test)]
+                assert!(cs.is_satisfied().unwrap());
 
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+                let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+                Ok(extract_w_x::<C1::ScalarField>(&cs))
+            },
+        )?;
         if x_i1[0] != u_i1_x || x_i1[1] != cf_u_i1_x {
             return Err(Error::NotEqual);
         }
@@ -714,7 +1059,9 @@ where
         self.i += C1::ScalarField::one();
         self.z_i = z_i1;
         self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs.A.n_rows, &mut rng);
-        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)?;
+        self.u_i = timed(info_span!("commitment"), &mut metrics.commitment, || {
+            self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)
+        })?;
         self.W_i = W_i1;
         self.U_i = U_i1;
 
@@ -725,13 +1072,18 @@ where
                 .check_relaxed_instance_relation(&self.W_i, &self.U_i)?;
         }
 
-        Ok(())
+        metrics.total = start.elapsed();
+        Ok(metrics)
     }
 
     fn state(&self) -> Vec<C1::ScalarField> {
         self.z_i.clone()
     }
 
+    fn num_constraints(&self) -> (usize, usize) {
+        (self.r1cs.A.n_rows, self.cf_r1cs.A.n_rows)
+    }
+
     fn instances(
         &self,
     ) -> (
@@ -804,6 +1156,32 @@ where
 
         Ok(())
     }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        IVCProof {
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            i: self.i,
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn verify_proof(vp: Self::VerifierParam, proof: &Self::IVCProof) -> Result<(), Error> {
+        Self::verify(
+            vp,
+            proof.z_0.clone(),
+            proof.z_i.clone(),
+            proof.i,
+            (proof.U_i.clone(), proof.W_i.clone()),
+            (proof.u_i.clone(), proof.w_i.clone()),
+            (proof.cf_U_i.clone(), proof.cf_W_i.clone()),
+        )
+    }
 }
 
 impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
@@ -903,6 +1281,8 @@ pub fn get_r1cs_from_cs<F: PrimeField>(
 pub fn get_r1cs<C1, GC1, C2, GC2, FC>(
     poseidon_config: &PoseidonConfig<C1::ScalarField>,
     F_circuit: FC,
+    mu: usize,
+    nu: usize,
 ) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
 where
     C1: CurveGroup,
@@ -919,7 +1299,7 @@ where
     for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
 {
     let augmented_F_circuit =
-        AugmentedFCircuit::<C1, C2, GC2, FC>::empty(poseidon_config, F_circuit);
+        AugmentedFCircuit::<C1, C2, GC2, FC>::empty(poseidon_config, F_circuit, mu, nu);
     let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty(NOVA_CF_N_POINTS);
     let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
     let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
@@ -931,6 +1311,8 @@ where
 pub fn get_cs_params_len<C1, GC1, C2, GC2, FC>(
     poseidon_config: &PoseidonConfig<C1::ScalarField>,
     F_circuit: FC,
+    mu: usize,
+    nu: usize,
 ) -> Result<(usize, usize), Error>
 where
     C1: CurveGroup,
@@ -946,7 +1328,7 @@ where
     for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
     for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
 {
-    let (r1cs, cf_r1cs) = get_r1cs::<C1, GC1, C2, GC2, FC>(poseidon_config, F_circuit)?;
+    let (r1cs, cf_r1cs) = get_r1cs::<C1, GC1, C2, GC2, FC>(poseidon_config, F_circuit, mu, nu)?;
     Ok((r1cs.A.n_rows, cf_r1cs.A.n_rows))
 }
 
@@ -1003,6 +1385,8 @@ pub mod tests {
                 cs_vp: None,
                 cf_cs_pp: None,
                 cf_cs_vp: None,
+                mu: 1,
+                nu: 1,
             };
         let nova_params = Nova::<
             Projective,
@@ -1031,6 +1415,21 @@ pub mod tests {
         }
         assert_eq!(Fr::from(num_steps as u32), nova.i);
 
+        // check that the IVCProof verifies after a serialization roundtrip
+        let ivc_proof = nova.ivc_proof();
+        let mut ivc_proof_bytes = vec![];
+        ivc_proof
+            .serialize_compressed(&mut ivc_proof_bytes)
+            .unwrap();
+        let ivc_proof =
+            IVCProof::<Projective, Projective2>::deserialize_compressed(&ivc_proof_bytes[..])
+                .unwrap();
+        Nova::<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, H>::verify_proof(
+            nova_params.1.clone(),
+            &ivc_proof,
+        )
+        .unwrap();
+
         let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
         Nova::<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, H>::verify(
             nova_params.1, // Nova's verifier params
@@ -1043,4 +1442,208 @@ pub mod tests {
         )
         .unwrap();
     }
+
+    /// Tests the Nova+CycleFold IVC when folding extra running & incoming instances at each step
+    #[test]
+    fn test_ivc_multi_instances() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let (mu, nu) = (2, 3);
+        let mut prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        prep_param.mu = mu;
+        prep_param.nu = nu;
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone()).unwrap();
+
+        let num_steps: usize = 3;
+        for i in 0..num_steps {
+            let mut running = vec![];
+            for j in 0..mu - 1 {
+                running.push(
+                    nova.new_running_instance(&mut rng, vec![Fr::from(j as u32 + 100)], vec![])
+                        .unwrap(),
+                );
+            }
+            let mut incoming = vec![];
+            for j in 0..nu - 1 {
+                incoming.push(
+                    nova.new_incoming_instance(&mut rng, vec![Fr::from(j as u32 + 200)], vec![])
+                        .unwrap(),
+                );
+            }
+            if i == 0 {
+                // as in HyperNova, the extra instances are required at every step, and their
+                // number has to match the mu & nu set at preprocess, even at the base case where
+                // they are ignored
+                assert!(matches!(
+                    nova.prove_step(&mut rng, vec![], None),
+                    Err(Error::MissingOtherInstances)
+                ));
+                assert!(matches!(
+                    nova.prove_step(&mut rng, vec![], Some((vec![], incoming.clone()))),
+                    Err(Error::NotSameLength(..))
+                ));
+            }
+            nova.prove_step(&mut rng, vec![], Some((running, incoming)))
+                .unwrap();
+        }
+        assert_eq!(Fr::from(num_steps as u32), nova.i);
+
+        let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
+        N::verify(
+            nova_params.1, // Nova's verifier params
+            z_0,
+            nova.z_i,
+            nova.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_prove_steps() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone()).unwrap();
+
+        // the external inputs are generated lazily by the iterator
+        let num_steps: usize = 3;
+        let mut steps = vec![];
+        let inputs = (0..num_steps).map(|_| (vec![], None));
+        nova.prove_steps(&mut rng, inputs, |progress| {
+            assert_eq!(progress.num_constraints, nova_params.1.r1cs.A.n_rows);
+            assert_eq!(progress.cf_num_constraints, nova_params.1.cf_r1cs.A.n_rows);
+            // the phases of the step do not overlap, so they can not exceed its total time
+            let m = progress.metrics;
+            assert!(m.witness_generation + m.commitment + m.folding + m.cyclefold <= m.total);
+            steps.push(progress.step);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(steps, (0..num_steps).collect::<Vec<usize>>());
+        assert_eq!(Fr::from(num_steps as u32), nova.i);
+
+        // abort from the callback after the first step, without consuming the remaining inputs
+        let mut consumed = 0;
+        let inputs = (0..num_steps).map(|_| {
+            consumed += 1;
+            (vec![], None)
+        });
+        let res = nova.prove_steps(&mut rng, inputs, |_| Err(Error::Other("abort".to_string())));
+        assert!(res.is_err());
+        assert_eq!(consumed, 1);
+        assert_eq!(Fr::from(num_steps as u32 + 1), nova.i);
+
+        let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
+        N::verify(
+            nova_params.1, // Nova's verifier params
+            z_0,
+            nova.z_i,
+            nova.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
+
+    /// Tests `prove_steps` when folding extra running & incoming instances at each step
+    #[test]
+    fn test_prove_steps_multi_instances() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let (mu, nu) = (2, 2);
+        let mut prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        prep_param.mu = mu;
+        prep_param.nu = nu;
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone()).unwrap();
+
+        // the extra instances are folded from the second step on, the ones of the first step are
+        // ignored
+        let num_steps: usize = 3;
+        let mut steps = vec![];
+        for i in 0..num_steps {
+            let running = nova
+                .new_running_instance(&mut rng, vec![Fr::from(i as u32 + 100)], vec![])
+                .unwrap();
+            let incoming = nova
+                .new_incoming_instance(&mut rng, vec![Fr::from(i as u32 + 200)], vec![])
+                .unwrap();
+            steps.push((vec![], Some((vec![running], vec![incoming]))));
+        }
+        nova.prove_steps(&mut rng, steps, |_| Ok(())).unwrap();
+        assert_eq!(Fr::from(num_steps as u32), nova.i);
+
+        // without the extra instances the step can not be proven, and the state is kept
+        assert!(matches!(
+            nova.prove_steps(&mut rng, vec![(vec![], None)], |_| Ok(())),
+            Err(Error::MissingOtherInstances)
+        ));
+        assert_eq!(Fr::from(num_steps as u32), nova.i);
+
+        let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
+        N::verify(
+            nova_params.1, // Nova's verifier params
+            z_0,
+            nova.z_i,
+            nova.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
 }
@@ -1,6 +1,6 @@
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::{CurveGroup, Group};
-use ark_std::Zero;
+use ark_std::{rand::RngCore, UniformRand, Zero};
 use std::marker::PhantomData;
 
 use super::{CommittedInstance, Witness};
@@ -107,6 +107,33 @@ where
         let cmT = CS::commit(cs_prover_params, &T, &C::ScalarField::zero())?;
         Ok((T, cmT))
     }
+    /// Like `compute_cmT`, but when `H` is set the cross-terms are committed with a random
+    /// blinding factor rT, so that cmT (and the cmE of the folded instance) does not leak the
+    /// cross-terms. rT is returned to be folded into the blinding of the error term with
+    /// `fold_instances_with_blinding`. When `H` is not set, rT=0.
+    #[allow(clippy::type_complexity)]
+    pub fn compute_cmT_with_blinding(
+        cs_prover_params: &CS::ProverParams,
+        r1cs: &R1CS<C::ScalarField>,
+        w1: &Witness<C>,
+        ci1: &CommittedInstance<C>,
+        w2: &Witness<C>,
+        ci2: &CommittedInstance<C>,
+        mut rng: impl RngCore,
+    ) -> Result<(Vec<C::ScalarField>, C::ScalarField, C), Error> {
+        let z1: Vec<C::ScalarField> = [vec![ci1.u], ci1.x.to_vec(), w1.W.to_vec()].concat();
+        let z2: Vec<C::ScalarField> = [vec![ci2.u], ci2.x.to_vec(), w2.W.to_vec()].concat();
+
+        // compute cross terms
+        let T = Self::compute_T(r1cs, ci1.u, ci2.u, &z1, &z2)?;
+        let rT = if H {
+            C::ScalarField::rand(&mut rng)
+        } else {
+            C::ScalarField::zero()
+        };
+        let cmT = CS::commit(cs_prover_params, &T, &rT)?;
+        Ok((T, rT, cmT))
+    }
     pub fn compute_cyclefold_cmT(
         cs_prover_params: &CS::ProverParams,
         r1cs: &R1CS<C::ScalarField>, // R1CS over C2.Fr=C1.Fq (here C=C2)
@@ -140,9 +167,25 @@ where
         T: &[C::ScalarField],
         cmT: C,
     ) -> Result<(Witness<C>, CommittedInstance<C>), Error> {
-        // fold witness
         // use r_T=0 since we don't need hiding property for cm(T)
-        let w3 = NIFS::<C, CS, H>::fold_witness(r, w1, w2, T, C::ScalarField::zero())?;
+        Self::fold_instances_with_blinding(r, w1, ci1, w2, ci2, T, C::ScalarField::zero(), cmT)
+    }
+
+    /// Like `fold_instances`, for cross-terms committed with the blinding factor rT (see
+    /// `compute_cmT_with_blinding`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn fold_instances_with_blinding(
+        r: C::ScalarField,
+        w1: &Witness<C>,
+        ci1: &CommittedInstance<C>,
+        w2: &Witness<C>,
+        ci2: &CommittedInstance<C>,
+        T: &[C::ScalarField],
+        rT: C::ScalarField,
+        cmT: C,
+    ) -> Result<(Witness<C>, CommittedInstance<C>), Error> {
+        // fold witness
+        let w3 = NIFS::<C, CS, H>::fold_witness(r, w1, w2, T, rT)?;
 
         // fold committed instances
         let ci3 = NIFS::<C, CS, H>::fold_committed_instance(r, ci1, ci2, &cmT);
@@ -7,18 +7,25 @@ use ark_r1cs_std::{
 };
 use ark_relations::r1cs::ConstraintSynthesizer;
 use ark_relations::r1cs::ConstraintSystem;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError, Write};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate, Write,
+};
 use std::marker::PhantomData;
 
-use super::{circuits::AugmentedFCircuit, Nova, ProverParams};
+use super::{circuits::AugmentedFCircuit, get_r1cs, Nova, ProverParams, VerifierParams};
 use super::{CommittedInstance, Witness};
 use crate::folding::{
+    checkpoint::Checkpointable,
     circuits::{cyclefold::CycleFoldCircuit, CF2},
     nova::NOVA_CF_N_POINTS,
+    params::{check_cs_params, ParamsHeader},
 };
 use crate::{
-    arith::r1cs::extract_r1cs, commitment::CommitmentScheme, folding::circuits::CF1,
+    arith::r1cs::{extract_r1cs, R1CS},
+    commitment::CommitmentScheme,
+    folding::circuits::CF1,
     frontend::FCircuit,
+    Error,
 };
 
 impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> CanonicalSerialize
@@ -99,7 +106,7 @@ impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Nova<C1, GC1, C2, GC2, FC, C
 where
     C1: CurveGroup,
     C2: CurveGroup,
-    FC: FCircuit<CF1<C1>, Params = ()>,
+    FC: FCircuit<CF1<C1>>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     <C1 as CurveGroup>::BaseField: PrimeField,
@@ -114,12 +121,16 @@ where
     GC2: CurveVar<C2, CF2<C2>>,
     GC2: ToConstraintFieldGadget<<C2 as CurveGroup>::BaseField>,
 {
-    pub fn deserialize_nova<R: std::io::prelude::Read>(
+    /// Deserializes the Nova prover state for the given (already constructed) `f_circuit`, whose
+    /// constraints are used to reconstruct the R1CS of the AugmentedFCircuit. This allows to
+    /// deserialize Nova for step circuits with non-unit `FCircuit::Params`.
+    pub fn deserialize_nova_with_circuit<R: std::io::prelude::Read>(
         mut reader: R,
         compress: ark_serialize::Compress,
         validate: ark_serialize::Validate,
         prover_params: ProverParams<C1, C2, CS1, CS2, H>,
         poseidon_config: PoseidonConfig<C1::ScalarField>,
+        f_circuit: FC,
     ) -> Result<Self, ark_serialize::SerializationError> {
         let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
         let i = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
@@ -133,11 +144,14 @@ where
         let cf_U_i =
             CommittedInstance::<C2>::deserialize_with_mode(&mut reader, compress, validate)?;
 
-        let f_circuit = FC::new(()).unwrap();
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, GC2, FC>::empty(&poseidon_config, f_circuit.clone());
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, GC2, FC>::empty(
+            &poseidon_config,
+            f_circuit.clone(),
+            prover_params.mu,
+            prover_params.nu,
+        );
         let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty(NOVA_CF_N_POINTS);
 
         augmented_F_circuit
@@ -164,6 +178,8 @@ where
             cf_cs_pp: prover_params.cf_cs_pp,
             F: f_circuit,
             pp_hash,
+            mu: prover_params.mu,
+            nu: prover_params.nu,
             i,
             z_0,
             z_i,
@@ -177,6 +193,316 @@ where
     }
 }
 
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    FC: FCircuit<CF1<C1>, Params = ()>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+    GC1: CurveVar<C1, <C2 as Group>::ScalarField>,
+    GC1: ToConstraintFieldGadget<<C2 as Group>::ScalarField>,
+    GC2: CurveVar<C2, CF2<C2>>,
+    GC2: ToConstraintFieldGadget<<C2 as CurveGroup>::BaseField>,
+{
+    /// Deserializes the Nova prover state for step circuits whose `FCircuit::Params` is `()`. For
+    /// other step circuits use `deserialize_nova_with_circuit`.
+    pub fn deserialize_nova<R: std::io::prelude::Read>(
+        reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        prover_params: ProverParams<C1, C2, CS1, CS2, H>,
+        poseidon_config: PoseidonConfig<C1::ScalarField>,
+    ) -> Result<Self, ark_serialize::SerializationError> {
+        let f_circuit = FC::new(()).map_err(|_| SerializationError::InvalidData)?;
+        Self::deserialize_nova_with_circuit(
+            reader,
+            compress,
+            validate,
+            prover_params,
+            poseidon_config,
+            f_circuit,
+        )
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    CS1::ProverParams: CanonicalSerialize,
+    CS2::ProverParams: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), SerializationError> {
+        self.cs_pp.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_pp.serialize_with_mode(&mut writer, compress)?;
+        self.mu.serialize_with_mode(&mut writer, compress)?;
+        self.nu.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.cs_pp.serialized_size(compress)
+            + self.cf_cs_pp.serialized_size(compress)
+            + self.mu.serialized_size(compress)
+            + self.nu.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    CS1::ProverParams: CanonicalSerialize + CanonicalDeserialize,
+    CS2::ProverParams: CanonicalSerialize + CanonicalDeserialize,
+{
+    /// Deserializes the prover params. The `PoseidonConfig` is not serialized, so it has to be
+    /// provided.
+    pub fn deserialize_prover_params<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    ) -> Result<Self, SerializationError> {
+        let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let nu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(ProverParams {
+            poseidon_config: poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+            mu,
+            nu,
+        })
+    }
+
+    /// Exports the prover params, prefixed by a [`ParamsHeader`] binding them to the given
+    /// `pp_hash` (obtained from `VerifierParams::pp_hash`) and FCircuit.
+    pub fn export<W: Write, FC: FCircuit<C1::ScalarField>>(
+        &self,
+        mut writer: W,
+        pp_hash: C1::ScalarField,
+        F_circuit: &FC,
+    ) -> Result<(), Error> {
+        ParamsHeader::new(pp_hash, F_circuit)?.write(&mut writer)?;
+        Ok(self.serialize_uncompressed(writer)?)
+    }
+
+    /// Imports the prover params exported with [`ProverParams::export`], checking that they were
+    /// generated for the given FCircuit, that they are bound to the `pp_hash` of the given
+    /// verifier params, and that their commitment schemes' params, `mu` and `nu` match the
+    /// verifier ones.
+    pub fn import<R: std::io::prelude::Read, FC: FCircuit<C1::ScalarField>>(
+        mut reader: R,
+        F_circuit: &FC,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+    ) -> Result<Self, Error>
+    where
+        <C1 as Group>::ScalarField: Absorb,
+        <C2 as Group>::ScalarField: Absorb,
+    {
+        let header = ParamsHeader::read(&mut reader, F_circuit)?;
+        header.check_pp_hash(vp.pp_hash()?)?;
+        let pp = Self::deserialize_prover_params(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            &vp.poseidon_config,
+        )?;
+        if pp.mu != vp.mu || pp.nu != vp.nu {
+            return Err(Error::InvalidParams(
+                "mu and nu do not match the verifier params".to_string(),
+            ));
+        }
+        check_cs_params::<C1, CS1, H>(&pp.cs_pp, &vp.cs_vp, vp.r1cs.A.n_rows)?;
+        check_cs_params::<C2, CS2, H>(&pp.cf_cs_pp, &vp.cf_cs_vp, vp.cf_r1cs.A.n_rows)?;
+        Ok(pp)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), SerializationError> {
+        self.r1cs.serialize_with_mode(&mut writer, compress)?;
+        self.cf_r1cs.serialize_with_mode(&mut writer, compress)?;
+        self.cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.mu.serialize_with_mode(&mut writer, compress)?;
+        self.nu.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.r1cs.serialized_size(compress)
+            + self.cf_r1cs.serialized_size(compress)
+            + self.cs_vp.serialized_size(compress)
+            + self.cf_cs_vp.serialized_size(compress)
+            + self.mu.serialized_size(compress)
+            + self.nu.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Deserializes the verifier params. The `PoseidonConfig` is not serialized, so it has to be
+    /// provided.
+    pub fn deserialize_verifier_params<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    ) -> Result<Self, SerializationError> {
+        let r1cs = R1CS::<C1::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_r1cs =
+            R1CS::<C2::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let nu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(VerifierParams {
+            poseidon_config: poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+            mu,
+            nu,
+        })
+    }
+
+    /// Exports the verifier params, prefixed by a [`ParamsHeader`] binding them to their
+    /// `pp_hash` and to the given FCircuit.
+    pub fn export<W: Write, FC: FCircuit<C1::ScalarField>>(
+        &self,
+        mut writer: W,
+        F_circuit: &FC,
+    ) -> Result<(), Error> {
+        ParamsHeader::new(self.pp_hash()?, F_circuit)?.write(&mut writer)?;
+        Ok(self.serialize_uncompressed(writer)?)
+    }
+
+    /// Imports the verifier params exported with [`VerifierParams::export`], checking that they
+    /// were generated for the given FCircuit and that their `pp_hash` matches the exported one.
+    /// Since the header only fingerprints the FCircuit, the R1CS of the AugmentedFCircuit and of
+    /// the CycleFold circuit are also recomputed for the given FCircuit and the imported `mu` and
+    /// `nu`, and checked against the imported ones.
+    pub fn import<R, GC1, GC2, FC>(
+        mut reader: R,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        F_circuit: &FC,
+    ) -> Result<Self, Error>
+    where
+        R: std::io::prelude::Read,
+        GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+        GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+        FC: FCircuit<C1::ScalarField>,
+        <C1 as CurveGroup>::BaseField: PrimeField,
+        <C2 as CurveGroup>::BaseField: PrimeField,
+        <C1 as Group>::ScalarField: Absorb,
+        <C2 as Group>::ScalarField: Absorb,
+        C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+        for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+        for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+    {
+        let header = ParamsHeader::read(&mut reader, F_circuit)?;
+        let vp = Self::deserialize_verifier_params(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            poseidon_config,
+        )?;
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, GC1, C2, GC2, FC>(poseidon_config, F_circuit.clone(), vp.mu, vp.nu)?;
+        if r1cs != vp.r1cs || cf_r1cs != vp.cf_r1cs {
+            return Err(Error::InvalidParams(
+                "the R1CS does not match the circuits of the given FCircuit, mu and nu".to_string(),
+            ));
+        }
+        header.check_pp_hash(vp.pp_hash()?)?;
+        Ok(vp)
+    }
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> Checkpointable<C1, C2, FC>
+    for Nova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+    GC1: CurveVar<C1, <C2 as Group>::ScalarField>,
+    GC1: ToConstraintFieldGadget<<C2 as Group>::ScalarField>,
+    GC2: CurveVar<C2, <C2 as CurveGroup>::BaseField>,
+    GC2: ToConstraintFieldGadget<<C2 as CurveGroup>::BaseField>,
+{
+    fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
+
+    fn num_steps(&self) -> C1::ScalarField {
+        self.i
+    }
+
+    fn deserialize_state<R: std::io::prelude::Read>(
+        reader: R,
+        params: &(Self::ProverParam, Self::VerifierParam),
+        step_circuit: FC,
+    ) -> Result<Self, Error> {
+        let state = Self::deserialize_nova_with_circuit(
+            reader,
+            Compress::No,
+            Validate::Yes,
+            params.0.clone(),
+            params.0.poseidon_config.clone(),
+            step_circuit,
+        )?;
+        if state.pp_hash != params.1.pp_hash()? {
+            return Err(Error::InvalidCheckpoint(
+                "pp_hash does not match the given params".to_string(),
+            ));
+        }
+        Ok(state)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_bn254::{constraints::GVar, Bn254, Fr, G1Projective as Projective};
@@ -186,10 +512,13 @@ pub mod tests {
 
     use crate::{
         commitment::{kzg::KZG, pedersen::Pedersen},
-        folding::nova::{Nova, PreprocessorParam},
-        frontend::{tests::CubicFCircuit, FCircuit},
+        folding::nova::{Nova, PreprocessorParam, ProverParams, VerifierParams},
+        frontend::{
+            tests::{CubicFCircuit, CustomFCircuit},
+            FCircuit,
+        },
         transcript::poseidon::poseidon_canonical_config,
-        FoldingScheme,
+        Error, FoldingScheme,
     };
 
     #[test]
@@ -265,4 +594,212 @@ pub mod tests {
 
         assert_eq!(deserialized_nova.w_i, nova.w_i);
     }
+
+    #[test]
+    fn test_serde_nova_with_circuit() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        // CustomFCircuit has non-unit `FCircuit::Params`
+        let F_circuit = CustomFCircuit::<Fr>::new(10).unwrap();
+
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CustomFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&nova_params, F_circuit, z_0).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, vec![], None).unwrap();
+        }
+
+        let mut bytes = vec![];
+        nova.serialize_compressed(&mut bytes).unwrap();
+
+        let mut deserialized_nova = N::deserialize_nova_with_circuit(
+            bytes.as_slice(),
+            Compress::Yes,
+            Validate::Yes,
+            nova_params.0.clone(),
+            poseidon_config,
+            F_circuit,
+        )
+        .unwrap();
+        assert_eq!(nova.i, deserialized_nova.i);
+        assert_eq!(nova.r1cs, deserialized_nova.r1cs);
+        assert_eq!(nova.cf_r1cs, deserialized_nova.cf_r1cs);
+
+        for _ in 0..2 {
+            deserialized_nova
+                .prove_step(&mut rng, vec![], None)
+                .unwrap();
+        }
+        let (running_instance, incoming_instance, cyclefold_instance) =
+            deserialized_nova.instances();
+        N::verify(
+            nova_params.1,
+            deserialized_nova.z_0.clone(),
+            deserialized_nova.z_i.clone(),
+            deserialized_nova.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_export_import_params() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CustomFCircuit::<Fr>::new(10).unwrap();
+
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CustomFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type PP = ProverParams<
+            Projective,
+            Projective2,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type VP = VerifierParams<
+            Projective,
+            Projective2,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F_circuit);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param).unwrap();
+        let pp_hash = vp.pp_hash().unwrap();
+
+        let mut pp_bytes = vec![];
+        pp.export(&mut pp_bytes, pp_hash, &F_circuit).unwrap();
+        let mut vp_bytes = vec![];
+        vp.export(&mut vp_bytes, &F_circuit).unwrap();
+
+        // import the params for the same FCircuit
+        let imported_vp =
+            VP::import::<_, GVar, GVar2, _>(vp_bytes.as_slice(), &poseidon_config, &F_circuit)
+                .unwrap();
+        let imported_pp = PP::import(pp_bytes.as_slice(), &F_circuit, &imported_vp).unwrap();
+        assert_eq!(imported_vp.pp_hash().unwrap(), pp_hash);
+
+        // the imported params can be used to run and verify the IVC
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&(imported_pp, imported_vp.clone()), F_circuit, z_0).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, vec![], None).unwrap();
+        }
+        N::verify_proof(imported_vp.clone(), &nova.ivc_proof()).unwrap();
+
+        // params generated for a different FCircuit are rejected
+        let other_F_circuit = CustomFCircuit::<Fr>::new(20).unwrap();
+        assert!(matches!(
+            VP::import::<_, GVar, GVar2, _>(
+                vp_bytes.as_slice(),
+                &poseidon_config,
+                &other_F_circuit
+            ),
+            Err(Error::InvalidParams(_))
+        ));
+        // the params of a different FCircuit are rejected, even if exported with the header of
+        // the given one
+        let other_prep_param = PreprocessorParam::new(poseidon_config.clone(), other_F_circuit);
+        let (_, other_vp) = N::preprocess(&mut rng, &other_prep_param).unwrap();
+        let mut other_vp_bytes = vec![];
+        other_vp.export(&mut other_vp_bytes, &F_circuit).unwrap();
+        assert!(matches!(
+            VP::import::<_, GVar, GVar2, _>(
+                other_vp_bytes.as_slice(),
+                &poseidon_config,
+                &F_circuit
+            ),
+            Err(Error::InvalidParams(_))
+        ));
+        // mu and nu are bound to the R1CS
+        let mut other_vp = vp.clone();
+        other_vp.mu = 2;
+        let mut other_vp_bytes = vec![];
+        other_vp.export(&mut other_vp_bytes, &F_circuit).unwrap();
+        assert!(matches!(
+            VP::import::<_, GVar, GVar2, _>(
+                other_vp_bytes.as_slice(),
+                &poseidon_config,
+                &F_circuit
+            ),
+            Err(Error::InvalidParams(_))
+        ));
+        // prover params for other mu and nu are rejected
+        let mut other_pp = pp.clone();
+        other_pp.nu = 2;
+        let mut other_pp_bytes = vec![];
+        other_pp
+            .export(&mut other_pp_bytes, pp_hash, &F_circuit)
+            .unwrap();
+        assert!(matches!(
+            PP::import(other_pp_bytes.as_slice(), &F_circuit, &imported_vp),
+            Err(Error::InvalidParams(_))
+        ));
+
+        // prover params bound to a different pp_hash are rejected
+        let mut other_pp_bytes = vec![];
+        pp.export(&mut other_pp_bytes, pp_hash + Fr::from(1_u32), &F_circuit)
+            .unwrap();
+        assert!(matches!(
+            PP::import(other_pp_bytes.as_slice(), &F_circuit, &imported_vp),
+            Err(Error::InvalidParams(_))
+        ));
+
+        // prover params from a different setup are rejected, even if exported with the right
+        // pp_hash
+        let (other_pp, _) = N::preprocess(&mut rng, &prep_param).unwrap();
+        let mut other_pp_bytes = vec![];
+        other_pp
+            .export(&mut other_pp_bytes, pp_hash, &F_circuit)
+            .unwrap();
+        assert!(matches!(
+            PP::import(other_pp_bytes.as_slice(), &F_circuit, &imported_vp),
+            Err(Error::InvalidParams(_))
+        ));
+
+        // tampered prover keys are rejected
+        let mut tampered_pp = pp.clone();
+        tampered_pp.cs_pp.powers_of_g.to_mut()[1] = tampered_pp.cs_pp.powers_of_g[0];
+        let mut tampered_pp_bytes = vec![];
+        tampered_pp
+            .export(&mut tampered_pp_bytes, pp_hash, &F_circuit)
+            .unwrap();
+        assert!(matches!(
+            PP::import(tampered_pp_bytes.as_slice(), &F_circuit, &imported_vp),
+            Err(Error::InvalidParams(_))
+        ));
+        let mut tampered_pp = pp;
+        tampered_pp.cf_cs_pp.generators[0] = tampered_pp.cf_cs_pp.generators[1];
+        let mut tampered_pp_bytes = vec![];
+        tampered_pp
+            .export(&mut tampered_pp_bytes, pp_hash, &F_circuit)
+            .unwrap();
+        assert!(matches!(
+            PP::import(tampered_pp_bytes.as_slice(), &F_circuit, &imported_vp),
+            Err(Error::InvalidParams(_))
+        ));
+    }
 }
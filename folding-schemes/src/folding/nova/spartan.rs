@@ -0,0 +1,363 @@
+/// This file implements a [Spartan](https://eprint.iacr.org/2019/550.pdf)-style argument of
+/// knowledge for the Relaxed R1CS relation, used by the offchain Decider. It consists of two
+/// sum-checks, reducing the satisfiability of the Relaxed R1CS instance to evaluations of the
+/// multilinear extensions of `W` and `E`, which are then opened against the `cmW` and `cmE`
+/// commitments with the MultilinearIPA.
+///
+/// The verifier evaluates the multilinear extensions of the R1CS matrices by itself (there is no
+/// SPARK compilation), so verification is linear in the number of non-zero entries of the
+/// matrices.
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{log2, One, Zero};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::{CommittedInstance, Witness};
+use crate::arith::r1cs::R1CS;
+use crate::commitment::{
+    mle_ipa::{MultilinearIPA, Proof as MLEIPAProof},
+    pedersen::Params as PedersenParams,
+};
+use crate::transcript::Transcript;
+use crate::utils::sum_check::{structs::IOPProof as SumCheckProof, IOPSumCheck, SumCheck};
+use crate::utils::vec::{mat_vec_mul, SparseMatrix};
+use crate::utils::virtual_polynomial::{build_eq_x_r_vec, eq_eval, VPAuxInfo, VirtualPolynomial};
+use crate::Error;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Proof<C: CurveGroup> {
+    /// sum-check proving that Az∘Bz - u⋅Cz - E = 0
+    pub sc1_proof: SumCheckProof<C::ScalarField>,
+    /// claimed evaluations of the MLEs of Az, Bz, Cz at r_x
+    pub v_A: C::ScalarField,
+    pub v_B: C::ScalarField,
+    pub v_C: C::ScalarField,
+    /// sum-check proving the evaluations v_A, v_B, v_C
+    pub sc2_proof: SumCheckProof<C::ScalarField>,
+    /// openings of W at r_y and E at r_x, which contain the evaluations v_W and v_E
+    pub W_proof: MLEIPAProof<C>,
+    pub E_proof: MLEIPAProof<C>,
+}
+
+/// Dimensions of the padded Relaxed R1CS used by the argument: the rows are padded to a power of
+/// two, and the columns are rearranged as z' = (W, u, x), where both halves are padded to the
+/// same power of two, so that z̃'(r_y) = (1 - r_y[s_y-1]) ⋅ W̃(r_y') + r_y[s_y-1] ⋅ (u,x)~(r_y').
+struct Dims {
+    /// number of variables over the rows
+    s_x: usize,
+    /// number of variables over the (rearranged) columns
+    s_y: usize,
+    /// length of each half of z'
+    half: usize,
+    /// io length, ie. |(u, x)|
+    io_len: usize,
+}
+
+impl Dims {
+    fn new<F: PrimeField>(r1cs: &R1CS<F>) -> Self {
+        let io_len = 1 + r1cs.l;
+        let w_len = r1cs.A.n_cols - io_len;
+        let half = w_len.max(io_len).next_power_of_two();
+        Self {
+            s_x: log2(r1cs.A.n_rows.next_power_of_two()) as usize,
+            s_y: log2(half) as usize + 1,
+            half,
+            io_len,
+        }
+    }
+
+    /// maps the column index of z = (u, x, W) into the column index of z' = (W, u, x)
+    fn col(&self, j: usize) -> usize {
+        if j < self.io_len {
+            return self.half + j;
+        }
+        j - self.io_len
+    }
+}
+
+fn pad<F: PrimeField>(v: &[F], len: usize) -> Vec<F> {
+    let mut v = v.to_vec();
+    v.resize(len, F::zero());
+    v
+}
+
+fn inner_prod<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(a_i, b_i)| *a_i * b_i).sum()
+}
+
+/// returns Σ_M ρ_M ⋅ M(r_x, y) for all the y in the boolean hypercube of the rearranged columns,
+/// where `eq_rx` contains the evaluations of eq(r_x, ·)
+fn matrices_at_rx<F: PrimeField>(
+    dims: &Dims,
+    matrices: [&SparseMatrix<F>; 3],
+    rho: &[F],
+    eq_rx: &[F],
+) -> Vec<F> {
+    let mut M_rx = vec![F::zero(); 2 * dims.half];
+    for (M, rho_M) in matrices.iter().zip(rho) {
+        for (row, eq_rx_i) in M.coeffs.iter().zip(eq_rx) {
+            let c = *rho_M * eq_rx_i;
+            for &(value, col) in row.iter() {
+                M_rx[dims.col(col)] += c * value;
+            }
+        }
+    }
+    M_rx
+}
+
+fn mle<F: PrimeField>(n_vars: usize, v: Vec<F>) -> Arc<DenseMultilinearExtension<F>> {
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(n_vars, v))
+}
+
+/// Proves that the given Relaxed R1CS `CommittedInstance` is satisfied by the `Witness`. The
+/// commitments cmW and cmE are expected to be non-hiding Pedersen commitments computed with
+/// `params`.
+pub fn prove<C: CurveGroup, T: Transcript<C::ScalarField>>(
+    params: &PedersenParams<C>,
+    transcript: &mut T,
+    r1cs: &R1CS<C::ScalarField>,
+    U: &CommittedInstance<C>,
+    W: &Witness<C>,
+) -> Result<Proof<C>, Error>
+where
+    <C as Group>::ScalarField: Absorb,
+{
+    let dims = Dims::new(r1cs);
+    let m = 1 << dims.s_x;
+
+    transcript.absorb(&U);
+
+    let z: Vec<C::ScalarField> = [vec![U.u], U.x.to_vec(), W.W.to_vec()].concat();
+    let Az = pad(&mat_vec_mul(&r1cs.A, &z)?, m);
+    let Bz = pad(&mat_vec_mul(&r1cs.B, &z)?, m);
+    let Cz = pad(&mat_vec_mul(&r1cs.C, &z)?, m);
+    let E = pad(&W.E, m);
+
+    // sum-check #1: 0 = Σ_x eq(τ, x) ⋅ (Az(x) ⋅ Bz(x) - u ⋅ Cz(x) - E(x))
+    let tau = transcript.get_challenges(dims.s_x);
+    let eq_tau = mle(dims.s_x, build_eq_x_r_vec(&tau)?);
+    let Az_mle = mle(dims.s_x, Az.clone());
+    let Bz_mle = mle(dims.s_x, Bz.clone());
+    let Cz_mle = mle(dims.s_x, Cz.clone());
+    let E_mle = mle(dims.s_x, E.clone());
+
+    let mut g = VirtualPolynomial::<C::ScalarField>::new(dims.s_x);
+    g.add_mle_list([eq_tau.clone(), Az_mle, Bz_mle], C::ScalarField::one())?;
+    g.add_mle_list([eq_tau.clone(), Cz_mle], -U.u)?;
+    g.add_mle_list([eq_tau, E_mle], -C::ScalarField::one())?;
+
+    let sc1_proof = IOPSumCheck::<C::ScalarField, T>::prove(&g, transcript)
+        .map_err(|err| Error::SumCheckProveError(err.to_string()))?;
+    let r_x = sc1_proof.point.clone();
+
+    let eq_rx = build_eq_x_r_vec(&r_x)?;
+    let (v_A, v_B, v_C) = (
+        inner_prod(&Az, &eq_rx),
+        inner_prod(&Bz, &eq_rx),
+        inner_prod(&Cz, &eq_rx),
+    );
+    transcript.absorb(&vec![v_A, v_B, v_C]);
+
+    // sum-check #2: Σ_M ρ_M ⋅ v_M = Σ_y (Σ_M ρ_M ⋅ M(r_x, y)) ⋅ z'(y)
+    let rho = transcript.get_challenges(3);
+    let M_rx = matrices_at_rx(&dims, [&r1cs.A, &r1cs.B, &r1cs.C], &rho, &eq_rx);
+    let W_padded = pad(&W.W, dims.half);
+    let z_prime = [W_padded.clone(), pad(&z[..dims.io_len], dims.half)].concat();
+
+    let mut g2 = VirtualPolynomial::<C::ScalarField>::new(dims.s_y);
+    g2.add_mle_list(
+        [mle(dims.s_y, M_rx), mle(dims.s_y, z_prime)],
+        C::ScalarField::one(),
+    )?;
+    let sc2_proof = IOPSumCheck::<C::ScalarField, T>::prove(&g2, transcript)
+        .map_err(|err| Error::SumCheckProveError(err.to_string()))?;
+    let r_y = sc2_proof.point.clone();
+
+    // open W at r_y (without its last coordinate, which selects the W half of z') and E at r_x
+    let W_proof = MultilinearIPA::<C>::prove_eval(
+        params,
+        transcript,
+        &U.cmW,
+        &W_padded,
+        &W.rW,
+        &r_y[..dims.s_y - 1],
+        None,
+    )?;
+    let E_proof =
+        MultilinearIPA::<C>::prove_eval(params, transcript, &U.cmE, &E, &W.rE, &r_x, None)?;
+
+    Ok(Proof {
+        sc1_proof,
+        v_A,
+        v_B,
+        v_C,
+        sc2_proof,
+        W_proof,
+        E_proof,
+    })
+}
+
+/// Verifies the Spartan proof for the given Relaxed R1CS `CommittedInstance`.
+pub fn verify<C: CurveGroup, T: Transcript<C::ScalarField>>(
+    params: &PedersenParams<C>,
+    transcript: &mut T,
+    r1cs: &R1CS<C::ScalarField>,
+    U: &CommittedInstance<C>,
+    proof: &Proof<C>,
+) -> Result<(), Error>
+where
+    <C as Group>::ScalarField: Absorb,
+{
+    let dims = Dims::new(r1cs);
+    if U.x.len() != r1cs.l {
+        return Err(Error::NotSameLength(
+            "U.x".to_string(),
+            U.x.len(),
+            "r1cs.l".to_string(),
+            r1cs.l,
+        ));
+    }
+    // the sum-check verifier expects the proofs to contain a message per round
+    if proof.sc1_proof.proofs.len() != dims.s_x {
+        return Err(Error::NotExpectedLength(
+            proof.sc1_proof.proofs.len(),
+            dims.s_x,
+        ));
+    }
+    if proof.sc2_proof.proofs.len() != dims.s_y {
+        return Err(Error::NotExpectedLength(
+            proof.sc2_proof.proofs.len(),
+            dims.s_y,
+        ));
+    }
+
+    transcript.absorb(&U);
+
+    // sum-check #1
+    let tau = transcript.get_challenges(dims.s_x);
+    let sc1_subclaim = IOPSumCheck::<C::ScalarField, T>::verify(
+        C::ScalarField::zero(),
+        &proof.sc1_proof,
+        &VPAuxInfo {
+            max_degree: 3,
+            num_variables: dims.s_x,
+            phantom: PhantomData::<C::ScalarField>,
+        },
+        transcript,
+    )
+    .map_err(|err| Error::SumCheckVerifyError(err.to_string()))?;
+    let r_x = sc1_subclaim.point;
+
+    let v_E = proof.E_proof.1;
+    if eq_eval(&tau, &r_x)? * (proof.v_A * proof.v_B - U.u * proof.v_C - v_E)
+        != sc1_subclaim.expected_evaluation
+    {
+        return Err(Error::SumCheckVerifyError(
+            "sum-check #1 final evaluation mismatch".to_string(),
+        ));
+    }
+    transcript.absorb(&vec![proof.v_A, proof.v_B, proof.v_C]);
+
+    // sum-check #2
+    let rho = transcript.get_challenges(3);
+    let claim = rho[0] * proof.v_A + rho[1] * proof.v_B + rho[2] * proof.v_C;
+    let sc2_subclaim = IOPSumCheck::<C::ScalarField, T>::verify(
+        claim,
+        &proof.sc2_proof,
+        &VPAuxInfo {
+            max_degree: 2,
+            num_variables: dims.s_y,
+            phantom: PhantomData::<C::ScalarField>,
+        },
+        transcript,
+    )
+    .map_err(|err| Error::SumCheckVerifyError(err.to_string()))?;
+    let r_y = sc2_subclaim.point;
+    let (r_y_lo, r_y_hi) = (&r_y[..dims.s_y - 1], r_y[dims.s_y - 1]);
+
+    // evaluate Σ_M ρ_M ⋅ M(r_x, r_y), and z'(r_y) from v_W and the public (u, x)
+    let eq_rx = build_eq_x_r_vec(&r_x)?;
+    let eq_ry = build_eq_x_r_vec(&r_y)?;
+    let M_rx = matrices_at_rx(&dims, [&r1cs.A, &r1cs.B, &r1cs.C], &rho, &eq_rx);
+    let M_rx_ry = inner_prod(&M_rx, &eq_ry);
+
+    let io = pad(&[vec![U.u], U.x.to_vec()].concat(), dims.half);
+    let v_io = inner_prod(&io, &build_eq_x_r_vec(r_y_lo)?);
+    let v_W = proof.W_proof.1;
+    let z_ry = (C::ScalarField::one() - r_y_hi) * v_W + r_y_hi * v_io;
+    if M_rx_ry * z_ry != sc2_subclaim.expected_evaluation {
+        return Err(Error::SumCheckVerifyError(
+            "sum-check #2 final evaluation mismatch".to_string(),
+        ));
+    }
+
+    // check the claimed evaluations of W and E against their commitments
+    MultilinearIPA::<C>::verify_eval(params, transcript, &U.cmW, r_y_lo, &proof.W_proof)?;
+    MultilinearIPA::<C>::verify_eval(params, transcript, &U.cmE, &r_x, &proof.E_proof)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::arith::r1cs::tests::{get_test_r1cs, get_test_z};
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::{nifs::NIFS, traits::NovaR1CS};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_spartan_relaxed_r1cs() {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs::<Fr>();
+        let z = get_test_z(3);
+        let (w, x) = r1cs.split_z(&z);
+
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, r1cs.A.n_rows.max(w.len())).unwrap();
+
+        // build a relaxed instance by folding the instance with itself with a random r
+        let W = Witness::<Projective>::new::<false>(w, r1cs.A.n_rows, &mut rng);
+        let U = W
+            .commit::<Pedersen<Projective>, false>(&pedersen_params, x)
+            .unwrap();
+        let (T, cmT) = NIFS::<Projective, Pedersen<Projective>>::compute_cmT(
+            &pedersen_params,
+            &r1cs,
+            &W,
+            &U,
+            &W,
+            &U,
+        )
+        .unwrap();
+        let r = Fr::rand(&mut rng);
+        let (W_i1, U_i1) =
+            NIFS::<Projective, Pedersen<Projective>>::fold_instances(r, &W, &U, &W, &U, &T, cmT)
+                .unwrap();
+        r1cs.check_relaxed_instance_relation(&W_i1, &U_i1).unwrap();
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof = prove(&pedersen_params, &mut transcript_p, &r1cs, &U_i1, &W_i1).unwrap();
+
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        verify(&pedersen_params, &mut transcript_v, &r1cs, &U_i1, &proof).unwrap();
+
+        // a not satisfying witness can not produce a valid proof
+        let mut W_bad = W_i1.clone();
+        W_bad.E[0] += Fr::one();
+        let mut U_bad = U_i1.clone();
+        U_bad.cmE = Pedersen::<Projective>::commit(&pedersen_params, &W_bad.E, &W_bad.rE).unwrap();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof = prove(&pedersen_params, &mut transcript_p, &r1cs, &U_bad, &W_bad).unwrap();
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(verify(&pedersen_params, &mut transcript_v, &r1cs, &U_bad, &proof).is_err());
+    }
+}
@@ -1,9 +1,11 @@
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::{CurveGroup, Group};
-use ark_std::One;
+use ark_std::{rand::RngCore, One, UniformRand, Zero};
 
 use super::{CommittedInstance, Witness};
 use crate::arith::{r1cs::R1CS, Arith};
+use crate::commitment::CommitmentScheme;
+use crate::utils::vec::{hadamard, mat_vec_mul, vec_scalar_mul, vec_sub};
 use crate::Error;
 
 /// NovaR1CS extends R1CS methods with Nova specific methods
@@ -25,6 +27,14 @@ pub trait NovaR1CS<C: CurveGroup> {
         W: &Witness<C>,
         U: &CommittedInstance<C>,
     ) -> Result<(), Error>;
+
+    /// samples a random satisfying Relaxed R1CS instance (Witness and CommittedInstance) for the
+    /// current R1CS structure, used to randomize instances for zero-knowledge.
+    fn sample_witness_instance<CS: CommitmentScheme<C, H>, const H: bool>(
+        &self,
+        params: &CS::ProverParams,
+        rng: impl RngCore,
+    ) -> Result<(Witness<C>, CommittedInstance<C>), Error>;
 }
 
 impl<C: CurveGroup> NovaR1CS<C> for R1CS<C::ScalarField>
@@ -66,4 +76,39 @@ where
         let Z: Vec<C::ScalarField> = [vec![U.u], U.x.to_vec(), W.W.to_vec()].concat();
         rel_r1cs.check_relation(&Z)
     }
+    fn sample_witness_instance<CS: CommitmentScheme<C, H>, const H: bool>(
+        &self,
+        params: &CS::ProverParams,
+        mut rng: impl RngCore,
+    ) -> Result<(Witness<C>, CommittedInstance<C>), Error> {
+        let w_len = self.A.n_cols - 1 - self.l;
+        let u = C::ScalarField::rand(&mut rng);
+        let x: Vec<C::ScalarField> = (0..self.l)
+            .map(|_| C::ScalarField::rand(&mut rng))
+            .collect();
+        let W: Vec<C::ScalarField> = (0..w_len).map(|_| C::ScalarField::rand(&mut rng)).collect();
+
+        // E = Az ∘ Bz - u * Cz, so that the relaxed R1CS relation holds
+        let Z: Vec<C::ScalarField> = [vec![u], x.clone(), W.clone()].concat();
+        let Az = mat_vec_mul(&self.A, &Z)?;
+        let Bz = mat_vec_mul(&self.B, &Z)?;
+        let Cz = mat_vec_mul(&self.C, &Z)?;
+        let E = vec_sub(&hadamard(&Az, &Bz)?, &vec_scalar_mul(&Cz, &u))?;
+
+        let (rW, rE) = if H {
+            (
+                C::ScalarField::rand(&mut rng),
+                C::ScalarField::rand(&mut rng),
+            )
+        } else {
+            (C::ScalarField::zero(), C::ScalarField::zero())
+        };
+        let cmW = CS::commit(params, &W, &rW)?;
+        let cmE = CS::commit(params, &E, &rE)?;
+
+        Ok((
+            Witness { E, rE, W, rW },
+            CommittedInstance { cmE, u, cmW, x },
+        ))
+    }
 }
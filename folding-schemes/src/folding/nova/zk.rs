@@ -0,0 +1,371 @@
+/// This file implements the zero-knowledge IVC proof for Nova, following the randomization
+/// technique described in [Nova](https://eprint.iacr.org/2021/370.pdf) (and used in
+/// [HyperNova](https://eprint.iacr.org/2023/573.pdf)): the last running instance is folded with
+/// a random satisfying Relaxed R1CS instance, so that the witness sent to the verifier is
+/// uniformly distributed. The CycleFold running instance is randomized in the same way.
+///
+/// Notice that it is only zero-knowledge when the commitment schemes are hiding (`H = true`).
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb, CryptographicSponge};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use ark_std::{One, Zero};
+
+use super::{
+    circuits::ChallengeGadget, nifs::NIFS, traits::NovaR1CS, CommittedInstance, Nova,
+    VerifierParams, Witness,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::CF2;
+use crate::frontend::FCircuit;
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::Error;
+
+/// RandomizedIVCProof contains the values that the verifier needs to check the IVC, in which the
+/// witnesses are randomized, so that they reveal nothing beyond `z_0`, `z_i` and `i`.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RandomizedIVCProof<C1: CurveGroup, C2: CurveGroup> {
+    pub U_i: CommittedInstance<C1>,
+    pub u_i: CommittedInstance<C1>,
+    /// commitment to the cross-terms of folding U_i with u_i into U_f
+    pub cmT: C1,
+    /// random Relaxed R1CS instance
+    pub U_r: CommittedInstance<C1>,
+    /// commitment to the cross-terms of folding U_f with U_r into U'
+    pub cmT_r: C1,
+    /// randomized witness of U'
+    pub W: Witness<C1>,
+    pub cf_U_i: CommittedInstance<C2>,
+    /// random CycleFold Relaxed R1CS instance
+    pub cf_U_r: CommittedInstance<C2>,
+    /// commitment to the cross-terms of folding cf_U_i with cf_U_r into cf_U'
+    pub cf_cmT: C2,
+    /// randomized witness of cf_U'
+    pub cf_W: Witness<C2>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2> Nova<C1, GC1, C2, GC2, FC, CS1, CS2, true>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    /// Computes the zero-knowledge proof of the current IVC state, which can be checked with
+    /// [`RandomizedIVCProof::verify`] instead of `FoldingScheme::verify`.
+    /// The proof contains the running instances `U_i` and `cf_U_i` as they are, which hide the
+    /// computation since `prove_step` blinds the commitments to the cross-terms folded into their
+    /// `cmE`.
+    pub fn prove_zk(&self, mut rng: impl RngCore) -> Result<RandomizedIVCProof<C1, C2>, Error> {
+        if self.i.is_zero() {
+            return Err(Error::NotEnoughSteps);
+        }
+        let poseidon_config = &self.poseidon_config;
+
+        // fold the running instance with the incoming one, U_f = NIFS.V(U_i, u_i)
+        let (T, rT, cmT) = NIFS::<C1, CS1, true>::compute_cmT_with_blinding(
+            &self.cs_pp,
+            &self.r1cs,
+            &self.W_i,
+            &self.U_i,
+            &self.w_i,
+            &self.u_i,
+            &mut rng,
+        )?;
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let r = challenge::<C1, _>(&mut transcript, self.pp_hash, &self.U_i, &self.u_i, cmT)?;
+        let W_f = NIFS::<C1, CS1, true>::fold_witness(r, &self.W_i, &self.w_i, &T, rT)?;
+        let U_f = NIFS::<C1, CS1, true>::verify(r, &self.U_i, &self.u_i, &cmT);
+
+        // fold U_f with a random satisfying instance, U' = NIFS.V(U_f, U_r)
+        let (W_r, U_r) = self
+            .r1cs
+            .sample_witness_instance::<CS1, true>(&self.cs_pp, &mut rng)?;
+        let (T_r, rT_r, cmT_r) = NIFS::<C1, CS1, true>::compute_cmT_with_blinding(
+            &self.cs_pp,
+            &self.r1cs,
+            &W_f,
+            &U_f,
+            &W_r,
+            &U_r,
+            &mut rng,
+        )?;
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let r_r = challenge::<C1, _>(&mut transcript, self.pp_hash, &U_f, &U_r, cmT_r)?;
+        let W = NIFS::<C1, CS1, true>::fold_witness(r_r, &W_f, &W_r, &T_r, rT_r)?;
+
+        // randomize the CycleFold running instance, cf_U' = NIFS.V(cf_U_i, cf_U_r)
+        let (cf_W_r, cf_U_r) = self
+            .cf_r1cs
+            .sample_witness_instance::<CS2, true>(&self.cf_cs_pp, &mut rng)?;
+        let (cf_T, cf_rT, cf_cmT) = NIFS::<C2, CS2, true>::compute_cmT_with_blinding(
+            &self.cf_cs_pp,
+            &self.cf_r1cs,
+            &self.cf_W_i,
+            &self.cf_U_i,
+            &cf_W_r,
+            &cf_U_r,
+            &mut rng,
+        )?;
+        let mut cf_transcript =
+            PoseidonSponge::<C2::ScalarField>::new(&poseidon_canonical_config::<C2::ScalarField>());
+        let cf_r = challenge::<C2, _>(
+            &mut cf_transcript,
+            cf_pp_hash::<C1, C2>(self.pp_hash),
+            &self.cf_U_i,
+            &cf_U_r,
+            cf_cmT,
+        )?;
+        let cf_W = NIFS::<C2, CS2, true>::fold_witness(cf_r, &self.cf_W_i, &cf_W_r, &cf_T, cf_rT)?;
+
+        Ok(RandomizedIVCProof {
+            U_i: self.U_i.clone(),
+            u_i: self.u_i.clone(),
+            cmT,
+            U_r,
+            cmT_r,
+            W,
+            cf_U_i: self.cf_U_i.clone(),
+            cf_U_r,
+            cf_cmT,
+            cf_W,
+        })
+    }
+}
+
+impl<C1, C2> RandomizedIVCProof<C1, C2>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// Verifies the zero-knowledge IVC proof for the given `z_0`, `z_i` and number of steps.
+    /// Analogously to `FoldingScheme::verify`, this method does not check the commitments
+    /// openings.
+    pub fn verify<CS1, CS2, const H: bool>(
+        &self,
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        num_steps: C1::ScalarField,
+    ) -> Result<(), Error>
+    where
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+    {
+        if num_steps.is_zero() {
+            return Err(Error::NotEnoughSteps);
+        }
+        if self.u_i.x.len() != 2 || self.U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = self.U_i.hash(&sponge, pp_hash, num_steps, z_0, z_i);
+        if expected_u_i_x != self.u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = self.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != self.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check u_i.cmE==0, u_i.u==1 (=u_i is a un-relaxed instance)
+        if !self.u_i.cmE.is_zero() || !self.u_i.u.is_one() {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // recompute U' = NIFS.V(NIFS.V(U_i, u_i), U_r)
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let r = challenge::<C1, _>(&mut transcript, pp_hash, &self.U_i, &self.u_i, self.cmT)?;
+        let U_f = NIFS::<C1, CS1, H>::verify(r, &self.U_i, &self.u_i, &self.cmT);
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let r_r = challenge::<C1, _>(&mut transcript, pp_hash, &U_f, &self.U_r, self.cmT_r)?;
+        let U = NIFS::<C1, CS1, H>::verify(r_r, &U_f, &self.U_r, &self.cmT_r);
+
+        // recompute cf_U' = NIFS.V(cf_U_i, cf_U_r)
+        let mut cf_transcript =
+            PoseidonSponge::<C2::ScalarField>::new(&poseidon_canonical_config::<C2::ScalarField>());
+        let cf_r = challenge::<C2, _>(
+            &mut cf_transcript,
+            cf_pp_hash::<C1, C2>(pp_hash),
+            &self.cf_U_i,
+            &self.cf_U_r,
+            self.cf_cmT,
+        )?;
+        let cf_U = NIFS::<C2, CS2, H>::verify(cf_r, &self.cf_U_i, &self.cf_U_r, &self.cf_cmT);
+
+        // check RelaxedR1CS satisfiability of the randomized instances
+        vp.r1cs.check_relaxed_instance_relation(&self.W, &U)?;
+        vp.cf_r1cs
+            .check_relaxed_instance_relation(&self.cf_W, &cf_U)?;
+
+        Ok(())
+    }
+}
+
+pub(super) fn challenge<C: CurveGroup, T: Transcript<C::ScalarField>>(
+    transcript: &mut T,
+    pp_hash: C::ScalarField,
+    ci1: &CommittedInstance<C>,
+    ci2: &CommittedInstance<C>,
+    cmT: C,
+) -> Result<C::ScalarField, Error>
+where
+    <C as CurveGroup>::BaseField: PrimeField,
+    <C as Group>::ScalarField: Absorb,
+{
+    let r_bits = ChallengeGadget::<C>::get_challenge_native(
+        transcript,
+        pp_hash,
+        ci1.clone(),
+        ci2.clone(),
+        cmT,
+    );
+    C::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits)).ok_or(Error::OutOfBounds)
+}
+
+/// returns the pp_hash represented in the CycleFold curve's scalar field
+pub(super) fn cf_pp_hash<C1: CurveGroup, C2: CurveGroup>(
+    pp_hash: C1::ScalarField,
+) -> C2::ScalarField {
+    C2::ScalarField::from_le_bytes_mod_order(&pp_hash.into_bigint().to_bytes_le())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_prove_zk() {
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&(pp, vp.clone()), F_circuit, z_0.clone()).unwrap();
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, vec![], None).unwrap();
+        }
+
+        let proof = nova.prove_zk(&mut rng).unwrap();
+        proof
+            .verify(&vp, z_0.clone(), nova.z_i.clone(), nova.i)
+            .unwrap();
+
+        // the randomized witness differs from the folding of the non-randomized instances
+        let proof2 = nova.prove_zk(&mut rng).unwrap();
+        assert_ne!(proof.W, proof2.W);
+        assert_ne!(proof.cf_W, proof2.cf_W);
+
+        // wrong number of steps or state
+        assert!(proof
+            .verify(&vp, z_0.clone(), nova.z_i.clone(), nova.i + Fr::one())
+            .is_err());
+        assert!(proof.verify(&vp, z_0.clone(), z_0, nova.i).is_err());
+
+        // tampered randomized witness
+        let mut wrong_proof = proof;
+        wrong_proof.W.W[0] += Fr::one();
+        assert!(wrong_proof
+            .verify(&vp, vec![Fr::from(3_u32)], nova.z_i.clone(), nova.i)
+            .is_err());
+    }
+
+    #[test]
+    fn test_prove_step_blinds_cmE() {
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        // two runs of the same computation
+        let z_0 = vec![Fr::from(3_u32)];
+        let runs = (0..2)
+            .map(|_| {
+                let mut nova = N::init(&(pp.clone(), vp.clone()), F_circuit, z_0.clone())?;
+                for _ in 0..3 {
+                    nova.prove_step(&mut rng, vec![], None)?;
+                }
+                let (U_i, u_i, cf_U_i) = nova.instances();
+                N::verify(
+                    vp.clone(),
+                    z_0.clone(),
+                    nova.state(),
+                    nova.i,
+                    U_i,
+                    u_i,
+                    cf_U_i,
+                )?;
+                Ok(nova)
+            })
+            .collect::<Result<Vec<_>, Error>>()
+            .unwrap();
+
+        assert_eq!(runs[0].z_i, runs[1].z_i);
+        assert_ne!(runs[0].U_i.cmE, runs[1].U_i.cmE);
+        assert_ne!(runs[0].cf_U_i.cmE, runs[1].cf_U_i.cmE);
+        // the cmE of the running instance is not the unblinded commitment to its error term
+        for nova in runs.iter() {
+            let unblinded_cmE =
+                Pedersen::<Projective, true>::commit(&pp.cs_pp, &nova.W_i.E, &Fr::zero()).unwrap();
+            assert!(!nova.W_i.rE.is_zero());
+            assert_ne!(nova.U_i.cmE, unblinded_cmE);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+/// This file implements the header used when exporting the prover & verifier params of the folding
+/// schemes, so that they can be loaded by other processes without rerunning `preprocess`.
+///
+/// The exported params are prefixed by a [`ParamsHeader`], containing the version of the format,
+/// the `pp_hash` of the params and a fingerprint of the `FCircuit` for which they were generated.
+/// When importing the params, the header is checked against the `FCircuit` in use, and the
+/// `pp_hash` against the one of the imported verifier params. Additionally, the imported prover
+/// params are checked against the verifier params (see [`check_cs_params`]), since they are not
+/// part of the `pp_hash`.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb, CryptographicSponge};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::Zero;
+use sha3::{Digest, Sha3_256};
+use std::io::{Read, Write};
+
+use crate::arith::r1cs::extract_r1cs;
+use crate::commitment::CommitmentScheme;
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::Error;
+
+/// Version of the params format, to be increased when the serialization of the params changes.
+pub const PARAMS_VERSION: u8 = 2;
+
+/// Header prepended to the exported prover & verifier params.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ParamsHeader<F: PrimeField> {
+    pub version: u8,
+    /// hash of the public params, see `VerifierParams::pp_hash`
+    pub pp_hash: F,
+    /// SHA3-256 digest of the FCircuit, see [`circuit_fingerprint`]
+    pub circuit_fingerprint: Vec<u8>,
+}
+
+impl<F: PrimeField> ParamsHeader<F> {
+    pub fn new<FC: FCircuit<F>>(pp_hash: F, F_circuit: &FC) -> Result<Self, Error> {
+        Ok(Self {
+            version: PARAMS_VERSION,
+            pp_hash,
+            circuit_fingerprint: circuit_fingerprint(F_circuit)?,
+        })
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        Ok(self.serialize_uncompressed(writer)?)
+    }
+
+    /// reads the header, checking that its version is supported and that it was generated for the
+    /// given FCircuit
+    pub fn read<R: Read, FC: FCircuit<F>>(reader: R, F_circuit: &FC) -> Result<Self, Error> {
+        let header = Self::deserialize_with_mode(reader, Compress::No, Validate::Yes)?;
+        if header.version != PARAMS_VERSION {
+            return Err(Error::InvalidParams(format!(
+                "unsupported version {}, expected {}",
+                header.version, PARAMS_VERSION
+            )));
+        }
+        if header.circuit_fingerprint != circuit_fingerprint(F_circuit)? {
+            return Err(Error::InvalidParams(
+                "the params were generated for a different FCircuit".to_string(),
+            ));
+        }
+        Ok(header)
+    }
+
+    /// checks that the header is bound to the given pp_hash
+    pub fn check_pp_hash(&self, pp_hash: F) -> Result<(), Error> {
+        if self.pp_hash != pp_hash {
+            return Err(Error::InvalidParams(
+                "pp_hash does not match the imported params".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Computes the fingerprint of the given FCircuit, as the SHA3-256 digest of its state length,
+/// external inputs length, and the R1CS of its step constraints.
+pub fn circuit_fingerprint<F: PrimeField, FC: FCircuit<F>>(
+    F_circuit: &FC,
+) -> Result<Vec<u8>, Error> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let z_i =
+        Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(vec![F::zero(); F_circuit.state_len()]))?;
+    let external_inputs = Vec::<FpVar<F>>::new_witness(cs.clone(), || {
+        Ok(vec![F::zero(); F_circuit.external_inputs_len()])
+    })?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+    cs.finalize();
+    let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+    let r1cs = extract_r1cs::<F>(&cs);
+
+    let mut hasher = Sha3_256::new();
+    hasher.update((F_circuit.state_len() as u64).to_le_bytes());
+    hasher.update((F_circuit.external_inputs_len() as u64).to_le_bytes());
+    let mut r1cs_bytes = Vec::new();
+    r1cs.serialize_uncompressed(&mut r1cs_bytes)?;
+    hasher.update(r1cs_bytes);
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Checks that the prover params of the commitment scheme `CS` match its verifier params, by
+/// committing to a fixed vector of length `len` with the prover params and verifying the opening
+/// proof with the verifier params. This rejects prover params that were tampered with or that come
+/// from a different setup than the verifier params.
+pub fn check_cs_params<C, CS, const H: bool>(
+    prover_params: &CS::ProverParams,
+    verifier_params: &CS::VerifierParams,
+    len: usize,
+) -> Result<(), Error>
+where
+    C: CurveGroup,
+    CS: CommitmentScheme<C, H>,
+    <C as Group>::ScalarField: Absorb,
+{
+    let mismatch =
+        |_| Error::InvalidParams("the prover params do not match the verifier params".to_string());
+    let poseidon_config = poseidon_canonical_config::<C::ScalarField>();
+    let v: Vec<C::ScalarField> = (1..=len as u64).map(C::ScalarField::from).collect();
+    let blind = C::ScalarField::zero();
+
+    let cm = CS::commit(prover_params, &v, &blind).map_err(mismatch)?;
+    let transcript_p = &mut PoseidonSponge::<C::ScalarField>::new(&poseidon_config);
+    let proof = CS::prove(prover_params, transcript_p, &cm, &v, &blind, None).map_err(mismatch)?;
+    let transcript_v = &mut PoseidonSponge::<C::ScalarField>::new(&poseidon_config);
+    CS::verify(verifier_params, transcript_v, &cm, &proof).map_err(mismatch)
+}
@@ -1,26 +1,53 @@
-use ark_crypto_primitives::sponge::CryptographicSponge;
-use ark_ec::CurveGroup;
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain};
 use ark_r1cs_std::{
     alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    groups::GroupOpsBounds,
     poly::polynomial::univariate::dense::DensePolynomialVar,
+    prelude::CurveVar,
+    R1CSVar, ToBitsGadget, ToConstraintFieldGadget,
 };
-use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{One, Zero};
+use core::marker::PhantomData;
 
 use super::{
     folding::lagrange_polys,
     utils::{all_powers_var, betas_star_var, exponential_powers_var},
-    CommittedInstanceVar,
+    CommittedInstance, CommittedInstanceVar,
 };
 use crate::{
-    folding::circuits::nonnative::affine::NonNativeAffineVar, transcript::TranscriptVar,
+    folding::{
+        circuits::{
+            cyclefold::{
+                cf_io_len, CycleFoldChallengeGadget, CycleFoldCommittedInstanceVar, NIFSFullGadget,
+            },
+            nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+            CF1, CF2,
+        },
+        nova::CommittedInstance as CycleFoldCommittedInstance,
+    },
+    frontend::FCircuit,
+    transcript::TranscriptVar,
     utils::gadgets::VectorGadget,
 };
 
 pub struct FoldingGadget {}
 
 impl FoldingGadget {
+    /// In-circuit counterpart of `Folding::verify`. Returns the folded instance (whose `phi` is
+    /// left to be computed in CycleFold) together with the evaluations L_i(gamma), which are the
+    /// coefficients used to fold `phi`.
+    #[allow(clippy::type_complexity)]
     pub fn fold_committed_instance<C: CurveGroup, S: CryptographicSponge>(
         transcript: &mut impl TranscriptVar<C::ScalarField, S>,
         // running instance
@@ -30,7 +57,7 @@ impl FoldingGadget {
         // polys from P
         F_coeffs: Vec<FpVar<C::ScalarField>>,
         K_coeffs: Vec<FpVar<C::ScalarField>>,
-    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+    ) -> Result<(CommittedInstanceVar<C>, Vec<FpVar<C::ScalarField>>), SynthesisError> {
         let t = instance.betas.len();
         let n = F_coeffs.len();
 
@@ -96,14 +123,357 @@ impl FoldingGadget {
         }
 
         // return the folded instance
-        Ok(CommittedInstanceVar {
-            betas: betas_star,
-            // phi will be computed in CycleFold
-            phi: NonNativeAffineVar::new_constant(ConstraintSystemRef::None, C::zero())?,
-            e: e_star,
-            u: u_star,
-            x: x_star,
-        })
+        Ok((
+            CommittedInstanceVar {
+                betas: betas_star,
+                // phi will be computed in CycleFold
+                phi: NonNativeAffineVar::new_constant(ConstraintSystemRef::None, C::zero())?,
+                e: e_star,
+                u: u_star,
+                x: x_star,
+            },
+            L_X_evals,
+        ))
+    }
+}
+
+impl<C: CurveGroup> CommittedInstanceVar<C>
+where
+    C::ScalarField: Absorb,
+{
+    /// hash implements the committed instance hash compatible with the native implementation from
+    /// CommittedInstance.hash.
+    /// Returns `H(i, z_0, z_i, U_i)`, where `i` can be `i` but also `i+1`, and `U_i` is the
+    /// `CommittedInstance`.
+    /// Additionally it returns the vector of the field elements from the self parameters, so they
+    /// can be reused in other gadgets avoiding recalculating (reconstraining) them.
+    #[allow(clippy::type_complexity)]
+    pub fn hash<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        self,
+        sponge: &T,
+        pp_hash: FpVar<CF1<C>>,
+        i: FpVar<CF1<C>>,
+        z_0: Vec<FpVar<CF1<C>>>,
+        z_i: Vec<FpVar<CF1<C>>>,
+    ) -> Result<(FpVar<CF1<C>>, Vec<FpVar<CF1<C>>>), SynthesisError> {
+        let mut sponge = sponge.clone();
+        let U_vec = self.to_sponge_field_elements()?;
+        sponge.absorb(&pp_hash)?;
+        sponge.absorb(&i)?;
+        sponge.absorb(&z_0)?;
+        sponge.absorb(&z_i)?;
+        sponge.absorb(&U_vec)?;
+        Ok((sponge.squeeze_field_elements(1)?.pop().unwrap(), U_vec))
+    }
+}
+
+/// AugmentedFCircuit implements the F' circuit (augmented F) of ProtoGalaxy+CycleFold. At each
+/// step it folds the running instance `U_i` with `k` incoming instances (`u_i` plus the `k-1`
+/// instances `us` provided by the prover), where the check of the folding of the commitments
+/// `phi` is delegated to the CycleFold circuit, which folds them by the lagrange coefficients
+/// L_j(gamma).
+#[derive(Debug, Clone)]
+pub struct AugmentedFCircuit<
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    FC: FCircuit<CF1<C1>>,
+> where
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    pub _gc2: PhantomData<GC2>,
+    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub pp_hash: Option<CF1<C1>>,
+    pub t: usize, // length of the betas of the running instance
+    pub k: usize, // number of incoming instances folded at each step
+    pub i: Option<CF1<C1>>,
+    pub i_usize: Option<usize>,
+    pub z_0: Option<Vec<C1::ScalarField>>,
+    pub z_i: Option<Vec<C1::ScalarField>>,
+    pub external_inputs: Option<Vec<C1::ScalarField>>,
+    pub U_i: Option<CommittedInstance<C1>>,
+    pub u_i_phi: Option<C1>,
+    pub us: Option<Vec<CommittedInstance<C1>>>, // other incoming instances to be folded besides u_i
+    pub U_i1_phi: Option<C1>,
+    pub F_coeffs: Option<Vec<CF1<C1>>>,
+    pub K_coeffs: Option<Vec<CF1<C1>>>,
+    pub F: FC,              // F circuit
+    pub x: Option<CF1<C1>>, // public input (u_{i+1}.x[0])
+
+    // cyclefold verifier on C1
+    pub cf_u_i_cmW: Option<C2>, // input, cf_u_i.cmW
+    pub cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
+    pub cf_x: Option<CF1<C1>>,  // public input (u_{i+1}.x[1])
+    pub cf_cmT: Option<C2>,
+}
+
+impl<C1: CurveGroup, C2: CurveGroup, GC2: CurveVar<C2, CF2<C2>>, FC: FCircuit<CF1<C1>>>
+    AugmentedFCircuit<C1, C2, GC2, FC>
+where
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    pub fn empty(
+        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        F_circuit: FC,
+        t: usize,
+        k: usize,
+    ) -> Self {
+        Self {
+            _gc2: PhantomData,
+            poseidon_config: poseidon_config.clone(),
+            pp_hash: None,
+            t,
+            k,
+            i: None,
+            i_usize: None,
+            z_0: None,
+            z_i: None,
+            external_inputs: None,
+            U_i: None,
+            u_i_phi: None,
+            us: None,
+            U_i1_phi: None,
+            F_coeffs: None,
+            K_coeffs: None,
+            F: F_circuit,
+            x: None,
+            // cyclefold values
+            cf_u_i_cmW: None,
+            cf_U_i: None,
+            cf_x: None,
+            cf_cmT: None,
+        }
+    }
+}
+
+impl<C1, C2, GC2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, GC2, FC>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<CF1<C1>>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_0
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_i
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let external_inputs = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .external_inputs
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.external_inputs_len()]))
+        })?;
+
+        let U_dummy = CommittedInstance::<C1>::dummy_running(2, self.t);
+        let u_dummy = CommittedInstance::<C1>::dummy_incoming(2);
+
+        let U_i = CommittedInstanceVar::<C1>::new_witness(cs.clone(), || {
+            Ok(self.U_i.unwrap_or(U_dummy.clone()))
+        })?;
+        // the other incoming instances are not relaxed, so only their phi & x are allocated
+        let us = self
+            .us
+            .unwrap_or(vec![u_dummy; self.k - 1])
+            .iter()
+            .map(|u| {
+                Ok(CommittedInstanceVar {
+                    phi: NonNativeAffineVar::new_witness(cs.clone(), || Ok(u.phi))?,
+                    betas: vec![],
+                    e: FpVar::<CF1<C1>>::zero(),
+                    u: FpVar::<CF1<C1>>::one(),
+                    x: Vec::new_witness(cs.clone(), || Ok(u.x.clone()))?,
+                })
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        let U_i1_phi = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_phi.unwrap_or_else(C1::zero))
+        })?;
+        // F(X) has degree t and K(X) has degree d*k-(k+1), with d=2 for R1CS
+        let F_coeffs = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self.F_coeffs.unwrap_or(vec![CF1::<C1>::zero(); self.t + 1]))
+        })?;
+        let K_coeffs = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self.K_coeffs.unwrap_or(vec![CF1::<C1>::zero(); self.k]))
+        })?;
+
+        // the CycleFold circuit folds the point at infinity, U_i.phi and the k incoming phis
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(cf_io_len(self.k + 2));
+        let cf_U_i = CycleFoldCommittedInstanceVar::<C2, GC2>::new_witness(cs.clone(), || {
+            Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
+        })?;
+        let cf_cmT = GC2::new_witness(cs.clone(), || Ok(self.cf_cmT.unwrap_or_else(C2::zero)))?;
+
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+
+        // get z_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 =
+            self.F
+                .generate_step_constraints(cs.clone(), i_usize, z_i.clone(), external_inputs)?;
+
+        let is_basecase = i.is_zero()?;
+
+        // Primary Part
+        // P.1. Compute u_i.x
+        // u_i.x[0] = H(i, z_0, z_i, U_i)
+        let (u_i_x, _) = U_i.clone().hash(
+            &sponge,
+            pp_hash.clone(),
+            i.clone(),
+            z_0.clone(),
+            z_i.clone(),
+        )?;
+        // u_i.x[1] = H(cf_U_i)
+        let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+
+        // P.2. Construct u_i
+        let u_i = CommittedInstanceVar::<C1> {
+            // u_i.phi is provided by the prover as witness
+            phi: NonNativeAffineVar::new_witness(cs.clone(), || {
+                Ok(self.u_i_phi.unwrap_or_else(C1::zero))
+            })?,
+            betas: vec![],
+            // incoming instances are not relaxed
+            e: FpVar::<CF1<C1>>::zero(),
+            u: FpVar::<CF1<C1>>::one(),
+            // u_i.x is computed in step 1
+            x: vec![u_i_x, cf_u_i_x],
+        };
+        let all_us = [vec![u_i], us].concat();
+
+        // P.3. Folding.verify, obtains U_{i+1} by folding U_i & [u_i, us].
+        // Notice that FoldingGadget::fold_committed_instance does not fold phi. We set `U_i1.phi`
+        // to the unconstrained witness `U_i1_phi`, its correctness is checked on the other curve.
+        let mut transcript = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        transcript.absorb(&pp_hash)?;
+        let (mut U_i1, L_X_evals) = FoldingGadget::fold_committed_instance(
+            &mut transcript,
+            &U_i,
+            &all_us,
+            F_coeffs,
+            K_coeffs,
+        )?;
+        U_i1.phi = U_i1_phi;
+
+        // P.4.a compute and check the first output of F'
+        // Base case: u_{i+1}.x[0] == H((1, z_0, z_{i+1}, U_{\bot})
+        // Non-base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{i+1})
+        let (u_i1_x, _) = U_i1.clone().hash(
+            &sponge,
+            pp_hash.clone(),
+            i + FpVar::<CF1<C1>>::one(),
+            z_0.clone(),
+            z_i1.clone(),
+        )?;
+        let (u_i1_x_base, _) = CommittedInstanceVar::new_constant(cs.clone(), U_dummy)?.hash(
+            &sponge,
+            pp_hash.clone(),
+            FpVar::<CF1<C1>>::one(),
+            z_0.clone(),
+            z_i1.clone(),
+        )?;
+        let x = FpVar::new_input(cs.clone(), || Ok(self.x.unwrap_or(u_i1_x_base.value()?)))?;
+        x.enforce_equal(&is_basecase.select(&u_i1_x_base, &u_i1_x)?)?;
+
+        // convert the lagrange coefficients L_j(gamma) to `NonNativeUintVar`
+        let L_X_evals_nonnat = L_X_evals
+            .iter()
+            .map(|L_j| {
+                let mut bits = L_j.to_bits_le()?;
+                bits.resize(
+                    bits.len().max(C1::BaseField::MODULUS_BIT_SIZE as usize),
+                    Boolean::FALSE,
+                );
+                Ok(NonNativeUintVar::from(&bits))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        // CycleFold part
+        // C.1. Compute cf_u_i.x
+        // The CycleFold circuit computes phi_{i+1} = 0 + \sum_j L_j(gamma) * phi_j, where the
+        // first point (the point at infinity) is added so that L_0(gamma) is not assumed to be 1.
+        let zero = NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?;
+        let cf_x: Vec<NonNativeUintVar<CF2<C2>>> = [
+            L_X_evals_nonnat,
+            vec![zero.x, zero.y],
+            vec![U_i.phi.x, U_i.phi.y],
+            all_us
+                .iter()
+                .flat_map(|u| vec![u.phi.x.clone(), u.phi.y.clone()])
+                .collect(),
+            vec![U_i1.phi.x, U_i1.phi.y],
+        ]
+        .concat();
+
+        // C.2. Construct `cf_u_i`
+        let cf_u_i = CycleFoldCommittedInstanceVar::<C2, GC2> {
+            // cf_u_i.cmE = 0. Notice that we enforce cmE to be equal to 0 since it is allocated
+            // as 0.
+            cmE: GC2::zero(),
+            // cf_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf_u_i.cmW is provided by the prover as witness
+            cmW: GC2::new_witness(cs.clone(), || Ok(self.cf_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf_u_i.x is computed in step 1
+            x: cf_x,
+        };
+
+        // C.3. nifs.verify (fold_committed_instance), obtains cf_U_{i+1} by folding cf_u_i & cf_U_i.
+        // compute cf_r = H(cf_u_i, cf_U_i, cf_cmT)
+        let cf_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i_vec,
+            cf_u_i.clone(),
+            cf_cmT.clone(),
+        )?;
+        // Convert cf_r_bits to a `NonNativeFieldVar`
+        let cf_r_nonnat = {
+            let mut bits = cf_r_bits.clone();
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        // Fold cf_u_i & cf_U_i into cf_U_{i+1}
+        let cf_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
+            cf_r_bits,
+            cf_r_nonnat,
+            cf_cmT,
+            cf_U_i,
+            cf_u_i,
+        )?;
+
+        // Back to Primary Part
+        // P.4.b compute and check the second output of F'
+        // Base case: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-base case: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let (cf_u_i1_x, _) = cf_U_i1.clone().hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_base, _) =
+            CycleFoldCommittedInstanceVar::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = FpVar::new_input(cs.clone(), || {
+            Ok(self.cf_x.unwrap_or(cf_u_i1_x_base.value()?))
+        })?;
+        cf_x.enforce_equal(&is_basecase.select(&cf_u_i1_x_base, &cf_u_i1_x)?)?;
+
+        Ok(())
     }
 }
 
@@ -136,7 +506,7 @@ mod tests {
         let mut transcript_p = PoseidonSponge::new(&poseidon_config);
         let mut transcript_v = PoseidonSponge::new(&poseidon_config);
 
-        let (_, _, F_coeffs, K_coeffs) = Folding::<Projective>::prove(
+        let (_, _, F_coeffs, K_coeffs, L_X_evals) = Folding::<Projective>::prove(
             &mut transcript_p,
             &r1cs,
             &instance,
@@ -161,7 +531,7 @@ mod tests {
         let F_coeffs_var = Vec::new_witness(cs.clone(), || Ok(F_coeffs))?;
         let K_coeffs_var = Vec::new_witness(cs.clone(), || Ok(K_coeffs))?;
 
-        let folded_instance_var = FoldingGadget::fold_committed_instance(
+        let (folded_instance_var, L_X_evals_var) = FoldingGadget::fold_committed_instance(
             &mut transcript_var,
             &instance_var,
             &instances_var,
@@ -172,6 +542,7 @@ mod tests {
         assert_eq!(folded_instance.e, folded_instance_var.e.value()?);
         assert_eq!(folded_instance.u, folded_instance_var.u.value()?);
         assert_eq!(folded_instance.x, folded_instance_var.x.value()?);
+        assert_eq!(L_X_evals, L_X_evals_var.value()?);
         assert!(cs.is_satisfied()?);
 
         Ok(())
@@ -6,7 +6,7 @@ use ark_poly::{
     univariate::{DensePolynomial, SparsePolynomial},
     DenseUVPolynomial, EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial,
 };
-use ark_std::{cfg_into_iter, log2, Zero};
+use ark_std::{cfg_into_iter, log2, One, Zero};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::marker::PhantomData;
 
@@ -32,7 +32,10 @@ where
     <C as CurveGroup>::BaseField: Absorb,
 {
     #![allow(clippy::type_complexity)]
-    /// implements the non-interactive Prover from the folding scheme described in section 4
+    /// implements the non-interactive Prover from the folding scheme described in section 4.
+    /// Besides the folded instance & witness and the coefficients of F(X) and K(X), it returns
+    /// the evaluations L_i(gamma) of the lagrange polynomials, which are the coefficients used to
+    /// fold the commitments.
     pub fn prove(
         transcript: &mut impl Transcript<C::ScalarField>,
         r1cs: &R1CS<C::ScalarField>,
@@ -48,6 +51,7 @@ where
             Witness<C::ScalarField>,
             Vec<C::ScalarField>, // F_X coeffs
             Vec<C::ScalarField>, // K_X coeffs
+            Vec<C::ScalarField>, // L_X evals at gamma
         ),
         Error,
     > {
@@ -62,6 +66,7 @@ where
         let d = 2; // for the moment hardcoded to 2 since it only supports R1CS
         let k = vec_instances.len();
         let t = instance.betas.len();
+        let m = r1cs.A.n_rows;
         let n = r1cs.A.n_cols;
 
         let z = [vec![instance.u], instance.x.clone(), w.w.clone()].concat();
@@ -74,8 +79,14 @@ where
                 n,
             ));
         }
-        if log2(n) as usize != t {
-            return Err(Error::NotEqual);
+        // the betas must cover the (padded) number of constraints
+        if (log2(m) as usize) > t {
+            return Err(Error::NotSameLength(
+                "instance.betas.len()".to_string(),
+                t,
+                "log2(r1cs.A.n_rows)".to_string(),
+                log2(m) as usize,
+            ));
         }
         if !(k + 1).is_power_of_two() {
             return Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(k)));
@@ -88,13 +99,19 @@ where
         let delta = transcript.get_challenge();
         let deltas = exponential_powers(delta, t);
 
-        let f_z = eval_f(r1cs, &z)?;
+        // pad f(z) with zeroes (ie. trivially satisfied constraints) up to 2^t elements, so that
+        // the binary tree is full
+        let mut f_z = eval_f(r1cs, &z)?;
+        f_z.resize(1 << t, C::ScalarField::zero());
 
         // F(X)
         let F_X: SparsePolynomial<C::ScalarField> =
             calc_f_from_btree(&f_z, &instance.betas, &deltas).expect("Error calculating F[x]");
-        let F_X_dense = DensePolynomial::from(F_X.clone());
-        transcript.absorb(&F_X_dense.coeffs);
+        // F(X) has degree t, its coefficients are padded to t+1 so that the amount of elements
+        // absorbed by the transcript (and allocated in-circuit) does not depend on the values
+        let mut F_coeffs = DensePolynomial::from(F_X.clone()).coeffs;
+        F_coeffs.resize(t + 1, C::ScalarField::zero());
+        transcript.absorb(&F_coeffs);
 
         let alpha = transcript.get_challenge();
 
@@ -107,7 +124,7 @@ where
         // sanity check: check that the new randomized instance (the original instance but with
         // 'refreshed' randomness) satisfies the relation.
         #[cfg(test)]
-        tests::check_instance(
+        check_instance(
             r1cs,
             &CommittedInstance {
                 phi: instance.phi,
@@ -189,8 +206,11 @@ where
         if !remainder.is_zero() {
             return Err(Error::ProtoGalaxy(ProtoGalaxyError::RemainderNotZero));
         }
+        // K(X) has degree d*k-(k+1), pad its coefficients as done with F(X)
+        let mut K_coeffs = K_X.coeffs.clone();
+        K_coeffs.resize(d * k - k, C::ScalarField::zero());
 
-        transcript.absorb(&K_X.coeffs);
+        transcript.absorb(&K_coeffs);
 
         let gamma = transcript.get_challenge();
 
@@ -229,8 +249,9 @@ where
                 w: w_star,
                 r_w: r_w_star,
             },
-            F_X_dense.coeffs,
-            K_X.coeffs,
+            F_coeffs,
+            K_coeffs,
+            L_X_evals,
         ))
     }
 
@@ -380,6 +401,51 @@ fn calc_f_from_btree<F: PrimeField>(
     Ok(layers[root_index][0].clone())
 }
 
+/// checks that the running instance satisfies the relation, ie. that
+/// e == \sum_i pow_i(betas) * f_i(z)
+pub fn check_instance<C: CurveGroup>(
+    r1cs: &R1CS<C::ScalarField>,
+    instance: &CommittedInstance<C>,
+    w: &Witness<C::ScalarField>,
+) -> Result<(), Error> {
+    let z = [vec![instance.u], instance.x.clone(), w.w.clone()].concat();
+    let f_z = eval_f(r1cs, &z)?; // f(z)
+
+    if instance.betas.len() < log2(f_z.len()) as usize {
+        return Err(Error::NotSameLength(
+            "instance.betas.len()".to_string(),
+            instance.betas.len(),
+            "log2(f_z.len())".to_string(),
+            log2(f_z.len()) as usize,
+        ));
+    }
+
+    let mut r = C::ScalarField::zero();
+    for (i, f_z_i) in f_z.iter().enumerate() {
+        r += pow_i(i, &instance.betas) * f_z_i;
+    }
+    if instance.e == r {
+        return Ok(());
+    }
+    Err(Error::NotSatisfied)
+}
+
+/// checks that the incoming (non-relaxed) instance satisfies the R1CS relation, ie. f(z) == 0
+pub fn check_incoming_instance<C: CurveGroup>(
+    r1cs: &R1CS<C::ScalarField>,
+    instance: &CommittedInstance<C>,
+    w: &Witness<C::ScalarField>,
+) -> Result<(), Error> {
+    if instance.u != C::ScalarField::one() || !instance.e.is_zero() {
+        return Err(Error::R1CSUnrelaxedFail);
+    }
+    let z = [vec![instance.u], instance.x.clone(), w.w.clone()].concat();
+    if !is_zero_vec(&eval_f(r1cs, &z)?) {
+        return Err(Error::NotSatisfied);
+    }
+    Ok(())
+}
+
 // lagrange_polys method from caulk: https://github.com/caulk-crypto/caulk/tree/8210b51fb8a9eef4335505d1695c44ddc7bf8170/src/multi/setup.rs#L300
 pub fn lagrange_polys<F: PrimeField>(
     domain_n: GeneralEvaluationDomain<F>,
@@ -416,34 +482,6 @@ pub mod tests {
     use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
     use crate::transcript::poseidon::poseidon_canonical_config;
 
-    pub(crate) fn check_instance<C: CurveGroup>(
-        r1cs: &R1CS<C::ScalarField>,
-        instance: &CommittedInstance<C>,
-        w: &Witness<C::ScalarField>,
-    ) -> Result<(), Error> {
-        let z = [vec![instance.u], instance.x.clone(), w.w.clone()].concat();
-
-        if instance.betas.len() != log2(z.len()) as usize {
-            return Err(Error::NotSameLength(
-                "instance.betas.len()".to_string(),
-                instance.betas.len(),
-                "log2(z.len())".to_string(),
-                log2(z.len()) as usize,
-            ));
-        }
-
-        let f_z = eval_f(r1cs, &z)?; // f(z)
-
-        let mut r = C::ScalarField::zero();
-        for (i, f_z_i) in f_z.iter().enumerate() {
-            r += pow_i(i, &instance.betas) * f_z_i;
-        }
-        if instance.e == r {
-            return Ok(());
-        }
-        Err(Error::NotSatisfied)
-    }
-
     #[test]
     fn test_pow_i() {
         let mut rng = ark_std::test_rng();
@@ -549,15 +587,16 @@ pub mod tests {
         let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
         let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
 
-        let (folded_instance, folded_witness, F_coeffs, K_coeffs) = Folding::<Projective>::prove(
-            &mut transcript_p,
-            &r1cs,
-            &instance,
-            &witness,
-            &instances,
-            &witnesses,
-        )
-        .unwrap();
+        let (folded_instance, folded_witness, F_coeffs, K_coeffs, _) =
+            Folding::<Projective>::prove(
+                &mut transcript_p,
+                &r1cs,
+                &instance,
+                &witness,
+                &instances,
+                &witnesses,
+            )
+            .unwrap();
 
         // verifier
         let folded_instance_v = Folding::<Projective>::verify(
@@ -598,7 +637,7 @@ pub mod tests {
             // generate the instances to be fold
             let (_, _, witnesses, instances) = prepare_inputs(k);
 
-            let (folded_instance, folded_witness, F_coeffs, K_coeffs) =
+            let (folded_instance, folded_witness, F_coeffs, K_coeffs, _) =
                 Folding::<Projective>::prove(
                     &mut transcript_p,
                     &r1cs,
@@ -1,23 +1,51 @@
-use std::borrow::Borrow;
-
 /// Implements the scheme described in [ProtoGalaxy](https://eprint.iacr.org/2023/1106.pdf)
-use ark_ec::CurveGroup;
-use ark_ff::PrimeField;
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     fields::fp::FpVar,
+    groups::GroupOpsBounds,
+    prelude::CurveVar,
+    ToConstraintFieldGadget,
 };
-use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, Namespace, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{borrow::Borrow, log2, marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
+use std::time::Instant;
 use thiserror::Error;
+use tracing::info_span;
 
-use super::circuits::nonnative::affine::NonNativeAffineVar;
+use super::circuits::{
+    cyclefold::{fold_cyclefold_circuit, CycleFoldCircuit},
+    nonnative::affine::NonNativeAffineVar,
+    CF2,
+};
+use super::nova::{
+    get_r1cs_from_cs, traits::NovaR1CS, CommittedInstance as CycleFoldCommittedInstance,
+    PreprocessorParam, Witness as CycleFoldWitness,
+};
+use crate::{
+    arith::r1cs::{extract_w_x, R1CS},
+    commitment::CommitmentScheme,
+    frontend::FCircuit,
+    transcript::Transcript,
+    utils::{get_cm_coordinates, pp_hash, timed},
+    Error, FoldingScheme, MultiFolding, StepMetrics,
+};
 
 pub mod circuits;
 pub mod folding;
 pub mod traits;
 pub(crate) mod utils;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+use circuits::AugmentedFCircuit;
+use folding::{check_incoming_instance, check_instance, Folding};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CommittedInstance<C: CurveGroup> {
     phi: C,
     betas: Vec<C::ScalarField>,
@@ -26,6 +54,57 @@ pub struct CommittedInstance<C: CurveGroup> {
     x: Vec<C::ScalarField>,
 }
 
+impl<C: CurveGroup> CommittedInstance<C> {
+    /// returns a running instance with all the values set to zero, with `t` betas
+    pub fn dummy_running(io_len: usize, t: usize) -> Self {
+        Self {
+            phi: C::zero(),
+            betas: vec![C::ScalarField::zero(); t],
+            e: C::ScalarField::zero(),
+            u: C::ScalarField::zero(),
+            x: vec![C::ScalarField::zero(); io_len],
+        }
+    }
+
+    /// returns an incoming (non-relaxed) instance, which has no betas, e=0 and u=1
+    pub fn dummy_incoming(io_len: usize) -> Self {
+        Self {
+            phi: C::zero(),
+            betas: vec![],
+            e: C::ScalarField::zero(),
+            u: C::ScalarField::one(),
+            x: vec![C::ScalarField::zero(); io_len],
+        }
+    }
+}
+
+impl<C: CurveGroup> CommittedInstance<C>
+where
+    C::ScalarField: Absorb,
+    C::BaseField: PrimeField,
+{
+    /// hash implements the committed instance hash compatible with the gadget implemented in
+    /// protogalaxy/circuits.rs::CommittedInstanceVar.hash.
+    /// Returns `H(i, z_0, z_i, U_i)`, where `i` can be `i` but also `i+1`, and `U_i` is the
+    /// `CommittedInstance`.
+    pub fn hash<T: Transcript<C::ScalarField>>(
+        &self,
+        sponge: &T,
+        pp_hash: C::ScalarField,
+        i: C::ScalarField,
+        z_0: Vec<C::ScalarField>,
+        z_i: Vec<C::ScalarField>,
+    ) -> C::ScalarField {
+        let mut sponge = sponge.clone();
+        sponge.absorb(&pp_hash);
+        sponge.absorb(&i);
+        sponge.absorb(&z_0);
+        sponge.absorb(&z_i);
+        sponge.absorb(&self);
+        sponge.squeeze_field_elements(1)[0]
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CommittedInstanceVar<C: CurveGroup> {
     phi: NonNativeAffineVar<C>,
@@ -57,12 +136,43 @@ impl<C: CurveGroup> AllocVar<CommittedInstance<C>, C::ScalarField> for Committed
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Witness<F: PrimeField> {
     w: Vec<F>,
     r_w: F,
 }
 
+impl<F: PrimeField> Witness<F> {
+    pub fn new<const H: bool>(w: Vec<F>, mut rng: impl RngCore) -> Self {
+        let r_w = if H { F::rand(&mut rng) } else { F::zero() };
+        Self { w, r_w }
+    }
+
+    pub fn dummy(r1cs: &R1CS<F>) -> Self {
+        Self {
+            w: vec![F::zero(); r1cs.A.n_cols - r1cs.l - 1],
+            r_w: F::zero(),
+        }
+    }
+
+    /// commits to the witness, returning the incoming (non-relaxed) instance with the given
+    /// public inputs
+    pub fn commit<C: CurveGroup<ScalarField = F>, CS: CommitmentScheme<C, H>, const H: bool>(
+        &self,
+        params: &CS::ProverParams,
+        x: Vec<F>,
+    ) -> Result<CommittedInstance<C>, Error> {
+        let phi = CS::commit(params, &self.w, &self.r_w)?;
+        Ok(CommittedInstance {
+            phi,
+            betas: vec![],
+            e: F::zero(),
+            u: F::one(),
+            x,
+        })
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ProtoGalaxyError {
     #[error("The remainder from G(X)-F(α)*L_0(X)) / Z(X) should be zero")]
@@ -76,3 +186,933 @@ pub enum ProtoGalaxyError {
     #[error("The lengths of β and δ do not equal: |β| = {0}, |δ|={0}")]
     WrongLenBetas(usize, usize),
 }
+
+#[derive(Debug, Clone)]
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    pub cs_pp: CS1::ProverParams,
+    pub cf_cs_pp: CS2::ProverParams,
+    /// number of incoming instances to be folded at each step
+    pub k: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifierParams<
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    const H: bool,
+> {
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    pub r1cs: R1CS<C1::ScalarField>,
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub cs_vp: CS1::VerifierParams,
+    pub cf_cs_vp: CS2::VerifierParams,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters of ProtoGalaxy
+    pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
+        pp_hash::<C1, C2, CS1, CS2, H>(
+            &self.r1cs,
+            &self.cf_r1cs,
+            &self.cs_vp,
+            &self.cf_cs_vp,
+            &self.poseidon_config,
+        )
+    }
+}
+
+/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit, for
+/// the given number `k` of incoming instances folded at each step. Returns also `t`, the number
+/// of betas of the running instance.
+/// The size of the AugmentedFCircuit depends on `t`, while `t` has to be at least
+/// `log2(r1cs.A.n_rows)`, so `t` is increased until the betas cover the constraints of the
+/// circuit.
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, GC1, C2, GC2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuit: FC,
+    k: usize,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>, usize), Error>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    let mut t = 1;
+    let r1cs = loop {
+        let augmented_F_circuit =
+            AugmentedFCircuit::<C1, C2, GC2, FC>::empty(poseidon_config, F_circuit.clone(), t, k);
+        let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
+        let log_m = log2(r1cs.A.n_rows) as usize;
+        if log_m <= t {
+            break r1cs;
+        }
+        t = log_m;
+    };
+    // the points are folded by the lagrange coefficients L_j(gamma), which are full field
+    // elements, so the CycleFold circuit uses all their bits
+    let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty_with_r_bits_len(
+        k + 2,
+        C1::ScalarField::MODULUS_BIT_SIZE as usize,
+    );
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs, t))
+}
+
+/// Implements ProtoGalaxy+CycleFold's IVC, described in
+/// [ProtoGalaxy](https://eprint.iacr.org/2023/1106.pdf) and
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait.
+/// At each step, the running instance is folded with `k` incoming instances: the one from the
+/// previous step, and `k-1` other incoming instances provided to `prove_step`.
+#[derive(Clone, Debug)]
+pub struct ProtoGalaxy<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    _gc1: PhantomData<GC1>,
+    _c2: PhantomData<C2>,
+    _gc2: PhantomData<GC2>,
+    /// R1CS of the Augmented Function circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub k: usize, // number of incoming instances to be folded at each step
+    pub i: C1::ScalarField,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// ProtoGalaxy instances
+    pub W_i: Witness<C1::ScalarField>,
+    pub U_i: CommittedInstance<C1>,
+    pub w_i: Witness<C1::ScalarField>,
+    pub u_i: CommittedInstance<C1>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// IVCProof contains all the values needed to verify the ProtoGalaxy+CycleFold IVC, so that it
+/// can be serialized and verified through `FoldingScheme::verify_proof`.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1: CurveGroup, C2: CurveGroup> {
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// number of steps
+    pub i: C1::ScalarField,
+    /// ProtoGalaxy instances
+    pub W_i: Witness<C1::ScalarField>,
+    pub U_i: CommittedInstance<C1>,
+    pub w_i: Witness<C1::ScalarField>,
+    pub u_i: CommittedInstance<C1>,
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> MultiFolding<C1, C2, FC>
+    for ProtoGalaxy<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1::ScalarField>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1::ScalarField>);
+    type MultiInstance = Vec<Self::IncomingInstance>;
+
+    /// ProtoGalaxy folds a single running instance at each step, so there are no 'other' running
+    /// instances to be created.
+    fn new_running_instance(
+        &self,
+        _rng: impl RngCore,
+        _state: Vec<C1::ScalarField>,
+        _external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<Self::RunningInstance, Error> {
+        Err(Error::NotSupported(
+            "ProtoGalaxy folds a single running instance at each step".to_string(),
+        ))
+    }
+
+    /// Creates a new incoming instance for the given state, which satisfies the ProtoGalaxy's
+    /// R1CS. This method can be used to generate the 'other' incoming instances to be folded in
+    /// the multi-folding step.
+    fn new_incoming_instance(
+        &self,
+        mut rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<Self::IncomingInstance, Error> {
+        let t = self.U_i.betas.len();
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+
+        // prepare the initial dummy instances
+        let U_i = CommittedInstance::<C1>::dummy_running(2, t);
+        let cf_U_i = CycleFoldCommittedInstance::<C2>::dummy(self.cf_r1cs.l);
+
+        let z_i1 = self
+            .F
+            .step_native(0, state.clone(), external_inputs.clone())?;
+
+        // compute u_{i+1}.x
+        let U_i1 = CommittedInstance::<C1>::dummy_running(2, t);
+        let u_i1_x = U_i1.hash(
+            &sponge,
+            self.pp_hash,
+            C1::ScalarField::one(), // i+1, where i=0
+            self.z_0.clone(),
+            z_i1,
+        );
+        let cf_u_i1_x = cf_U_i.hash_cyclefold(&sponge, self.pp_hash);
+
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, GC2, FC> {
+            pp_hash: Some(self.pp_hash),
+            i: Some(C1::ScalarField::zero()),
+            i_usize: Some(0),
+            z_0: Some(self.z_0.clone()),
+            z_i: Some(state),
+            external_inputs: Some(external_inputs),
+            U_i: Some(U_i),
+            U_i1_phi: Some(U_i1.phi),
+            x: Some(u_i1_x),
+            cf_x: Some(cf_u_i1_x),
+            ..AugmentedFCircuit::empty(&self.poseidon_config, self.F.clone(), t, self.k)
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        augmented_f_circuit.generate_constraints(cs.clone())?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x::<C1::ScalarField>(&cs);
+
+        let w = Witness::<C1::ScalarField>::new::<H>(w, &mut rng);
+        let u = w.commit::<C1, CS1, H>(&self.cs_pp, x)?;
+
+        #[cfg(test)]
+        check_incoming_instance(&self.r1cs, &u, &w)?;
+
+        Ok((u, w))
+    }
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
+    for ProtoGalaxy<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    /// Reuse Nova's PreprocessorParam, together with the number `k` of incoming instances to be
+    /// folded at each step, where `k+1` must be a power of two.
+    type PreprocessorParam = (PreprocessorParam<C1, C2, FC, CS1, CS2, H>, usize);
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1::ScalarField>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1::ScalarField>);
+    type MultiCommittedInstanceWithWitness = Vec<Self::IncomingInstance>;
+    type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
+
+    fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        let (prep_param, k) = prep_param;
+        if *k < 1 || !(k + 1).is_power_of_two() {
+            return Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(*k)));
+        }
+
+        let (r1cs, cf_r1cs, _) = get_r1cs::<C1, GC1, C2, GC2, FC>(
+            &prep_param.poseidon_config,
+            prep_param.F.clone(),
+            *k,
+        )?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let cs_pp: CS1::ProverParams;
+        let cs_vp: CS1::VerifierParams;
+        let cf_cs_pp: CS2::ProverParams;
+        let cf_cs_vp: CS2::VerifierParams;
+        if prep_param.cs_pp.is_some()
+            && prep_param.cf_cs_pp.is_some()
+            && prep_param.cs_vp.is_some()
+            && prep_param.cf_cs_vp.is_some()
+        {
+            cs_pp = prep_param.clone().cs_pp.unwrap();
+            cs_vp = prep_param.clone().cs_vp.unwrap();
+            cf_cs_pp = prep_param.clone().cf_cs_pp.unwrap();
+            cf_cs_vp = prep_param.clone().cf_cs_vp.unwrap();
+        } else {
+            (cs_pp, cs_vp) = CS1::setup(&mut rng, r1cs.A.n_cols - r1cs.l - 1)?;
+            (cf_cs_pp, cf_cs_vp) = CS2::setup(&mut rng, cf_r1cs.A.n_cols - cf_r1cs.l - 1)?;
+        }
+
+        let pp = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+            k: *k,
+        };
+        let vp = VerifierParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    /// Initializes the ProtoGalaxy+CycleFold's IVC for the given parameters and initial state
+    /// `z_0`.
+    fn init(
+        params: &(Self::ProverParam, Self::VerifierParam),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+        if pp.k < 1 || !(pp.k + 1).is_power_of_two() {
+            return Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(
+                pp.k,
+            )));
+        }
+
+        // prepare the circuits to obtain their R1CS, together with the number of betas of the
+        // running instance
+        let (r1cs, cf_r1cs, t) =
+            get_r1cs::<C1, GC1, C2, GC2, FC>(&pp.poseidon_config, F.clone(), pp.k)?;
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances
+        let W_dummy = Witness::<C1::ScalarField>::dummy(&r1cs);
+        let U_dummy = CommittedInstance::<C1>::dummy_running(r1cs.l, t);
+        let w_dummy = W_dummy.clone();
+        let u_dummy = CommittedInstance::<C1>::dummy_incoming(r1cs.l);
+        let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_instance();
+
+        // W_dummy=W_0 is a 'dummy witness', all zeroes, but with the size corresponding to the
+        // R1CS that we're working with.
+        Ok(Self {
+            _gc1: PhantomData,
+            _c2: PhantomData,
+            _gc2: PhantomData,
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            k: pp.k,
+            i: C1::ScalarField::zero(),
+            z_0: z_0.clone(),
+            z_i: z_0,
+            W_i: W_dummy,
+            U_i: U_dummy,
+            w_i: w_dummy,
+            u_i: u_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+        })
+    }
+
+    /// Implements IVC.P of ProtoGalaxy+CycleFold
+    #[tracing::instrument(skip_all, name = "protogalaxy_prove_step")]
+    fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: Vec<C1::ScalarField>,
+        other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<StepMetrics, Error> {
+        let start = Instant::now();
+        let mut metrics = StepMetrics::default();
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+
+        // recall, k is the number of all the incoming instances, including the one from the
+        // previous step which is not part of the 'other_instances', hence the +1.
+        let other_instances = match other_instances {
+            Some(other_instances) => other_instances,
+            None if self.k == 1 => vec![],
+            None => return Err(Error::MissingOtherInstances),
+        };
+        if other_instances.len() + 1 != self.k {
+            return Err(Error::NotSameLength(
+                "other_instances.len()".to_string(),
+                other_instances.len(),
+                "protogalaxy.k - 1".to_string(),
+                self.k - 1,
+            ));
+        }
+        let (us, ws): (Vec<CommittedInstance<C1>>, Vec<Witness<C1::ScalarField>>) =
+            other_instances.into_iter().unzip();
+        // the AugmentedFCircuit takes the other instances as incoming (non-relaxed) instances
+        if us
+            .iter()
+            .any(|u| u.u != C1::ScalarField::one() || !u.e.is_zero() || !u.betas.is_empty())
+        {
+            return Err(Error::R1CSUnrelaxedFail);
+        }
+
+        if self.z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+        if external_inputs.len() != self.F.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "F.external_inputs_len()".to_string(),
+                self.F.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let mut i_bytes: [u8; 8] = [0; 8];
+        i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+        let i_usize: usize = usize::from_le_bytes(i_bytes);
+
+        let z_i1 = timed(
+            info_span!("step_native"),
+            &mut metrics.witness_generation,
+            || {
+                self.F
+                    .step_native(i_usize, self.z_i.clone(), external_inputs.clone())
+            },
+        )?;
+
+        let t = self.U_i.betas.len();
+        // u_{i+1}.x[0] = H(i+1, z_0, z_{i+1}, U_{i+1})
+        let u_i1_x: C1::ScalarField;
+        // u_{i+1}.x[1] = H(cf_U_{i+1})
+        let cf_u_i1_x: C1::ScalarField;
+        let (U_i1, W_i1);
+        let augmented_f_circuit: AugmentedFCircuit<C1, C2, GC2, FC>;
+
+        if self.i == C1::ScalarField::zero() {
+            W_i1 = Witness::<C1::ScalarField>::dummy(&self.r1cs);
+            U_i1 = CommittedInstance::<C1>::dummy_running(self.r1cs.l, t);
+
+            u_i1_x = U_i1.hash(
+                &sponge,
+                self.pp_hash,
+                C1::ScalarField::one(),
+                self.z_0.clone(),
+                z_i1.clone(),
+            );
+
+            // hash the initial (dummy) CycleFold instance, which is used as the 2nd public
+            // input in the AugmentedFCircuit
+            cf_u_i1_x = self.cf_U_i.hash_cyclefold(&sponge, self.pp_hash);
+
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, GC2, FC> {
+                pp_hash: Some(self.pp_hash),
+                i: Some(C1::ScalarField::zero()),
+                i_usize: Some(0),
+                z_0: Some(self.z_0.clone()),
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs),
+                U_i: Some(self.U_i.clone()),
+                u_i_phi: Some(self.u_i.phi),
+                us: Some(us),
+                U_i1_phi: Some(U_i1.phi),
+                x: Some(u_i1_x),
+                cf_x: Some(cf_u_i1_x),
+                ..AugmentedFCircuit::empty(&self.poseidon_config, self.F.clone(), t, self.k)
+            };
+        } else {
+            let mut transcript_p: PoseidonSponge<C1::ScalarField> =
+                PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+            transcript_p.absorb(&self.pp_hash);
+            let (F_coeffs, K_coeffs, L_X_evals);
+            (U_i1, W_i1, F_coeffs, K_coeffs, L_X_evals) =
+                timed(info_span!("folding"), &mut metrics.folding, || {
+                    Folding::<C1>::prove(
+                        &mut transcript_p,
+                        &self.r1cs,
+                        &self.U_i,
+                        &self.W_i,
+                        &[vec![self.u_i.clone()], us.clone()].concat(),
+                        &[vec![self.w_i.clone()], ws].concat(),
+                    )
+                })?;
+
+            // sanity check: check the folded instance relation
+            #[cfg(test)]
+            check_instance(&self.r1cs, &U_i1, &W_i1)?;
+
+            u_i1_x = U_i1.hash(
+                &sponge,
+                self.pp_hash,
+                self.i + C1::ScalarField::one(),
+                self.z_0.clone(),
+                z_i1.clone(),
+            );
+
+            // the commitments are folded by the lagrange coefficients L_j(gamma), so the
+            // CycleFold circuit uses all their bits
+            let L_X_evals_bits: Vec<Vec<bool>> = L_X_evals
+                .iter()
+                .map(|L_j| {
+                    let mut bits = L_j.into_bigint().to_bits_le();
+                    bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
+                    bits
+                })
+                .collect();
+            // when the scalar field is larger than the base field (eg. Pallas), L_j(gamma) may
+            // not fit in the base field, in which case the step can not be proven
+            let L_X_evals_Fq: Vec<C1::BaseField> = L_X_evals_bits
+                .iter()
+                .map(|bits| {
+                    C1::BaseField::from_bigint(BigInteger::from_bits_le(bits))
+                        .ok_or(Error::OutOfBounds)
+                })
+                .collect::<Result<_, Error>>()?;
+
+            // CycleFold part:
+            // get the vector used as public inputs 'x' in the CycleFold circuit.
+            // The CycleFold circuit computes U_{i+1}.phi = 0 + \sum_j L_j(gamma) * phi_j, where
+            // the first point is the point at infinity, so that all the points are multiplied by
+            // their lagrange coefficient. Its public input is:
+            // x = [L_0, L_1, ..., L_k, 0, U_i.phi, u_i.phi, us[0].phi, ..., U_{i+1}.phi],
+            // where each point is in fact its coordinates.
+            let cf_u_i_x = [
+                L_X_evals_Fq,
+                get_cm_coordinates(&C1::zero()),
+                get_cm_coordinates(&self.U_i.phi),
+                get_cm_coordinates(&self.u_i.phi),
+                us.iter().flat_map(|u| get_cm_coordinates(&u.phi)).collect(),
+                get_cm_coordinates(&U_i1.phi),
+            ]
+            .concat();
+
+            let cf_circuit = CycleFoldCircuit::<C1, GC1> {
+                _gc: PhantomData,
+                n_points: self.k + 2,
+                r_bits: Some(L_X_evals_bits),
+                points: Some(
+                    [
+                        vec![C1::zero(), self.U_i.phi, self.u_i.phi],
+                        us.iter().map(|u| u.phi).collect(),
+                    ]
+                    .concat(),
+                ),
+                x: Some(cf_u_i_x.clone()),
+            };
+
+            let (_cf_w_i, cf_u_i, cf_W_i1, cf_U_i1, cf_cmT, _) =
+                timed(info_span!("cyclefold"), &mut metrics.cyclefold, || {
+                    fold_cyclefold_circuit::<C1, GC1, C2, GC2, FC, CS1, CS2, H>(
+                        self.k + 2,
+                        &mut transcript_p,
+                        self.cf_r1cs.clone(),
+                        self.cf_cs_pp.clone(),
+                        self.pp_hash,
+                        self.cf_W_i.clone(), // CycleFold running instance witness
+                        self.cf_U_i.clone(), // CycleFold running instance
+                        cf_u_i_x,
+                        cf_circuit,
+                        &mut rng,
+                    )
+                })?;
+
+            cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, self.pp_hash);
+
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, GC2, FC> {
+                _gc2: PhantomData,
+                poseidon_config: self.poseidon_config.clone(),
+                pp_hash: Some(self.pp_hash),
+                t,
+                k: self.k,
+                i: Some(self.i),
+                i_usize: Some(i_usize),
+                z_0: Some(self.z_0.clone()),
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs),
+                U_i: Some(self.U_i.clone()),
+                u_i_phi: Some(self.u_i.phi),
+                us: Some(us),
+                U_i1_phi: Some(U_i1.phi),
+                F_coeffs: Some(F_coeffs),
+                K_coeffs: Some(K_coeffs),
+                F: self.F.clone(),
+                x: Some(u_i1_x),
+
+                // cyclefold values
+                cf_u_i_cmW: Some(cf_u_i.cmW),
+                cf_U_i: Some(self.cf_U_i.clone()),
+                cf_x: Some(cf_u_i1_x),
+                cf_cmT: Some(cf_cmT),
+            };
+
+            // assign the next round instances
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+        }
+
+        let (w_i1, x_i1) = timed(
+            info_span!("witness_generation"),
+            &mut metrics.witness_generation,
+            || -> Result<_, Error> {
+                let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+                augmented_f_circuit.generate_constraints(cs.clone())?;
+
+                #[cfg(test)]
+                assert!(cs.is_satisfied()?);
+
+                let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+                Ok(extract_w_x::<C1::ScalarField>(&cs))
+            },
+        )?;
+        if x_i1[0] != u_i1_x || x_i1[1] != cf_u_i1_x {
+            return Err(Error::NotEqual);
+        }
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.w_i = Witness::<C1::ScalarField>::new::<H>(w_i1, &mut rng);
+        self.u_i = timed(info_span!("commitment"), &mut metrics.commitment, || {
+            self.w_i.commit::<C1, CS1, H>(&self.cs_pp, x_i1)
+        })?;
+        self.W_i = W_i1;
+        self.U_i = U_i1;
+
+        #[cfg(test)]
+        {
+            check_instance(&self.r1cs, &self.U_i, &self.W_i)?;
+            check_incoming_instance(&self.r1cs, &self.u_i, &self.w_i)?;
+            self.cf_r1cs
+                .check_relaxed_instance_relation(&self.cf_W_i, &self.cf_U_i)?;
+        }
+
+        metrics.total = start.elapsed();
+        Ok(metrics)
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn num_constraints(&self) -> (usize, usize) {
+        (self.r1cs.A.n_rows, self.cf_r1cs.A.n_rows)
+    }
+
+    fn instances(
+        &self,
+    ) -> (
+        Self::RunningInstance,
+        Self::IncomingInstance,
+        Self::CFInstance,
+    ) {
+        (
+            (self.U_i.clone(), self.W_i.clone()),
+            (self.u_i.clone(), self.w_i.clone()),
+            (self.cf_U_i.clone(), self.cf_W_i.clone()),
+        )
+    }
+
+    /// Implements IVC.V of ProtoGalaxy+CycleFold. Notice that this method does not include the
+    /// commitments verification, which is done in the Decider.
+    fn verify(
+        vp: Self::VerifierParam,
+        z_0: Vec<C1::ScalarField>, // initial state
+        z_i: Vec<C1::ScalarField>, // last state
+        num_steps: C1::ScalarField,
+        running_instance: Self::RunningInstance,
+        incoming_instance: Self::IncomingInstance,
+        cyclefold_instance: Self::CFInstance,
+    ) -> Result<(), Error> {
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        let (U_i, W_i) = running_instance;
+        let (u_i, w_i) = incoming_instance;
+        let (cf_U_i, cf_W_i) = cyclefold_instance;
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, z_0, z_i.clone());
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check the running instance relation
+        check_instance(&vp.r1cs, &U_i, &W_i)?;
+        // check the incoming instance relation
+        check_incoming_instance(&vp.r1cs, &u_i, &w_i)?;
+
+        // check CycleFold's RelaxedR1CS satisfiability
+        vp.cf_r1cs
+            .check_relaxed_instance_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        IVCProof {
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            i: self.i,
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn verify_proof(vp: Self::VerifierParam, proof: &Self::IVCProof) -> Result<(), Error> {
+        Self::verify(
+            vp,
+            proof.z_0.clone(),
+            proof.z_i.clone(),
+            proof.i,
+            (proof.U_i.clone(), proof.W_i.clone()),
+            (proof.u_i.clone(), proof.w_i.clone()),
+            (proof.cf_U_i.clone(), proof.cf_W_i.clone()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commitment::kzg::KZG;
+    use ark_bn254::{constraints::GVar, Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::frontend::tests::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    pub fn test_ivc() {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        );
+
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        );
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve, folding a single incoming instance at each step
+        test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit, 1);
+    }
+
+    #[test]
+    pub fn test_ivc_pallas_vesta() {
+        // the scalar field of Pallas is larger than its base field
+        use ark_pallas::{constraints::GVar, Fr, Projective};
+        use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+        type PG = ProtoGalaxy<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let protogalaxy_params = PG::preprocess(&mut rng, &(prep_param, 2)).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut protogalaxy = PG::init(&protogalaxy_params, F_circuit, z_0).unwrap();
+        let num_steps: usize = 3;
+        for _ in 0..num_steps {
+            let (u, w) = protogalaxy
+                .new_incoming_instance(&mut rng, vec![Fr::from(85_u32)], vec![])
+                .unwrap();
+            protogalaxy
+                .prove_step(&mut rng, vec![], Some(vec![(u, w)]))
+                .unwrap();
+        }
+        assert_eq!(Fr::from(num_steps as u32), protogalaxy.i);
+
+        let ivc_proof = protogalaxy.ivc_proof();
+        PG::verify_proof(protogalaxy_params.1, &ivc_proof).unwrap();
+    }
+
+    // test_ivc allowing to choose the CommitmentSchemes and the number of incoming instances
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        F_circuit: CubicFCircuit<Fr>,
+        k: usize,
+    ) {
+        type PG<CS1, CS2, const H: bool> =
+            ProtoGalaxy<Projective, GVar, Projective2, GVar2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+        let mut rng = ark_std::test_rng();
+
+        let prep_param =
+            PreprocessorParam::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>::new(
+                poseidon_config,
+                F_circuit,
+            );
+        let protogalaxy_params = PG::<CS1, CS2, H>::preprocess(&mut rng, &(prep_param, k)).unwrap();
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut protogalaxy =
+            PG::<CS1, CS2, H>::init(&protogalaxy_params, F_circuit, z_0.clone()).unwrap();
+
+        let num_steps: usize = 3;
+        for _ in 0..num_steps {
+            // prepare some new instances to fold in the multifolding step
+            let mut us = vec![];
+            for j in 0..k - 1 {
+                let instance_state = vec![Fr::from(j as u32 + 85_u32)];
+                let (u, w) = protogalaxy
+                    .new_incoming_instance(&mut rng, instance_state, vec![])
+                    .unwrap();
+                us.push((u, w));
+            }
+
+            protogalaxy.prove_step(&mut rng, vec![], Some(us)).unwrap();
+        }
+        assert_eq!(Fr::from(num_steps as u32), protogalaxy.i);
+
+        // check that the IVCProof verifies after a serialization roundtrip
+        let ivc_proof = protogalaxy.ivc_proof();
+        let mut ivc_proof_bytes = vec![];
+        ivc_proof
+            .serialize_compressed(&mut ivc_proof_bytes)
+            .unwrap();
+        let ivc_proof =
+            IVCProof::<Projective, Projective2>::deserialize_compressed(&ivc_proof_bytes[..])
+                .unwrap();
+        PG::<CS1, CS2, H>::verify_proof(protogalaxy_params.1.clone(), &ivc_proof).unwrap();
+
+        let (running_instance, incoming_instance, cyclefold_instance) = protogalaxy.instances();
+        PG::<CS1, CS2, H>::verify(
+            protogalaxy_params.1, // verifier_params
+            z_0,
+            protogalaxy.z_i,
+            protogalaxy.i,
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,565 @@
+/// contains the [SuperNova](https://eprint.iacr.org/2022/1758.pdf)-style augmented circuit, built
+/// on top of the Nova circuits.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    groups::GroupOpsBounds,
+    prelude::CurveVar,
+    select::CondSelectGadget,
+    R1CSVar, ToConstraintFieldGadget,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{One, Zero};
+use core::marker::PhantomData;
+
+use crate::folding::circuits::{
+    cyclefold::{
+        cf_io_len, CycleFoldChallengeGadget, CycleFoldCommittedInstanceVar, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1, CF2,
+};
+use crate::folding::nova::{
+    circuits::{ChallengeGadget, CommittedInstanceVar, NIFSGadget},
+    CommittedInstance, NOVA_CF_N_POINTS,
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar};
+
+/// Returns `H(i, pc, z_0, z_i, U_i[0], ..., U_i[n-1])`, where `pc` is the index of the step circuit
+/// that has been executed at the last step, and `U_i` are the running instances of each of the
+/// step circuits.
+pub fn running_instances_hash<C: CurveGroup, T: Transcript<C::ScalarField>>(
+    sponge: &T,
+    pp_hash: C::ScalarField, // public params hash
+    i: C::ScalarField,
+    pc: usize,
+    z_0: &[C::ScalarField],
+    z_i: &[C::ScalarField],
+    U_i: &[CommittedInstance<C>],
+) -> C::ScalarField
+where
+    <C as Group>::ScalarField: Absorb,
+    <C as CurveGroup>::BaseField: PrimeField,
+{
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash);
+    sponge.absorb(&i);
+    sponge.absorb(&C::ScalarField::from(pc as u64));
+    sponge.absorb(&z_0.to_vec());
+    sponge.absorb(&z_i.to_vec());
+    for U in U_i {
+        sponge.absorb(&U);
+    }
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// In-circuit counterpart of `running_instances_hash`.
+fn running_instances_hash_gadget<C, S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+    sponge: &T,
+    pp_hash: FpVar<CF1<C>>,
+    i: FpVar<CF1<C>>,
+    pc: FpVar<CF1<C>>,
+    z_0: Vec<FpVar<CF1<C>>>,
+    z_i: Vec<FpVar<CF1<C>>>,
+    U_i: &[CommittedInstanceVar<C>],
+) -> Result<FpVar<CF1<C>>, SynthesisError>
+where
+    C: CurveGroup,
+    <C as CurveGroup>::BaseField: PrimeField,
+{
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash)?;
+    sponge.absorb(&i)?;
+    sponge.absorb(&pc)?;
+    sponge.absorb(&z_0)?;
+    sponge.absorb(&z_i)?;
+    for U in U_i {
+        sponge.absorb(&U.to_sponge_field_elements()?)?;
+    }
+    Ok(sponge.squeeze_field_elements(1)?.pop().unwrap())
+}
+
+/// returns `true_value` if `cond` is true, and `false_value` otherwise
+fn select_committed_instance<C>(
+    cond: &Boolean<CF1<C>>,
+    true_value: &CommittedInstanceVar<C>,
+    false_value: &CommittedInstanceVar<C>,
+) -> Result<CommittedInstanceVar<C>, SynthesisError>
+where
+    C: CurveGroup,
+    <C as CurveGroup>::BaseField: PrimeField,
+{
+    let select_point = |a: &NonNativeAffineVar<C>, b: &NonNativeAffineVar<C>| {
+        Ok::<_, SynthesisError>(NonNativeAffineVar {
+            x: NonNativeUintVar::conditionally_select(cond, &a.x, &b.x)?,
+            y: NonNativeUintVar::conditionally_select(cond, &a.y, &b.y)?,
+        })
+    };
+    Ok(CommittedInstanceVar {
+        u: cond.select(&true_value.u, &false_value.u)?,
+        x: true_value
+            .x
+            .iter()
+            .zip(&false_value.x)
+            .map(|(a, b)| cond.select(a, b))
+            .collect::<Result<Vec<_>, _>>()?,
+        cmE: select_point(&true_value.cmE, &false_value.cmE)?,
+        cmW: select_point(&true_value.cmW, &false_value.cmW)?,
+    })
+}
+
+/// AugmentedFCircuit implements the augmented circuit of the `pc`-th step circuit of the
+/// non-uniform IVC. It differs from Nova's AugmentedFCircuit in that it receives the running
+/// instances of all the step circuits, and folds the incoming instance `u_i` into the running
+/// instance of the step circuit that produced it, `U_i[pc_prev]`, which is selected in-circuit.
+/// Its output `u_{i+1}.x[0]` binds the index `pc` of the executed step circuit, so that the next
+/// step folds `u_{i+1}` into the right running instance.
+#[derive(Debug, Clone)]
+pub struct AugmentedFCircuit<
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    FC: FCircuit<CF1<C1>>,
+> where
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    pub _gc2: PhantomData<GC2>,
+    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    /// index of this step circuit
+    pub pc: usize,
+    /// number of step circuits
+    pub n_circuits: usize,
+    pub pp_hash: Option<CF1<C1>>,
+    pub i: Option<CF1<C1>>,
+    pub i_usize: Option<usize>,
+    /// index of the step circuit executed at the previous step, which produced u_i
+    pub pc_prev: Option<usize>,
+    pub z_0: Option<Vec<C1::ScalarField>>,
+    pub z_i: Option<Vec<C1::ScalarField>>,
+    pub external_inputs: Option<Vec<C1::ScalarField>>,
+    pub u_i_cmW: Option<C1>,
+    pub U_i: Option<Vec<CommittedInstance<C1>>>,
+    pub U_i1_cmE: Option<C1>,
+    pub U_i1_cmW: Option<C1>,
+    pub cmT: Option<C1>,
+    pub F: FC,              // F circuit
+    pub x: Option<CF1<C1>>, // public input (u_{i+1}.x[0])
+
+    // cyclefold verifier on C1
+    pub cf1_u_i_cmW: Option<C2>,               // input
+    pub cf2_u_i_cmW: Option<C2>,               // input
+    pub cf_U_i: Option<CommittedInstance<C2>>, // input
+    pub cf1_cmT: Option<C2>,
+    pub cf2_cmT: Option<C2>,
+    pub cf_x: Option<CF1<C1>>, // public input (u_{i+1}.x[1])
+}
+
+impl<C1: CurveGroup, C2: CurveGroup, GC2: CurveVar<C2, CF2<C2>>, FC: FCircuit<CF1<C1>>>
+    AugmentedFCircuit<C1, C2, GC2, FC>
+where
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    pub fn empty(
+        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        F_circuit: FC,
+        pc: usize,
+        n_circuits: usize,
+    ) -> Self {
+        Self {
+            _gc2: PhantomData,
+            poseidon_config: poseidon_config.clone(),
+            pc,
+            n_circuits,
+            pp_hash: None,
+            i: None,
+            i_usize: None,
+            pc_prev: None,
+            z_0: None,
+            z_i: None,
+            external_inputs: None,
+            u_i_cmW: None,
+            U_i: None,
+            U_i1_cmE: None,
+            U_i1_cmW: None,
+            cmT: None,
+            F: F_circuit,
+            x: None,
+            // cyclefold values
+            cf1_u_i_cmW: None,
+            cf2_u_i_cmW: None,
+            cf_U_i: None,
+            cf1_cmT: None,
+            cf2_cmT: None,
+            cf_x: None,
+        }
+    }
+}
+
+impl<C1, C2, GC2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, GC2, FC>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<CF1<C1>>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let pc_prev = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(CF1::<C1>::from(self.pc_prev.unwrap_or(0) as u64))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_0
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_i
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let external_inputs = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .external_inputs
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.external_inputs_len()]))
+        })?;
+
+        let u_dummy = CommittedInstance::dummy(2);
+        let U_i = Vec::<CommittedInstanceVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.U_i.unwrap_or(vec![u_dummy.clone(); self.n_circuits]))
+        })?;
+        let U_i1_cmE = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmE.unwrap_or_else(C1::zero))
+        })?;
+        let U_i1_cmW = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmW.unwrap_or_else(C1::zero))
+        })?;
+
+        let cmT =
+            NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.cmT.unwrap_or_else(C1::zero)))?;
+
+        let cf_u_dummy = CommittedInstance::dummy(cf_io_len(NOVA_CF_N_POINTS));
+        let cf_U_i = CycleFoldCommittedInstanceVar::<C2, GC2>::new_witness(cs.clone(), || {
+            Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
+        })?;
+        let cf1_cmT = GC2::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
+        let cf2_cmT = GC2::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        // get z_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 =
+            self.F
+                .generate_step_constraints(cs.clone(), i_usize, z_i.clone(), external_inputs)?;
+
+        let is_basecase = i.is_zero()?;
+
+        // Selection of the running instance
+        // S.1. pc_prev must be the index of one of the step circuits
+        let selectors = (0..self.n_circuits)
+            .map(|k| pc_prev.is_eq(&FpVar::constant(CF1::<C1>::from(k as u64))))
+            .collect::<Result<Vec<Boolean<CF1<C1>>>, SynthesisError>>()?;
+        Boolean::kary_or(&selectors)?.enforce_equal(&Boolean::TRUE)?;
+        // S.2. select U_i[pc_prev], the running instance of the step circuit that produced u_i
+        let mut U_sel = U_i[0].clone();
+        for (U, selector) in U_i.iter().zip(&selectors).skip(1) {
+            U_sel = select_committed_instance(selector, U, &U_sel)?;
+        }
+
+        // Primary Part
+        // P.1. Compute u_i.x
+        // u_i.x[0] = H(i, pc_prev, z_0, z_i, U_i)
+        let u_i_x = running_instances_hash_gadget(
+            &sponge,
+            pp_hash.clone(),
+            i.clone(),
+            pc_prev,
+            z_0.clone(),
+            z_i.clone(),
+            &U_i,
+        )?;
+        // u_i.x[1] = H(cf_U_i)
+        let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+
+        // P.2. Construct u_i
+        let u_i = CommittedInstanceVar {
+            // u_i.cmE = cm(0)
+            cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+            // u_i.u = 1
+            u: FpVar::one(),
+            // u_i.cmW is provided by the prover as witness
+            cmW: NonNativeAffineVar::new_witness(cs.clone(), || {
+                Ok(self.u_i_cmW.unwrap_or(C1::zero()))
+            })?,
+            // u_i.x is computed in step 1
+            x: vec![u_i_x, cf_u_i_x],
+        };
+
+        // P.3. nifs.verify, obtains U_{i+1}[pc_prev] by folding u_i & U_i[pc_prev].
+
+        // compute r = H(u_i, U_i[pc_prev], cmT)
+        let r_bits = ChallengeGadget::<C1>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            U_sel.to_sponge_field_elements()?,
+            u_i.clone(),
+            cmT.clone(),
+        )?;
+        let r = Boolean::le_bits_to_fp_var(&r_bits)?;
+        // Also convert r_bits to a `NonNativeFieldVar`
+        let r_nonnat = {
+            let mut bits = r_bits;
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+
+        // Notice that NIFSGadget::fold_committed_instance does not fold cmE & cmW, whose
+        // correctness is checked on the other curve.
+        let mut U_i1_sel =
+            NIFSGadget::<C1>::fold_committed_instance(r, U_sel.clone(), u_i.clone())?;
+        U_i1_sel.cmE = U_i1_cmE;
+        U_i1_sel.cmW = U_i1_cmW;
+
+        // U_{i+1}[k] = U_{i+1}[pc_prev] if k == pc_prev, U_i[k] otherwise
+        let U_i1 = U_i
+            .iter()
+            .zip(&selectors)
+            .map(|(U, selector)| select_committed_instance(selector, &U_i1_sel, U))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // P.4.a compute and check the first output of F'
+        // Base case: u_{i+1}.x[0] == H((i+1, pc, z_0, z_{i+1}, U_{\bot})
+        // Non-base case: u_{i+1}.x[0] == H((i+1, pc, z_0, z_{i+1}, U_{i+1})
+        let pc = FpVar::constant(CF1::<C1>::from(self.pc as u64));
+        let u_i1_x = running_instances_hash_gadget(
+            &sponge,
+            pp_hash.clone(),
+            i + FpVar::<CF1<C1>>::one(),
+            pc.clone(),
+            z_0.clone(),
+            z_i1.clone(),
+            &U_i1,
+        )?;
+        let U_dummy =
+            vec![CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?; self.n_circuits];
+        let u_i1_x_base = running_instances_hash_gadget(
+            &sponge,
+            pp_hash.clone(),
+            FpVar::<CF1<C1>>::one(),
+            pc,
+            z_0,
+            z_i1,
+            &U_dummy,
+        )?;
+        let x = FpVar::new_input(cs.clone(), || Ok(self.x.unwrap_or(u_i1_x_base.value()?)))?;
+        x.enforce_equal(&is_basecase.select(&u_i1_x_base, &u_i1_x)?)?;
+
+        // CycleFold part, same as in Nova's AugmentedFCircuit but for the selected instances
+        // C.1. Compute cf1_u_i.x and cf2_u_i.x
+        let cfW_x = vec![
+            r_nonnat.clone(),
+            U_sel.cmW.x,
+            U_sel.cmW.y,
+            u_i.cmW.x,
+            u_i.cmW.y,
+            U_i1_sel.cmW.x,
+            U_i1_sel.cmW.y,
+        ];
+        let cfE_x = vec![
+            r_nonnat,
+            U_sel.cmE.x,
+            U_sel.cmE.y,
+            cmT.x,
+            cmT.y,
+            U_i1_sel.cmE.x,
+            U_i1_sel.cmE.y,
+        ];
+
+        // C.2. Construct `cf1_u_i` and `cf2_u_i`
+        let cf1_u_i = CycleFoldCommittedInstanceVar {
+            // cf1_u_i.cmE = 0
+            cmE: GC2::zero(),
+            // cf1_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf1_u_i.cmW is provided by the prover as witness
+            cmW: GC2::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf1_u_i.x is computed in step 1
+            x: cfW_x,
+        };
+        let cf2_u_i = CycleFoldCommittedInstanceVar {
+            // cf2_u_i.cmE = 0
+            cmE: GC2::zero(),
+            // cf2_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf2_u_i.cmW is provided by the prover as witness
+            cmW: GC2::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf2_u_i.x is computed in step 1
+            x: cfE_x,
+        };
+
+        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
+        // by folding cf2_u_i & cf1_U_{i+1}.
+        let cf1_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i_vec,
+            cf1_u_i.clone(),
+            cf1_cmT.clone(),
+        )?;
+        let cf1_r_nonnat = {
+            let mut bits = cf1_r_bits.clone();
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        let cf1_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
+            cf1_r_bits,
+            cf1_r_nonnat,
+            cf1_cmT,
+            cf_U_i,
+            cf1_u_i,
+        )?;
+
+        let cf2_r_bits = CycleFoldChallengeGadget::<C2, GC2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf1_U_i1.to_native_sponge_field_elements()?,
+            cf2_u_i.clone(),
+            cf2_cmT.clone(),
+        )?;
+        let cf2_r_nonnat = {
+            let mut bits = cf2_r_bits.clone();
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        let cf_U_i1 = NIFSFullGadget::<C2, GC2>::fold_committed_instance(
+            cf2_r_bits,
+            cf2_r_nonnat,
+            cf2_cmT,
+            cf1_U_i1,
+            cf2_u_i,
+        )?;
+
+        // Back to Primary Part
+        // P.4.b compute and check the second output of F'
+        // Base case: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-base case: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let (cf_u_i1_x, _) = cf_U_i1.clone().hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_base, _) =
+            CycleFoldCommittedInstanceVar::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = FpVar::new_input(cs.clone(), || {
+            Ok(self.cf_x.unwrap_or(cf_u_i1_x_base.value()?))
+        })?;
+        cf_x.enforce_equal(&is_basecase.select(&cf_u_i1_x_base, &cf_u_i1_x)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_running_instances_hash() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let pp_hash = Fr::from(42u32); // only for test
+
+        let i = Fr::from(3_u32);
+        let pc = 1;
+        let z_0 = vec![Fr::from(3_u32)];
+        let z_i = vec![Fr::from(5_u32)];
+        let U_i = (0..3)
+            .map(|_| CommittedInstance::<Projective> {
+                cmE: Projective::rand(&mut rng),
+                u: Fr::rand(&mut rng),
+                cmW: Projective::rand(&mut rng),
+                x: vec![Fr::rand(&mut rng); 2],
+            })
+            .collect::<Vec<_>>();
+
+        // compute the hash natively
+        let h = running_instances_hash(&sponge, pp_hash, i, pc, &z_0, &z_i, &U_i);
+
+        // compute the hash in-circuit
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let spongeVar = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        let pp_hashVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(pp_hash)).unwrap();
+        let iVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(i)).unwrap();
+        let pcVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(pc as u64))).unwrap();
+        let z_0Var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_0.clone())).unwrap();
+        let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone())).unwrap();
+        let U_iVar =
+            Vec::<CommittedInstanceVar<Projective>>::new_witness(cs.clone(), || Ok(U_i.clone()))
+                .unwrap();
+        let hVar = running_instances_hash_gadget(
+            &spongeVar, pp_hashVar, iVar, pcVar, z_0Var, z_iVar, &U_iVar,
+        )
+        .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(hVar.value().unwrap(), h);
+    }
+
+    #[test]
+    fn test_select_committed_instance() {
+        fn rand_ci<R: ark_std::rand::Rng>(rng: &mut R) -> CommittedInstance<Projective> {
+            CommittedInstance::<Projective> {
+                cmE: Projective::rand(rng),
+                u: Fr::rand(rng),
+                cmW: Projective::rand(rng),
+                x: vec![Fr::rand(rng); 2],
+            }
+        }
+        let mut rng = ark_std::test_rng();
+        let (a, b) = (rand_ci(&mut rng), rand_ci(&mut rng));
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let aVar =
+            CommittedInstanceVar::<Projective>::new_witness(cs.clone(), || Ok(a.clone())).unwrap();
+        let bVar =
+            CommittedInstanceVar::<Projective>::new_witness(cs.clone(), || Ok(b.clone())).unwrap();
+        for (cond, expected) in [(true, &aVar), (false, &bVar)] {
+            let condVar = Boolean::new_witness(cs.clone(), || Ok(cond)).unwrap();
+            let selected = select_committed_instance(&condVar, &aVar, &bVar).unwrap();
+            assert_eq!(selected.u.value().unwrap(), expected.u.value().unwrap());
+            assert_eq!(selected.x.value().unwrap(), expected.x.value().unwrap());
+            assert_eq!(
+                selected.cmW.to_constraint_field().unwrap().value().unwrap(),
+                expected.cmW.to_constraint_field().unwrap().value().unwrap()
+            );
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
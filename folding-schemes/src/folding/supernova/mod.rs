@@ -0,0 +1,840 @@
+/// Implements a non-uniform IVC in the style of [SuperNova](https://eprint.iacr.org/2022/1758.pdf)
+/// on top of the Nova+[CycleFold](https://eprint.iacr.org/2023/1192.pdf) folding scheme.
+///
+/// Instead of a single step circuit, it is defined over a set of step circuits `F_0, ...,
+/// F_{n-1}`, and at each step the prover chooses (through the program counter `pc`) which one is
+/// executed. The state keeps one running instance per step circuit, and the augmented circuit of
+/// the executed step circuit folds the incoming instance into the running instance of the step
+/// circuit that produced it, checking in-circuit that this selection is the one bound to the
+/// incoming instance.
+///
+/// Notice that the verifier does not check which sequence of step circuits has been executed, only
+/// that each step has been computed by one of the step circuits. If the control flow of the
+/// computation has to be enforced, it can be done by the step circuits through the state `z_i`.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{groups::GroupOpsBounds, prelude::CurveVar, ToConstraintFieldGadget};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::rand::RngCore;
+use ark_std::{One, Zero};
+use core::marker::PhantomData;
+use sha3::{Digest, Sha3_256};
+
+use crate::arith::r1cs::{extract_w_x, R1CS};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::cyclefold::{fold_cyclefold_circuit, CycleFoldCircuit};
+use crate::folding::circuits::CF2;
+use crate::folding::nova::{
+    circuits::ChallengeGadget, get_r1cs_from_cs, nifs::NIFS, traits::NovaR1CS, CommittedInstance,
+    ProverParams, Witness, NOVA_CF_N_POINTS,
+};
+use crate::frontend::FCircuit;
+use crate::transcript::Transcript;
+use crate::utils::{get_cm_coordinates, pp_hash};
+use crate::Error;
+
+pub mod circuits;
+use circuits::{running_instances_hash, AugmentedFCircuit};
+
+#[derive(Debug, Clone)]
+pub struct PreprocessorParam<C1, C2, FC, CS1, CS2, const H: bool>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// step circuits, indexed by the program counter
+    pub F: Vec<FC>,
+    // cs params if not provided, will be generated at the preprocess method
+    pub cs_pp: Option<CS1::ProverParams>,
+    pub cs_vp: Option<CS1::VerifierParams>,
+    pub cf_cs_pp: Option<CS2::ProverParams>,
+    pub cf_cs_vp: Option<CS2::VerifierParams>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> PreprocessorParam<C1, C2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub fn new(poseidon_config: PoseidonConfig<C1::ScalarField>, F: Vec<FC>) -> Self {
+        Self {
+            poseidon_config,
+            F,
+            cs_pp: None,
+            cs_vp: None,
+            cf_cs_pp: None,
+            cf_cs_vp: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// R1CS of the augmented circuit of each of the step circuits
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub cs_vp: CS1::VerifierParams,
+    pub cf_cs_vp: CS2::VerifierParams,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    C2: CurveGroup,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters, which binds the R1CS of all the step circuits
+    pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.r1cs.len().to_le_bytes());
+        for r1cs in &self.r1cs {
+            let h = pp_hash::<C1, C2, CS1, CS2, H>(
+                r1cs,
+                &self.cf_r1cs,
+                &self.cs_vp,
+                &self.cf_cs_vp,
+                &self.poseidon_config,
+            )?;
+            hasher.update(h.into_bigint().to_bytes_le());
+        }
+        Ok(C1::ScalarField::from_le_bytes_mod_order(&hasher.finalize()))
+    }
+}
+
+/// Implements a SuperNova-style non-uniform IVC over Nova+CycleFold. The `H` const generic
+/// specifies whether the homorphic commitment scheme is blinding.
+#[derive(Clone, Debug)]
+pub struct SuperNova<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    _gc1: PhantomData<GC1>,
+    _c2: PhantomData<C2>,
+    _gc2: PhantomData<GC2>,
+    /// R1CS of the augmented circuit of each of the step circuits
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// step circuits, indexed by the program counter
+    pub F: Vec<FC>,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// index of the step circuit executed at the last step, which produced u_i
+    pub pc: usize,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// SuperNova instances, one running instance per step circuit
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub W_i: Vec<Witness<C1>>,
+    pub U_i: Vec<CommittedInstance<C1>>,
+
+    /// CycleFold running instance
+    pub cf_W_i: Witness<C2>,
+    pub cf_U_i: CommittedInstance<C2>,
+}
+
+impl<C1, GC1, C2, GC2, FC, CS1, CS2, const H: bool> SuperNova<C1, GC1, C2, GC2, FC, CS1, CS2, H>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    pub fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H>,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        Error,
+    > {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, GC1, C2, GC2, FC>(&prep_param.poseidon_config, &prep_param.F)?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let cs_pp: CS1::ProverParams;
+        let cs_vp: CS1::VerifierParams;
+        let cf_cs_pp: CS2::ProverParams;
+        let cf_cs_vp: CS2::VerifierParams;
+        if prep_param.cs_pp.is_some()
+            && prep_param.cf_cs_pp.is_some()
+            && prep_param.cs_vp.is_some()
+            && prep_param.cf_cs_vp.is_some()
+        {
+            cs_pp = prep_param.clone().cs_pp.unwrap();
+            cs_vp = prep_param.clone().cs_vp.unwrap();
+            cf_cs_pp = prep_param.clone().cf_cs_pp.unwrap();
+            cf_cs_vp = prep_param.clone().cf_cs_vp.unwrap();
+        } else {
+            // the commitment params must be large enough for the biggest of the step circuits
+            let max_n_rows = r1cs.iter().map(|r1cs| r1cs.A.n_rows).max().unwrap_or(0);
+            (cs_pp, cs_vp) = CS1::setup(&mut rng, max_n_rows)?;
+            (cf_cs_pp, cf_cs_vp) = CS2::setup(&mut rng, cf_r1cs.A.n_rows)?;
+        }
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+            // multi-instances folding is not supported in SuperNova
+            mu: 1,
+            nu: 1,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+            mu: 1,
+            nu: 1,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the non-uniform IVC for the given parameters, step circuits and initial state
+    /// `z_0`.
+    pub fn init(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        F: Vec<FC>,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+        if F.len() != vp.r1cs.len() {
+            return Err(Error::NotSameLength(
+                "F.len()".to_string(),
+                F.len(),
+                "vp.r1cs.len()".to_string(),
+                vp.r1cs.len(),
+            ));
+        }
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances, u_0 is considered to be produced by the step circuit 0
+        let (W_dummy, U_dummy): (Vec<Witness<C1>>, Vec<CommittedInstance<C1>>) =
+            vp.r1cs.iter().map(|r1cs| r1cs.dummy_instance()).unzip();
+        let (cf_w_dummy, cf_u_dummy) = vp.cf_r1cs.dummy_instance();
+
+        Ok(Self {
+            _gc1: PhantomData,
+            _c2: PhantomData,
+            _gc2: PhantomData,
+            r1cs: vp.r1cs.clone(),
+            cf_r1cs: vp.cf_r1cs.clone(),
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            pc: 0,
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: W_dummy[0].clone(),
+            u_i: U_dummy[0].clone(),
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_w_dummy,
+            cf_U_i: cf_u_dummy,
+        })
+    }
+
+    /// Implements IVC.P of the non-uniform IVC, executing the `pc`-th step circuit.
+    pub fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        pc: usize,
+        external_inputs: Vec<C1::ScalarField>,
+    ) -> Result<(), Error> {
+        if pc >= self.F.len() {
+            return Err(Error::OutOfBounds);
+        }
+        let F = &self.F[pc];
+        if self.z_i.len() != F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                F.state_len(),
+            ));
+        }
+        if external_inputs.len() != F.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "F.external_inputs_len()".to_string(),
+                F.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+        let mut i_bytes: [u8; 8] = [0; 8];
+        i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+        let i_usize: usize = usize::from_le_bytes(i_bytes);
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let z_i1 = F.step_native(i_usize, self.z_i.clone(), external_inputs.clone())?;
+
+        // u_i has been produced by the step circuit pc_prev, so it is folded into its running
+        // instance
+        let pc_prev = self.pc;
+
+        // compute T and cmT for the augmented circuit of pc_prev
+        let (T, cmT) = NIFS::<C1, CS1, H>::compute_cmT(
+            &self.cs_pp,
+            &self.r1cs[pc_prev],
+            &self.w_i,
+            &self.u_i,
+            &self.W_i[pc_prev],
+            &self.U_i[pc_prev],
+        )?;
+
+        // r_bits is the r used to the RLC of the F' instances
+        let r_bits = ChallengeGadget::<C1>::get_challenge_native(
+            &mut transcript,
+            self.pp_hash,
+            self.U_i[pc_prev].clone(),
+            self.u_i.clone(),
+            cmT,
+        );
+        let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let r_Fq = C1::BaseField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+
+        // fold the incoming instance into the running instance of pc_prev
+        let (W_i1_sel, U_i1_sel): (Witness<C1>, CommittedInstance<C1>) =
+            NIFS::<C1, CS1, H>::fold_instances(
+                r_Fr,
+                &self.W_i[pc_prev],
+                &self.U_i[pc_prev],
+                &self.w_i,
+                &self.u_i,
+                &T,
+                cmT,
+            )?;
+        let mut U_i1 = self.U_i.clone();
+        U_i1[pc_prev] = U_i1_sel.clone();
+
+        // folded instance output (public input, x)
+        // u_{i+1}.x[0] = H(i+1, pc, z_0, z_{i+1}, U_{i+1})
+        let u_i1_x = running_instances_hash(
+            &sponge,
+            self.pp_hash,
+            self.i + C1::ScalarField::one(),
+            pc,
+            &self.z_0,
+            &z_i1,
+            &U_i1,
+        );
+        // u_{i+1}.x[1] = H(cf_U_{i+1})
+        let cf_u_i1_x: C1::ScalarField;
+
+        let mut augmented_F_circuit = AugmentedFCircuit::<C1, C2, GC2, FC> {
+            pp_hash: Some(self.pp_hash),
+            i: Some(self.i),
+            i_usize: Some(i_usize),
+            pc_prev: Some(pc_prev),
+            z_0: Some(self.z_0.clone()),
+            z_i: Some(self.z_i.clone()),
+            external_inputs: Some(external_inputs),
+            u_i_cmW: Some(self.u_i.cmW),
+            U_i: Some(self.U_i.clone()),
+            U_i1_cmE: Some(U_i1_sel.cmE),
+            U_i1_cmW: Some(U_i1_sel.cmW),
+            cmT: Some(cmT),
+            x: Some(u_i1_x),
+            ..AugmentedFCircuit::empty(&self.poseidon_config, F.clone(), pc, self.F.len())
+        };
+
+        if self.i == C1::ScalarField::zero() {
+            // base case
+            cf_u_i1_x = self.cf_U_i.hash_cyclefold(&sponge, self.pp_hash);
+            augmented_F_circuit.cf_x = Some(cf_u_i1_x);
+        } else {
+            // CycleFold part:
+            // get the vector used as public inputs 'x' in the CycleFold circuit
+            // cyclefold circuit for cmW
+            let cfW_u_i_x = [
+                vec![r_Fq],
+                get_cm_coordinates(&self.U_i[pc_prev].cmW),
+                get_cm_coordinates(&self.u_i.cmW),
+                get_cm_coordinates(&U_i1_sel.cmW),
+            ]
+            .concat();
+            // cyclefold circuit for cmE
+            let cfE_u_i_x = [
+                vec![r_Fq],
+                get_cm_coordinates(&self.U_i[pc_prev].cmE),
+                get_cm_coordinates(&cmT),
+                get_cm_coordinates(&U_i1_sel.cmE),
+            ]
+            .concat();
+
+            let cfW_circuit = CycleFoldCircuit::<C1, GC1> {
+                _gc: PhantomData,
+                n_points: NOVA_CF_N_POINTS,
+                r_bits: Some(vec![r_bits.clone()]),
+                points: Some(vec![self.U_i[pc_prev].cmW, self.u_i.cmW]),
+                x: Some(cfW_u_i_x.clone()),
+            };
+            let cfE_circuit = CycleFoldCircuit::<C1, GC1> {
+                _gc: PhantomData,
+                n_points: NOVA_CF_N_POINTS,
+                r_bits: Some(vec![r_bits.clone()]),
+                points: Some(vec![self.U_i[pc_prev].cmE, cmT]),
+                x: Some(cfE_u_i_x.clone()),
+            };
+
+            // fold self.cf_U_i + cfW_U -> folded running with cfW
+            let (_cfW_w_i, cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT, _) = self.fold_cyclefold_circuit(
+                &mut transcript,
+                self.cf_W_i.clone(),
+                self.cf_U_i.clone(),
+                cfW_u_i_x,
+                cfW_circuit,
+                &mut rng,
+            )?;
+            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
+            let (_cfE_w_i, cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT, _) = self.fold_cyclefold_circuit(
+                &mut transcript,
+                cfW_W_i1,
+                cfW_U_i1,
+                cfE_u_i_x,
+                cfE_circuit,
+                &mut rng,
+            )?;
+
+            cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, self.pp_hash);
+
+            augmented_F_circuit.cf1_u_i_cmW = Some(cfW_u_i.cmW);
+            augmented_F_circuit.cf2_u_i_cmW = Some(cfE_u_i.cmW);
+            augmented_F_circuit.cf_U_i = Some(self.cf_U_i.clone());
+            augmented_F_circuit.cf1_cmT = Some(cfW_cmT);
+            augmented_F_circuit.cf2_cmT = Some(cf_cmT);
+            augmented_F_circuit.cf_x = Some(cf_u_i1_x);
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+
+            #[cfg(test)]
+            {
+                self.cf_r1cs.check_instance_relation(&_cfW_w_i, &cfW_u_i)?;
+                self.cf_r1cs.check_instance_relation(&_cfE_w_i, &cfE_u_i)?;
+                self.cf_r1cs
+                    .check_relaxed_instance_relation(&self.cf_W_i, &self.cf_U_i)?;
+            }
+        }
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        augmented_F_circuit.generate_constraints(cs.clone())?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied().unwrap());
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+        if x_i1[0] != u_i1_x || x_i1[1] != cf_u_i1_x {
+            return Err(Error::NotEqual);
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.pc = pc;
+        self.z_i = z_i1;
+        self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs[pc].A.n_rows, &mut rng);
+        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)?;
+        self.W_i[pc_prev] = W_i1_sel;
+        self.U_i = U_i1;
+
+        #[cfg(test)]
+        {
+            self.r1cs[pc].check_instance_relation(&self.w_i, &self.u_i)?;
+            self.r1cs[pc_prev]
+                .check_relaxed_instance_relation(&self.W_i[pc_prev], &self.U_i[pc_prev])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    /// returns the instances at the current step, in the following order:
+    /// (running_instances, incoming_instance, cyclefold_instance)
+    #[allow(clippy::type_complexity)]
+    pub fn instances(
+        &self,
+    ) -> (
+        Vec<(CommittedInstance<C1>, Witness<C1>)>,
+        (CommittedInstance<C1>, Witness<C1>),
+        (CommittedInstance<C2>, Witness<C2>),
+    ) {
+        (
+            self.U_i
+                .iter()
+                .cloned()
+                .zip(self.W_i.iter().cloned())
+                .collect(),
+            (self.u_i.clone(), self.w_i.clone()),
+            (self.cf_U_i.clone(), self.cf_W_i.clone()),
+        )
+    }
+
+    /// Implements IVC.V of the non-uniform IVC, where `pc` is the index of the step circuit
+    /// executed at the last step. Notice that this method does not include the commitments
+    /// verification.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        vp: VerifierParams<C1, C2, CS1, CS2, H>,
+        z_0: Vec<C1::ScalarField>, // initial state
+        z_i: Vec<C1::ScalarField>, // last state
+        num_steps: C1::ScalarField,
+        pc: usize,
+        running_instances: Vec<(CommittedInstance<C1>, Witness<C1>)>,
+        incoming_instance: (CommittedInstance<C1>, Witness<C1>),
+        cyclefold_instance: (CommittedInstance<C2>, Witness<C2>),
+    ) -> Result<(), Error> {
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if pc >= vp.r1cs.len() || running_instances.len() != vp.r1cs.len() {
+            return Err(Error::IVCVerificationFail);
+        }
+        let (U_i, W_i): (Vec<CommittedInstance<C1>>, Vec<Witness<C1>>) =
+            running_instances.into_iter().unzip();
+        let (u_i, w_i) = incoming_instance;
+        let (cf_U_i, cf_W_i) = cyclefold_instance;
+
+        if u_i.x.len() != 2 || U_i.iter().any(|U| U.x.len() != 2) {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instances
+        // u_i.X[0] == H(i, pc, z_0, z_i, U_i)
+        let expected_u_i_x =
+            running_instances_hash(&sponge, pp_hash, num_steps, pc, &z_0, &z_i, &U_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check u_i.cmE==0, u_i.u==1 (=u_i is a un-relaxed instance)
+        if !u_i.cmE.is_zero() || !u_i.u.is_one() {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability of u_i, which is an instance of the pc-th augmented circuit
+        vp.r1cs[pc].check_instance_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability of the running instances
+        for (r1cs, (W, U)) in vp.r1cs.iter().zip(W_i.iter().zip(&U_i)) {
+            r1cs.check_relaxed_instance_relation(W, U)?;
+        }
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs
+            .check_relaxed_instance_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+
+    // folds the given cyclefold circuit and its instances
+    #[allow(clippy::type_complexity)]
+    fn fold_cyclefold_circuit<T: Transcript<C1::ScalarField>>(
+        &self,
+        transcript: &mut T,
+        cf_W_i: Witness<C2>,           // witness of the running instance
+        cf_U_i: CommittedInstance<C2>, // running instance
+        cf_u_i_x: Vec<C2::ScalarField>,
+        cf_circuit: CycleFoldCircuit<C1, GC1>,
+        rng: &mut impl RngCore,
+    ) -> Result<
+        (
+            Witness<C2>,
+            CommittedInstance<C2>, // u_i
+            Witness<C2>,           // W_i1
+            CommittedInstance<C2>, // U_i1
+            C2,                    // cmT
+            C2::ScalarField,       // r_Fq
+        ),
+        Error,
+    > {
+        fold_cyclefold_circuit::<C1, GC1, C2, GC2, FC, CS1, CS2, H>(
+            NOVA_CF_N_POINTS,
+            transcript,
+            self.cf_r1cs.clone(),
+            self.cf_cs_pp.clone(),
+            self.pp_hash,
+            cf_W_i,
+            cf_U_i,
+            cf_u_i_x,
+            cf_circuit,
+            rng,
+        )
+    }
+}
+
+/// helper method to get the R1CS of the augmented circuit of each of the given step circuits, and
+/// the R1CS of the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, GC1, C2, GC2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F: &[FC],
+) -> Result<(Vec<R1CS<C1::ScalarField>>, R1CS<C2::ScalarField>), Error>
+where
+    C1: CurveGroup,
+    GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+    C2: CurveGroup,
+    GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+    FC: FCircuit<C1::ScalarField>,
+    <C1 as CurveGroup>::BaseField: PrimeField,
+    <C2 as CurveGroup>::BaseField: PrimeField,
+    <C1 as Group>::ScalarField: Absorb,
+    <C2 as Group>::ScalarField: Absorb,
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+    for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+{
+    if F.is_empty() {
+        return Err(Error::Empty);
+    }
+    // all the step circuits operate over the same state and external inputs
+    for F_j in F.iter().skip(1) {
+        if F_j.state_len() != F[0].state_len() {
+            return Err(Error::NotSameLength(
+                "F[j].state_len()".to_string(),
+                F_j.state_len(),
+                "F[0].state_len()".to_string(),
+                F[0].state_len(),
+            ));
+        }
+        if F_j.external_inputs_len() != F[0].external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "F[j].external_inputs_len()".to_string(),
+                F_j.external_inputs_len(),
+                "F[0].external_inputs_len()".to_string(),
+                F[0].external_inputs_len(),
+            ));
+        }
+    }
+
+    let r1cs = F
+        .iter()
+        .enumerate()
+        .map(|(pc, F_j)| {
+            let augmented_F_circuit = AugmentedFCircuit::<C1, C2, GC2, FC>::empty(
+                poseidon_config,
+                F_j.clone(),
+                pc,
+                F.len(),
+            );
+            get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let cf_circuit = CycleFoldCircuit::<C1, GC1>::empty(NOVA_CF_N_POINTS);
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_pallas::{constraints::GVar, Fr, Projective};
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_vesta::{constraints::GVar as GVar2, Projective as Projective2};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    /// OpFCircuit is a step circuit that, depending on its opcode, computes z^3 + z + 5 (opcode
+    /// 0) or 2z (opcode 1), so that the augmented circuits of each opcode have different sizes.
+    #[derive(Clone, Copy, Debug)]
+    pub struct OpFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+        opcode: usize,
+    }
+    impl<F: PrimeField> FCircuit<F> for OpFCircuit<F> {
+        type Params = usize;
+
+        fn new(opcode: Self::Params) -> Result<Self, Error> {
+            Ok(Self {
+                _f: PhantomData,
+                opcode,
+            })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn external_inputs_len(&self) -> usize {
+            0
+        }
+        fn step_native(
+            &self,
+            _i: usize,
+            z_i: Vec<F>,
+            _external_inputs: Vec<F>,
+        ) -> Result<Vec<F>, Error> {
+            match self.opcode {
+                0 => Ok(vec![z_i[0] * z_i[0] * z_i[0] + z_i[0] + F::from(5_u32)]),
+                _ => Ok(vec![z_i[0].double()]),
+            }
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Vec<FpVar<F>>,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            match self.opcode {
+                0 => {
+                    let five = FpVar::<F>::new_constant(cs.clone(), F::from(5u32))?;
+                    let z_i = z_i[0].clone();
+                    Ok(vec![&z_i * &z_i * &z_i + &z_i + &five])
+                }
+                _ => Ok(vec![&z_i[0] + &z_i[0]]),
+            }
+        }
+    }
+
+    type SN = SuperNova<
+        Projective,
+        GVar,
+        Projective2,
+        GVar2,
+        OpFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    #[test]
+    fn test_supernova() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F = vec![
+            OpFCircuit::<Fr>::new(0).unwrap(),
+            OpFCircuit::<Fr>::new(1).unwrap(),
+        ];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F.clone());
+        let params = SN::preprocess(&mut rng, &prep_param).unwrap();
+        // the augmented circuits of each step circuit have different sizes
+        assert_ne!(params.1.r1cs[0].A.n_rows, params.1.r1cs[1].A.n_rows);
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut supernova = SN::init(&params, F.clone(), z_0.clone()).unwrap();
+
+        let program = [1, 0, 0, 1, 1, 0];
+        let mut expected_z_i = z_0.clone();
+        for (i, pc) in program.iter().enumerate() {
+            supernova.prove_step(&mut rng, *pc, vec![]).unwrap();
+            expected_z_i = F[*pc].step_native(i, expected_z_i, vec![]).unwrap();
+        }
+        assert_eq!(supernova.state(), expected_z_i);
+        assert_eq!(supernova.i, Fr::from(program.len() as u32));
+
+        let (running_instances, incoming_instance, cyclefold_instance) = supernova.instances();
+        SN::verify(
+            params.1.clone(),
+            z_0.clone(),
+            supernova.z_i.clone(),
+            supernova.i,
+            supernova.pc,
+            running_instances.clone(),
+            incoming_instance.clone(),
+            cyclefold_instance.clone(),
+        )
+        .unwrap();
+
+        // the verification fails if the incoming instance is attributed to another step circuit
+        assert!(SN::verify(
+            params.1,
+            z_0,
+            supernova.z_i.clone(),
+            supernova.i,
+            1 - supernova.pc,
+            running_instances,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .is_err());
+
+        // out of range program counter
+        assert!(supernova.prove_step(&mut rng, 2, vec![]).is_err());
+    }
+}
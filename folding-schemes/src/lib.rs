@@ -4,8 +4,10 @@
 
 use ark_ec::{pairing::Pairing, CurveGroup};
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::CryptoRng;
 use ark_std::{fmt::Debug, rand::RngCore};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 use crate::frontend::FCircuit;
@@ -37,6 +39,8 @@ pub enum Error {
     // Relation errors
     #[error("Relation not satisfied")]
     NotSatisfied,
+    #[error("Relation not satisfied at {0}")]
+    UnsatisfiedConstraint(arith::UnsatisfiedConstraint),
     #[error("SNARK verification failed")]
     SNARKVerificationFail,
     #[error("IVC verification failed")]
@@ -81,6 +85,8 @@ pub enum Error {
     IncorrectBlinding(bool, String),
     #[error("Commitment verification failed")]
     CommitmentVerificationFail,
+    #[error("Invalid ptau file: {0}")]
+    InvalidPtau(String),
 
     // Other
     #[error("{0}")]
@@ -105,6 +111,67 @@ pub enum Error {
     NoMultiInstances,
     #[error("Missing 'other' instances, since this is a multi-instances folding scheme")]
     MissingOtherInstances,
+    #[error("Invalid checkpoint: {0}")]
+    InvalidCheckpoint(String),
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    // Ledger errors
+    #[error("Note is locked until height {0}, current height is {1}")]
+    NoteLocked(String, String),
+    #[error("Transaction expired at height {0}, current height is {1}")]
+    TransactionExpired(String, String),
+    #[error("Serial number {0} has already been spent")]
+    DoubleSpend(String),
+    #[error("Serial number {0} is already in the blockchain")]
+    DuplicateSerialNumber(String),
+    #[error("Value of asset {0} is not conserved")]
+    ValueNotConserved(String),
+    #[error("Spend of note {0} is not authorized")]
+    SpendNotAuthorized(String),
+    #[error("Note {0} is not in the ledger")]
+    UnknownNote(String),
+    #[error("Swap parties must contribute different assets")]
+    SwapSameAsset,
+    #[error("Note of asset {0} does not match the asset {1} of the swap")]
+    AssetMismatch(String, String),
+    #[error("Value {0} does not fit in 64 bits")]
+    ValueOutOfRange(String),
+    #[error("Nullifier {0} is used by a pending transaction with a higher or equal fee")]
+    PendingConflict(String),
+}
+
+/// Time spent in each of the phases of a folding step, returned by [`FoldingScheme::prove_step`].
+/// The same phases are also recorded as `tracing` spans, which can be collected with any
+/// `tracing` subscriber for a finer grained profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepMetrics {
+    /// time spent computing the next state and generating the witness of the augmented circuit
+    pub witness_generation: Duration,
+    /// time spent committing to the witness of the augmented circuit
+    pub commitment: Duration,
+    /// time spent folding the instances over the main curve, ie. computing the cross-terms in
+    /// Nova and ProtoGalaxy, and running the NIMFS (including the sum-check) in HyperNova
+    pub folding: Duration,
+    /// time spent generating and folding the CycleFold instances
+    pub cyclefold: Duration,
+    /// total time of the step
+    pub total: Duration,
+}
+
+/// Progress information reported by [`FoldingScheme::prove_steps`] after each proven step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepProgress {
+    /// index of the step in the stream of steps given to `prove_steps`
+    pub step: usize,
+    /// metrics of this step
+    pub metrics: StepMetrics,
+    /// time spent since the beginning of the `prove_steps` call
+    pub total_elapsed: Duration,
+    /// number of constraints of the augmented circuit
+    pub num_constraints: usize,
+    /// number of constraints of the CycleFold circuit
+    pub cf_num_constraints: usize,
 }
 
 /// FoldingScheme defines trait that is implemented by the diverse folding schemes. It is defined
@@ -127,6 +194,9 @@ where
     type MultiCommittedInstanceWithWitness: Debug; // type used for the extra instances in the multi-instance folding setting
     type CFInstance: Debug; // CycleFold CommittedInstance & Witness
 
+    // contains all the values needed to verify the IVC, see `ivc_proof` and `verify_proof`
+    type IVCProof: Debug + Clone + CanonicalSerialize + CanonicalDeserialize;
+
     fn preprocess(
         rng: impl RngCore,
         prep_param: &Self::PreprocessorParam,
@@ -138,16 +208,61 @@ where
         z_0: Vec<C1::ScalarField>, // initial state
     ) -> Result<Self, Error>;
 
+    // proves the next step of the IVC, returning the time spent in each of its phases
     fn prove_step(
         &mut self,
         rng: impl RngCore,
         external_inputs: Vec<C1::ScalarField>,
         other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
-    ) -> Result<(), Error>;
+    ) -> Result<StepMetrics, Error>;
+
+    /// Proves a step for each of the `(external_inputs, other_instances)` yielded by the given
+    /// iterator, so that long workloads can be streamed (eg. from a file or a channel) without
+    /// holding all the inputs in memory. Each item is passed to `prove_step`, so for the
+    /// multi-instance folding settings it has to contain the extra instances to be folded at that
+    /// step, and `None` otherwise. After each step, `on_step` is called with the [`StepProgress`]
+    /// of the step.
+    ///
+    /// The execution is aborted at the first error, either returned by `prove_step` or by
+    /// `on_step`, without consuming the remaining inputs. In that case the folding scheme is left
+    /// at the state of the last step that was successfully proven.
+    fn prove_steps<I, P>(
+        &mut self,
+        mut rng: impl RngCore,
+        steps: I,
+        mut on_step: P,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<
+            Item = (
+                Vec<C1::ScalarField>,
+                Option<Self::MultiCommittedInstanceWithWitness>,
+            ),
+        >,
+        P: FnMut(&StepProgress) -> Result<(), Error>,
+    {
+        let (num_constraints, cf_num_constraints) = self.num_constraints();
+        let start = Instant::now();
+        for (step, (external_inputs, other_instances)) in steps.into_iter().enumerate() {
+            let metrics = self.prove_step(&mut rng, external_inputs, other_instances)?;
+            on_step(&StepProgress {
+                step,
+                metrics,
+                total_elapsed: start.elapsed(),
+                num_constraints,
+                cf_num_constraints,
+            })?;
+        }
+        Ok(())
+    }
 
     // returns the state at the current step
     fn state(&self) -> Vec<C1::ScalarField>;
 
+    // returns the number of constraints of the circuits proven at each step, in the following
+    // order: (augmented_circuit, cyclefold_circuit)
+    fn num_constraints(&self) -> (usize, usize);
+
     // returns the instances at the current step, in the following order:
     // (running_instance, incoming_instance, cyclefold_instance)
     fn instances(
@@ -168,6 +283,13 @@ where
         incoming_instance: Self::IncomingInstance,
         cyclefold_instance: Self::CFInstance,
     ) -> Result<(), Error>;
+
+    // returns the IVCProof at the current step, which bundles the initial & current states, the
+    // number of steps and the instances, so that it can be stored or sent to a verifier
+    fn ivc_proof(&self) -> Self::IVCProof;
+
+    // verifies the given IVCProof, equivalent to calling `verify` with the values contained in it
+    fn verify_proof(vp: Self::VerifierParam, proof: &Self::IVCProof) -> Result<(), Error>;
 }
 
 /// Trait with auxiliary methods for multi-folding schemes (ie. HyperNova, ProtoGalaxy, etc),
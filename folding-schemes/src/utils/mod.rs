@@ -4,6 +4,7 @@ use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
 use ark_std::Zero;
 use sha3::{Digest, Sha3_256};
+use std::time::{Duration, Instant};
 
 use crate::arith::Arith;
 use crate::commitment::CommitmentScheme;
@@ -31,6 +32,15 @@ pub fn powers_of<F: PrimeField>(x: F, n: usize) -> Vec<F> {
     c
 }
 
+/// Runs `f` inside of the given `tracing` span, adding the time that it took to `elapsed`. Used to
+/// both trace and measure the phases of the folding steps, see [`crate::StepMetrics`].
+pub fn timed<T>(span: tracing::Span, elapsed: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let res = span.in_scope(f);
+    *elapsed += start.elapsed();
+    res
+}
+
 /// returns the coordinates of a commitment point. This is compatible with the arkworks
 /// GC.to_constraint_field()[..2]
 pub fn get_cm_coordinates<C: CurveGroup>(cm: &C) -> Vec<C::BaseField> {
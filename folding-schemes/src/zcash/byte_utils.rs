@@ -1,19 +1,207 @@
-use ark_bn254::Fr;
-use ark_ff::PrimeField;
-use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar, ToBytesGadget};
-use ark_relations::r1cs::ConstraintSystem;
-
-pub fn fpvar_to_bytes<F: PrimeField>(value: FpVar<F>) -> Vec<u8> {
-    // Convert FpVar to bytes
-    let bytes = value.to_bytes().unwrap();
-
-    // Convert bytes to Vec<u8>
-    return bytes.iter().map(|byte| byte.value().unwrap()).collect();
-}
-pub fn bytes_to_field_element_and_field_element_variable(bytes: Vec<u8>) -> (Fr, FpVar<Fr>) {
-    let cs = ConstraintSystem::new_ref();
-    // Create a constraint system
-    let a = Fr::from_le_bytes_mod_order(&bytes);
-    let recon = FpVar::new_witness(cs, || Ok(a)).unwrap();
-    (a, recon)
+/// Conversions between field elements and bytes, both natively and in-circuit, so that ledger
+/// objects can be hashed consistently in and out of the circuit.
+/// Field elements are unpacked into their canonical encoding (`F::BigInt::NUM_LIMBS * 8` bytes),
+/// and bytes are packed into field elements in chunks of `byte_capacity::<F>()` bytes, which
+/// always fit in the field, so the packing is injective for inputs of a given length.
+/// Since the canonical encoding is longer than `byte_capacity::<F>()`, packing the bytes of an
+/// unpacked element gives two elements instead of the original one: the inverse of the unpacking
+/// is [`field_element_from_bytes`] (and `BytesGadget::bytes_to_fpvar` in-circuit), which only
+/// accepts canonical encodings.
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    boolean::Boolean, fields::fp::FpVar, uint8::UInt8, ToBitsGadget, ToBytesGadget,
+};
+use ark_relations::r1cs::SynthesisError;
+
+use crate::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// returns the number of bytes that can be packed into a single field element
+pub fn byte_capacity<F: PrimeField>() -> usize {
+    ((F::MODULUS_BIT_SIZE - 1) / 8) as usize
+}
+
+/// Returns the canonical encoding of the field element
+pub fn field_element_to_bytes<F: PrimeField>(x: F, endianness: Endianness) -> Vec<u8> {
+    match endianness {
+        Endianness::Little => x.into_bigint().to_bytes_le(),
+        Endianness::Big => x.into_bigint().to_bytes_be(),
+    }
+}
+
+/// returns the number of bytes of the canonical encoding of a field element
+pub fn encoding_len<F: PrimeField>() -> usize {
+    F::BigInt::NUM_LIMBS * 8
+}
+
+/// Inverse of [`field_element_to_bytes`]: reads the field element from its canonical encoding,
+/// failing if the bytes do not have the length of the encoding or encode a value that is not
+/// smaller than the modulus.
+pub fn field_element_from_bytes<F: PrimeField>(
+    bytes: &[u8],
+    endianness: Endianness,
+) -> Result<F, Error> {
+    if bytes.len() != encoding_len::<F>() {
+        return Err(Error::NotExpectedLength(bytes.len(), encoding_len::<F>()));
+    }
+    let mut bytes = bytes.to_vec();
+    if endianness == Endianness::Big {
+        bytes.reverse();
+    }
+    let bits: Vec<bool> = bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect();
+    F::from_bigint(F::BigInt::from_bits_le(&bits)).ok_or_else(|| {
+        Error::BigIntConversionError("bytes are not a canonical field element".to_string())
+    })
+}
+
+/// Packs the bytes into field elements, `byte_capacity::<F>()` bytes per element. The endianness
+/// applies to the bytes inside each chunk, the chunks follow the order of the input.
+/// This is not the inverse of [`field_element_to_bytes`], see [`field_element_from_bytes`].
+pub fn bytes_to_field_elements<F: PrimeField>(bytes: &[u8], endianness: Endianness) -> Vec<F> {
+    bytes
+        .chunks(byte_capacity::<F>())
+        .map(|chunk| match endianness {
+            Endianness::Little => F::from_le_bytes_mod_order(chunk),
+            Endianness::Big => F::from_be_bytes_mod_order(chunk),
+        })
+        .collect()
+}
+
+pub struct BytesGadget;
+impl BytesGadget {
+    /// In-circuit counterpart of [`field_element_to_bytes`]. The decomposition is enforced to be
+    /// the canonical one (the value of the bytes is smaller than the modulus), and each byte is
+    /// made of boolean constrained bits.
+    pub fn fpvar_to_bytes<F: PrimeField>(
+        x: &FpVar<F>,
+        endianness: Endianness,
+    ) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut bytes = x.to_bytes()?;
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    /// In-circuit counterpart of [`field_element_from_bytes`], the inverse of `fpvar_to_bytes`.
+    /// The bytes are enforced to be a canonical encoding, that is, their value is smaller than
+    /// the modulus.
+    pub fn bytes_to_fpvar<F: PrimeField>(
+        bytes: &[UInt8<F>],
+        endianness: Endianness,
+    ) -> Result<FpVar<F>, SynthesisError> {
+        if bytes.len() != encoding_len::<F>() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut bytes = bytes.to_vec();
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        // `le_bits_to_fp_var` enforces the bits to be smaller than the modulus when there are at
+        // least `F::MODULUS_BIT_SIZE` of them
+        Boolean::le_bits_to_fp_var(&bytes.to_bits_le()?)
+    }
+
+    /// In-circuit counterpart of [`bytes_to_field_elements`].
+    pub fn bytes_to_fpvars<F: PrimeField>(
+        bytes: &[UInt8<F>],
+        endianness: Endianness,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        bytes
+            .chunks(byte_capacity::<F>())
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                if endianness == Endianness::Big {
+                    chunk.reverse();
+                }
+                Boolean::le_bits_to_fp_var(&chunk.to_bits_le()?)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{rand::RngCore, UniformRand};
+
+    #[test]
+    fn test_fpvar_to_bytes() {
+        let mut rng = ark_std::test_rng();
+        let x = Fr::rand(&mut rng);
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let xVar = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+            let bytesVar = BytesGadget::fpvar_to_bytes(&xVar, endianness).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(
+                bytesVar.value().unwrap(),
+                field_element_to_bytes(x, endianness)
+            );
+        }
+        assert_eq!(
+            field_element_to_bytes(Fr::from(1_u32), Endianness::Big).last(),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn test_bytes_to_fpvars() {
+        let mut rng = ark_std::test_rng();
+        // more than two chunks, the last one being shorter
+        let mut bytes = vec![0u8; 2 * byte_capacity::<Fr>() + 5];
+        rng.fill_bytes(&mut bytes);
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let bytesVar = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(bytes.clone())).unwrap();
+            let xVar = BytesGadget::bytes_to_fpvars(&bytesVar, endianness).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            let x = bytes_to_field_elements::<Fr>(&bytes, endianness);
+            assert_eq!(x.len(), 3);
+            assert_eq!(xVar.value().unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn test_unpack_pack_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let x = Fr::rand(&mut rng);
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let bytes = field_element_to_bytes(x, endianness);
+            assert_eq!(
+                field_element_from_bytes::<Fr>(&bytes, endianness).unwrap(),
+                x
+            );
+            // the chunked packing is not the inverse of the unpacking
+            assert_eq!(bytes_to_field_elements::<Fr>(&bytes, endianness).len(), 2);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let xVar = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+            let bytesVar = BytesGadget::fpvar_to_bytes(&xVar, endianness).unwrap();
+            let recon = BytesGadget::bytes_to_fpvar(&bytesVar, endianness).unwrap();
+            recon.enforce_equal(&xVar).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // encodings of the modulus, which are not canonical
+        let modulus = Fr::MODULUS.to_bytes_le();
+        assert!(field_element_from_bytes::<Fr>(&modulus, Endianness::Little).is_err());
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let bytesVar = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(modulus)).unwrap();
+        BytesGadget::bytes_to_fpvar(&bytesVar, Endianness::Little).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+        // encodings of the wrong length
+        assert!(field_element_from_bytes::<Fr>(&[1, 2, 3], Endianness::Little).is_err());
+    }
 }
@@ -0,0 +1,452 @@
+/// Staging area between the creation of the transactions and their inclusion in the ledger.
+/// Transactions are validated natively against the ledger when submitted, pending transactions
+/// are kept conflict-free (a nullifier can only be revealed by one of them), and batches are
+/// selected by fee to be folded by the prover with the [`BatchCircuit`], one transaction per
+/// step (see [`fold_batch`]).
+/// The nullifier of a transaction is the serial number that it reveals, which the blockchain
+/// records when the transaction is included (see [`Blockchain::is_nullified`]).
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::{poseidon::PoseidonConfig, Absorb},
+};
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::rand::RngCore;
+use core::marker::PhantomData;
+use std::collections::HashMap;
+
+use super::time_lock::{check_time_lock, TimeLockGadget};
+use super::transaction::{Blockchain, Transaction};
+use crate::{frontend::FCircuit, Error, FoldingScheme};
+
+pub struct PendingTransaction<F: PrimeField> {
+    transaction: Transaction<F>,
+    nullifier: F,
+    fee: u64,
+    seq: u64, // arrival order, used to break ties between equal fees
+}
+impl<F> PendingTransaction<F>
+where
+    F: PrimeField,
+{
+    pub fn transaction(&self) -> &Transaction<F> {
+        &self.transaction
+    }
+    pub fn into_transaction(self) -> Transaction<F> {
+        self.transaction
+    }
+    pub fn nullifier(&self) -> F {
+        self.nullifier
+    }
+    pub fn fee(&self) -> u64 {
+        self.fee
+    }
+    /// returns the external inputs of the [`BatchCircuit`] step that folds this transaction
+    pub fn to_external_inputs(&self) -> Result<Vec<F>, Error> {
+        Ok(vec![
+            self.nullifier,
+            self.transaction.lock_until().value()?,
+            self.transaction.expiry_height().value()?,
+        ])
+    }
+}
+
+pub struct Mempool<F: PrimeField> {
+    pending: HashMap<F, PendingTransaction<F>>, // indexed by nullifier
+    next_seq: u64,
+}
+impl<F> Mempool<F>
+where
+    F: PrimeField,
+{
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            next_seq: 0,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+    pub fn contains(&self, nullifier: &F) -> bool {
+        self.pending.contains_key(nullifier)
+    }
+
+    /// Checks that the transaction can be included in the blockchain at its current height, and
+    /// returns its nullifier.
+    fn validate(blockchain: &Blockchain<F>, transaction: &Transaction<F>) -> Result<F, Error> {
+        let nullifier = transaction.serial_number().value()?;
+        if blockchain.is_nullified(&nullifier) {
+            return Err(Error::DoubleSpend(nullifier.to_string()));
+        }
+        check_time_lock(
+            F::from(blockchain.height()),
+            transaction.lock_until().value()?,
+            transaction.expiry_height().value()?,
+        )?;
+        Ok(nullifier)
+    }
+
+    /// Adds the transaction to the mempool. If a pending transaction already reveals the same
+    /// nullifier, the new one replaces it only if it pays a higher fee.
+    pub fn submit(
+        &mut self,
+        blockchain: &Blockchain<F>,
+        transaction: Transaction<F>,
+        fee: u64,
+    ) -> Result<(), Error> {
+        let nullifier = Self::validate(blockchain, &transaction)?;
+        if let Some(conflicting) = self.pending.get(&nullifier) {
+            if conflicting.fee >= fee {
+                return Err(Error::PendingConflict(nullifier.to_string()));
+            }
+        }
+        self.pending.insert(
+            nullifier,
+            PendingTransaction {
+                transaction,
+                nullifier,
+                fee,
+                seq: self.next_seq,
+            },
+        );
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the pending transactions that are no longer valid for the current
+    /// state of the blockchain (their nullifier got revealed, or they expired).
+    pub fn prune(&mut self, blockchain: &Blockchain<F>) -> Vec<PendingTransaction<F>> {
+        let invalid: Vec<F> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| Self::validate(blockchain, &p.transaction).is_err())
+            .map(|(nullifier, _)| *nullifier)
+            .collect();
+        invalid
+            .iter()
+            .filter_map(|nullifier| self.pending.remove(nullifier))
+            .collect()
+    }
+
+    /// returns the pending transactions ordered by fee (highest first), and by arrival order for
+    /// equal fees
+    fn ordered(&self) -> Vec<&PendingTransaction<F>> {
+        let mut ordered: Vec<&PendingTransaction<F>> = self.pending.values().collect();
+        ordered.sort_by(|a, b| b.fee.cmp(&a.fee).then(a.seq.cmp(&b.seq)));
+        ordered
+    }
+
+    /// returns, without removing them, up to `max` transactions that can be included at the
+    /// current height of the blockchain, ordered by fee
+    pub fn select_batch(
+        &self,
+        blockchain: &Blockchain<F>,
+        max: usize,
+    ) -> Vec<&PendingTransaction<F>> {
+        self.ordered()
+            .into_iter()
+            .filter(|p| Self::validate(blockchain, &p.transaction).is_ok())
+            .take(max)
+            .collect()
+    }
+
+    /// Prunes the invalid transactions and removes from the mempool up to `max` transactions,
+    /// ordered by fee, to be folded by the prover. Since pending transactions never share a
+    /// nullifier, the returned batch is conflict-free.
+    pub fn take_batch(
+        &mut self,
+        blockchain: &Blockchain<F>,
+        max: usize,
+    ) -> Vec<PendingTransaction<F>> {
+        self.prune(blockchain);
+        let selected: Vec<F> = self
+            .ordered()
+            .into_iter()
+            .take(max)
+            .map(|p| p.nullifier)
+            .collect();
+        selected
+            .iter()
+            .filter_map(|nullifier| self.pending.remove(nullifier))
+            .collect()
+    }
+}
+impl<F> Default for Mempool<F>
+where
+    F: PrimeField,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of external inputs of each step of the [`BatchCircuit`]: `[nullifier, lock_until,
+/// expiry_height]`.
+pub const BATCH_TRANSACTION_LEN: usize = 3;
+
+/// Circuit that folds a batch of transactions taken from the mempool, one transaction per step.
+/// The state is `[height, batch_hash]`, where `height` is the block height at which the batch is
+/// included, and `batch_hash` is a poseidon hash chain of the external inputs of each step, which
+/// binds the nullifiers and the lock and expiry heights of the folded transactions to the state.
+/// At each step, the time lock of the transaction is checked against `height`.
+#[derive(Clone, Debug)]
+pub struct BatchCircuit<F: PrimeField + Absorb> {
+    _f: PhantomData<F>,
+    poseidon_config: PoseidonConfig<F>,
+}
+impl<F: PrimeField + Absorb> FCircuit<F> for BatchCircuit<F> {
+    type Params = PoseidonConfig<F>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            _f: PhantomData,
+            poseidon_config: params,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        2
+    }
+
+    fn external_inputs_len(&self) -> usize {
+        BATCH_TRANSACTION_LEN
+    }
+
+    fn step_native(
+        &self,
+        _i: usize,
+        z_i: Vec<F>,
+        external_inputs: Vec<F>,
+    ) -> Result<Vec<F>, Error> {
+        if z_i.len() != self.state_len() {
+            return Err(Error::NotSameLength(
+                "state_len()".to_string(),
+                self.state_len(),
+                "z_i.len()".to_string(),
+                z_i.len(),
+            ));
+        }
+        if external_inputs.len() != self.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "external_inputs_len()".to_string(),
+                self.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+        let height = z_i[0];
+        check_time_lock(height, external_inputs[1], external_inputs[2])?;
+        let batch_hash = CRH::<F>::evaluate(
+            &self.poseidon_config,
+            [vec![z_i[1]], external_inputs].concat(),
+        )
+        .map_err(|e| Error::Other(e.to_string()))?;
+        Ok(vec![height, batch_hash])
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Vec<FpVar<F>>,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let crh_params =
+            CRHParametersVar::<F>::new_constant(cs.clone(), self.poseidon_config.clone())?;
+        let height = &z_i[0];
+        TimeLockGadget::enforce_time_lock(height, &external_inputs[1], &external_inputs[2])?;
+        let batch_hash =
+            CRHGadget::<F>::evaluate(&crh_params, &[&z_i[1..], &external_inputs[..]].concat())?;
+        Ok(vec![height.clone(), batch_hash])
+    }
+}
+
+/// Folds the batch (as returned by `Mempool::take_batch`) with the given folding scheme, one
+/// transaction per step. The folding scheme must have been initialized with the `BatchCircuit`
+/// and `z_0 = [height, batch_hash]`.
+pub fn fold_batch<C1, C2, FS>(
+    folding_scheme: &mut FS,
+    rng: impl RngCore,
+    batch: &[PendingTransaction<C1::ScalarField>],
+) -> Result<(), Error>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    C1::ScalarField: Absorb,
+    FS: FoldingScheme<C1, C2, BatchCircuit<C1::ScalarField>>,
+{
+    let steps = batch
+        .iter()
+        .map(|p| Ok((p.to_external_inputs()?, None)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    folding_scheme.prove_steps(rng, steps, |_| Ok(()))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{constraints::GVar, Fr, G1Projective as Projective};
+    use ark_grumpkin::{constraints::GVar as GVar2, Projective as Projective2};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{UniformRand, Zero};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    fn transaction(cs: ConstraintSystemRef<Fr>, sn_secret: u64) -> Transaction<Fr> {
+        let mut rng = ark_std::test_rng();
+        let tid = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let value = FpVar::new_witness(cs.clone(), || Ok(Fr::from(10_u32))).unwrap();
+        let sender = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let receiver = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let sn_secret = FpVar::new_witness(cs.clone(), || Ok(Fr::from(sn_secret))).unwrap();
+        Transaction::new(tid, value, sender, receiver, sn_secret)
+    }
+
+    #[test]
+    fn test_mempool() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut blockchain = Blockchain::<Fr>::new();
+        let mut mempool = Mempool::<Fr>::new();
+
+        // a transaction whose serial number is already in the ledger is rejected
+        let included = transaction(cs.clone(), 0);
+        blockchain.append_transaction(included.root(), included.serial_number());
+        assert!(mempool
+            .submit(&blockchain, transaction(cs.clone(), 0), 100)
+            .is_err());
+
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 1), 5)
+            .unwrap();
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 2), 20)
+            .unwrap();
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 3), 10)
+            .unwrap();
+
+        // a conflicting transaction only replaces the pending one if it pays a higher fee
+        assert!(mempool
+            .submit(&blockchain, transaction(cs.clone(), 2), 20)
+            .is_err());
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 1), 30)
+            .unwrap();
+        assert_eq!(mempool.len(), 3);
+
+        // a transaction is valid up to its expiry height, and gets pruned after it
+        let one = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1_u32))).unwrap();
+        let expiring = transaction(cs.clone(), 4).with_expiry_height(one);
+        blockchain.advance_height();
+        mempool.submit(&blockchain, expiring, 100).unwrap();
+        assert_eq!(mempool.len(), 4);
+        blockchain.advance_height();
+        let expired = mempool.prune(&blockchain);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(mempool.len(), 3);
+
+        let fees: Vec<u64> = mempool
+            .select_batch(&blockchain, 2)
+            .iter()
+            .map(|p| p.fee())
+            .collect();
+        assert_eq!(fees, vec![30, 20]);
+
+        let batch = mempool.take_batch(&blockchain, 2);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(mempool.len(), 1);
+        for p in batch {
+            blockchain.append_transaction(p.transaction().root(), p.transaction().serial_number());
+        }
+        assert_eq!(mempool.take_batch(&blockchain, 2)[0].fee(), 10);
+        assert!(mempool.is_empty());
+    }
+
+    #[test]
+    fn test_fold_batch() {
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            BatchCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut blockchain = Blockchain::<Fr>::new();
+        blockchain.advance_height();
+        let mut mempool = Mempool::<Fr>::new();
+
+        let lock_until = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1_u32))).unwrap();
+        let locked = transaction(cs.clone(), 1).with_lock_until(lock_until);
+        mempool.submit(&blockchain, locked, 30).unwrap();
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 2), 20)
+            .unwrap();
+        mempool
+            .submit(&blockchain, transaction(cs.clone(), 3), 10)
+            .unwrap();
+        let batch = mempool.take_batch(&blockchain, 2);
+        assert_eq!(batch.len(), 2);
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = BatchCircuit::<Fr>::new(poseidon_config.clone()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit.clone());
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        // the batch is included at the current height of the blockchain
+        let z_0 = vec![Fr::from(blockchain.height()), Fr::zero()];
+        let mut nova = N::init(&nova_params, F_circuit.clone(), z_0.clone()).unwrap();
+        fold_batch::<Projective, Projective2, N>(&mut nova, &mut rng, &batch).unwrap();
+
+        // the state commits to the folded transactions
+        let mut expected = z_0.clone();
+        for (i, p) in batch.iter().enumerate() {
+            expected = F_circuit
+                .step_native(i, expected, p.to_external_inputs().unwrap())
+                .unwrap();
+        }
+        assert_eq!(nova.state(), expected);
+
+        let (running_instance, incoming_instance, cyclefold_instance) = nova.instances();
+        N::verify(
+            nova_params.1,
+            z_0,
+            nova.state(),
+            Fr::from(batch.len() as u32),
+            running_instance,
+            incoming_instance,
+            cyclefold_instance,
+        )
+        .unwrap();
+
+        // a transaction that is still locked at the height of the batch can not be folded
+        let lock_until = FpVar::new_witness(cs.clone(), || Ok(Fr::from(2_u32))).unwrap();
+        let locked = PendingTransaction {
+            transaction: transaction(cs, 4).with_lock_until(lock_until),
+            nullifier: Fr::from(4_u32),
+            fee: 0,
+            seq: 0,
+        };
+        assert!(fold_batch::<Projective, Projective2, N>(&mut nova, &mut rng, &[locked]).is_err());
+    }
+}
@@ -2,12 +2,17 @@ pub mod byte_utils;
 pub mod coin_circuit;
 pub mod coin_gadget;
 pub mod fq_hasher;
+pub mod mempool;
 pub mod merkle_gadget;
 pub mod merkle_tree;
 pub mod public_ledger;
 pub mod public_ledger_gadget;
 pub mod spend_circuit;
 pub mod spend_key;
+pub mod supply;
+pub mod swap;
 pub mod t_circuit;
+pub mod time_lock;
 pub mod transaction;
+pub mod value;
 pub mod zcash_hash_gadget;
@@ -11,10 +11,29 @@ use ark_crypto_primitives::{
     sponge::{poseidon::PoseidonConfig, Absorb},
 };
 use ark_ff::PrimeField;
-use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, fields::FieldVar};
 use ark_relations::r1cs::SynthesisError;
 
+use super::time_lock::{check_time_lock, TimeLockGadget};
 use crate::{frontend::FCircuit, Error};
+
+/// Number of spend keys (and of transaction hashes) consumed at each step
+pub const LEDGER_ENTRIES_PER_STEP: usize = 3;
+
+/// Computes the commitment to the transactions that is kept in the state of the
+/// [`PublicLedgerCircuit`]: the poseidon hash of the transaction hashes followed by the
+/// `lock_until` and `expiry_height` of the transaction. Committing to the heights binds them to
+/// the public state, so that the prover can not choose them freely when proving a step.
+pub fn transactions_commitment<F: PrimeField + Absorb>(
+    poseidon_config: &PoseidonConfig<F>,
+    transaction_hashes: &[F],
+    lock_until: F,
+    expiry_height: F,
+) -> Result<F, Error> {
+    let inputs = [transaction_hashes, &[lock_until, expiry_height]].concat();
+    CRH::<F>::evaluate(poseidon_config, inputs).map_err(|e| Error::Other(e.to_string()))
+}
+
 #[derive(Clone, Debug)]
 pub struct PublicLedgerCircuit<F: PrimeField>
 where
@@ -37,58 +56,171 @@ where
     }
 
     fn state_len(&self) -> usize {
-        // One public input: the poseidon hash of the elements of vector T
-        2
+        // The poseidon hash of the spend keys, the commitment to the transactions (see
+        // `transactions_commitment`) and the current block height
+        3
     }
 
     fn external_inputs_len(&self) -> usize {
-        // The raw T vector which should be secret
-        // The size of T should match whatever number is here
-        6
+        // The spend keys and the transaction hashes, which should be secret, followed by the
+        // lock_until and expiry heights of the transaction included at this step
+        2 * LEDGER_ENTRIES_PER_STEP + 2
     }
     fn step_native(
         &self,
-        i: usize,
+        _i: usize,
         z_i: Vec<F>,
         external_inputs: Vec<F>, // inputs that are not part of the state
     ) -> Result<Vec<F>, Error> {
-        let mut inputs: Vec<F> = [].to_vec();
-        let mid = external_inputs.len() / 2;
-        let (first_half, second_half) = external_inputs.split_at(mid);
-        let first_half_vec: Vec<F> = first_half.to_vec();
-        let second_half_vec: Vec<F> = second_half.to_vec();
-
-        for ex_input in first_half_vec {
-            inputs.push(ex_input)
+        if z_i.len() != self.state_len() {
+            return Err(Error::NotSameLength(
+                "state_len()".to_string(),
+                self.state_len(),
+                "z_i.len()".to_string(),
+                z_i.len(),
+            ));
         }
-        let h = CRH::<F>::evaluate(&self.poseidon_config, inputs).unwrap();
-
-        let mut inputs: Vec<F> = [].to_vec();
-        for ex_input in second_half_vec {
-            inputs.push(ex_input)
+        if external_inputs.len() != self.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "external_inputs_len()".to_string(),
+                self.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
         }
-        let h1 = CRH::<F>::evaluate(&self.poseidon_config, inputs).unwrap();
+        let (spend_keys, rest) = external_inputs.split_at(LEDGER_ENTRIES_PER_STEP);
+        let (transaction_hashes, heights) = rest.split_at(LEDGER_ENTRIES_PER_STEP);
+
+        let h = CRH::<F>::evaluate(&self.poseidon_config, spend_keys.to_vec())
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let h1 = transactions_commitment(
+            &self.poseidon_config,
+            transaction_hashes,
+            heights[0],
+            heights[1],
+        )?;
 
         // I don't think we can do multiple vectors of external inputs so maybe we just allocate one big vector and
         //  half is for spend keys and the other for transaction hashes
-        assert_eq!(z_i[0], h);
-        assert_eq!(z_i[1], h1);
-        Ok(vec![h, h1])
+        if z_i[0] != h || z_i[1] != h1 {
+            return Err(Error::NotEqual);
+        }
+
+        let height = z_i[2];
+        check_time_lock(height, heights[0], heights[1])?;
+        Ok(vec![h, h1, height + F::one()])
     }
     fn generate_step_constraints(
         &self,
         cs: ark_relations::r1cs::ConstraintSystemRef<F>,
-        i: usize,
+        _i: usize,
         z_i: Vec<ark_r1cs_std::fields::fp::FpVar<F>>,
         external_inputs: Vec<ark_r1cs_std::fields::fp::FpVar<F>>, // inputs that are not part of the state
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
         let crh_params =
             CRHParametersVar::<F>::new_constant(cs.clone(), self.poseidon_config.clone())?;
-        let spend_keys = &external_inputs[0];
-        let transaction_hashes = &external_inputs[1];
-        let h: FpVar<F> = CRHGadget::<F>::evaluate(&crh_params, &[spend_keys.to_owned()])?;
-        let h1 = CRHGadget::<F>::evaluate(&crh_params, &[transaction_hashes.to_owned()])?;
+        let (spend_keys, rest) = external_inputs.split_at(LEDGER_ENTRIES_PER_STEP);
+        let heights = &rest[LEDGER_ENTRIES_PER_STEP..];
+
+        let h: FpVar<F> = CRHGadget::<F>::evaluate(&crh_params, spend_keys)?;
+        // the lock_until and expiry heights are committed together with the transaction hashes,
+        // so that they are bound to the state
+        let h1 = CRHGadget::<F>::evaluate(&crh_params, rest)?;
+        h.enforce_equal(&z_i[0])?;
+        h1.enforce_equal(&z_i[1])?;
+
+        let height = &z_i[2];
+        TimeLockGadget::enforce_time_lock(height, &heights[0], &heights[1])?;
+        Ok(vec![h, h1, height + FpVar::<F>::one()])
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_public_ledger_time_lock() {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let circuit = PublicLedgerCircuit::<Fr>::new(poseidon_config.clone()).unwrap();
+
+        let spend_keys: Vec<Fr> = (0..LEDGER_ENTRIES_PER_STEP)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let transaction_hashes: Vec<Fr> = (0..LEDGER_ENTRIES_PER_STEP)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let height = Fr::from(10_u32);
+
+        // (committed lock_until & expiry_height, lock_until & expiry_height given as external
+        // inputs, expected)
+        let cases = [
+            ((5_u32, 20_u32), (5_u32, 20_u32), true),
+            ((11, 0), (11, 0), false), // spent before lock_until
+            ((0, 9), (0, 9), false),   // expired
+            // the prover can not use other heights than the committed ones to bypass the lock
+            ((11, 0), (0, 0), false),
+            ((0, 9), (0, 0), false),
+        ];
+        for ((lock_until, expiry_height), (lock_until_w, expiry_height_w), expected) in cases {
+            let z_i = vec![
+                CRH::<Fr>::evaluate(&poseidon_config, spend_keys.clone()).unwrap(),
+                transactions_commitment(
+                    &poseidon_config,
+                    &transaction_hashes,
+                    Fr::from(lock_until),
+                    Fr::from(expiry_height),
+                )
+                .unwrap(),
+                height,
+            ];
+            let external_inputs = [
+                spend_keys.clone(),
+                transaction_hashes.clone(),
+                vec![Fr::from(lock_until_w), Fr::from(expiry_height_w)],
+            ]
+            .concat();
+
+            let z_i1 = circuit.step_native(0, z_i.clone(), external_inputs.clone());
+            assert_eq!(z_i1.is_ok(), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone())).unwrap();
+            let external_inputsVar =
+                Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(external_inputs)).unwrap();
+            let z_i1Var = circuit
+                .generate_step_constraints(cs.clone(), 0, z_iVar, external_inputsVar)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+            if expected {
+                assert_eq!(z_i1Var.value().unwrap(), z_i1.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_public_ledger_step_native_errors() {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let circuit = PublicLedgerCircuit::<Fr>::new(poseidon_config).unwrap();
+        let z_i = vec![Fr::from(1_u32), Fr::from(2_u32), Fr::from(3_u32)];
 
-        Ok(vec![h, h1])
+        // wrong number of external inputs
+        assert!(matches!(
+            circuit.step_native(0, z_i.clone(), vec![Fr::from(0_u32)]),
+            Err(Error::NotSameLength(..))
+        ));
+        // external inputs that do not match the state
+        let external_inputs = vec![Fr::from(0_u32); circuit.external_inputs_len()];
+        assert!(matches!(
+            circuit.step_native(0, z_i, external_inputs),
+            Err(Error::NotEqual)
+        ));
     }
 }
@@ -0,0 +1,695 @@
+/// Tracks the shielded supply of each asset across the folded ledger, and allows to prove with
+/// the Decider that the supply of an asset equals a public number.
+/// Each step folds a Pour, which spends `POUR_NOTES` shielded notes and creates `POUR_NOTES` new
+/// ones, while moving public value into (`vpub_old`, the minted value) and out of (`vpub_new`, the
+/// unshielded value) the pool. The circuit enforces the balance of the Pour and derives the change
+/// of the supply from the values of its notes, so the state contains, for each asset, the total
+/// minted value minus the total unshielded value. Since the Decider exposes `z_0` and `z_i` as
+/// public inputs, a verifier that checks `z_0` to be the empty pool and `z_i` to match the claimed
+/// supply obtains a proof that no Pour inflated the pool.
+/// The notes of the folded Pours are the ones settled in the ledger by `Blockchain::append_pour`:
+/// the state also keeps a hash chain of the Pours (see [`pours_hash`]), which the verifier
+/// recomputes from the Pours returned by the ledger.
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::{poseidon::PoseidonConfig, Absorb},
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    groups::GroupOpsBounds,
+    prelude::CurveVar,
+    R1CSVar, ToConstraintFieldGadget,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::Zero;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use super::transaction::Transaction;
+use super::value::{check_value, ValueGadget};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::CF2;
+use crate::folding::nova::{CommittedInstance, Nova};
+use crate::frontend::FCircuit;
+use crate::{Decider, Error};
+
+/// Number of notes spent, and of notes created, by each Pour
+pub const POUR_NOTES: usize = 2;
+
+/// Number of field elements describing each note of a Pour, see [`PourNote`].
+pub const POUR_NOTE_LEN: usize = 6;
+
+/// Public fields of a note spent or created by a Pour, in the layout `[serial_number, value,
+/// asset_id, receiver_public_key, lock_until, expiry_height]`, which are the fields of the note
+/// that the `SwapLedgerCircuit` commits to as well.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PourNote<F: PrimeField> {
+    pub serial_number: F,
+    pub value: F,
+    pub asset_id: F,
+    pub receiver_public_key: F,
+    pub lock_until: F,
+    pub expiry_height: F,
+}
+impl<F: PrimeField> PourNote<F> {
+    /// returns the public fields of the given note
+    pub fn from_transaction(note: &Transaction<F>) -> Result<Self, Error> {
+        Ok(Self {
+            serial_number: note.serial_number().value()?,
+            value: note.value().value()?,
+            asset_id: note.asset_id().value()?,
+            receiver_public_key: note.receiver_address().public_key().value()?,
+            lock_until: note.lock_until().value()?,
+            expiry_height: note.expiry_height().value()?,
+        })
+    }
+
+    fn from_slice(note: &[F]) -> Self {
+        Self {
+            serial_number: note[0],
+            value: note[1],
+            asset_id: note[2],
+            receiver_public_key: note[3],
+            lock_until: note[4],
+            expiry_height: note[5],
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<F> {
+        vec![
+            self.serial_number,
+            self.value,
+            self.asset_id,
+            self.receiver_public_key,
+            self.lock_until,
+            self.expiry_height,
+        ]
+    }
+}
+
+/// A Pour of a single asset, in the layout of the external inputs of the [`SupplyLedgerCircuit`]:
+/// `[vpub_old, vpub_new, old_notes, new_notes]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pour<F: PrimeField> {
+    /// public value that enters the pool, ie. the minted value
+    pub vpub_old: F,
+    /// public value that leaves the pool, ie. the unshielded value
+    pub vpub_new: F,
+    /// spent notes. As in Zcash, notes of value zero are dummy notes, used to mint or to spend
+    /// less than `POUR_NOTES` notes
+    pub old_notes: [PourNote<F>; POUR_NOTES],
+    /// created notes
+    pub new_notes: [PourNote<F>; POUR_NOTES],
+}
+impl<F: PrimeField> Pour<F> {
+    /// returns the Pour spending and creating the given notes
+    pub fn from_transactions(
+        vpub_old: F,
+        vpub_new: F,
+        old_notes: &[Transaction<F>; POUR_NOTES],
+        new_notes: &[Transaction<F>; POUR_NOTES],
+    ) -> Result<Self, Error> {
+        let notes = |notes: &[Transaction<F>; POUR_NOTES]| {
+            notes
+                .iter()
+                .map(PourNote::from_transaction)
+                .collect::<Result<Vec<_>, Error>>()
+                .map(|notes| [notes[0].clone(), notes[1].clone()])
+        };
+        Ok(Self {
+            vpub_old,
+            vpub_new,
+            old_notes: notes(old_notes)?,
+            new_notes: notes(new_notes)?,
+        })
+    }
+
+    fn from_slice(external_inputs: &[F]) -> Self {
+        let mut notes = external_inputs[2..]
+            .chunks(POUR_NOTE_LEN)
+            .map(PourNote::from_slice);
+        let mut next = || notes.next().unwrap();
+        Self {
+            vpub_old: external_inputs[0],
+            vpub_new: external_inputs[1],
+            old_notes: [next(), next()],
+            new_notes: [next(), next()],
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<F> {
+        let notes = self.old_notes.iter().chain(self.new_notes.iter());
+        [vec![self.vpub_old, self.vpub_new]]
+            .into_iter()
+            .chain(notes.map(PourNote::to_vec))
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    /// returns the asset of the Pour, which is the asset of its first spent note
+    pub fn asset_id(&self) -> F {
+        self.old_notes[0].asset_id
+    }
+
+    /// Checks natively that all the notes are of the same asset, that all the values fit in 64
+    /// bits and that the Pour is balanced:
+    /// `vpub_old + sum(old_values) == vpub_new + sum(new_values)`.
+    pub fn check_balance(&self) -> Result<(), Error> {
+        let notes = self.old_notes.iter().chain(self.new_notes.iter());
+        for note in notes.clone() {
+            if note.asset_id != self.asset_id() {
+                return Err(Error::AssetMismatch(
+                    note.asset_id.to_string(),
+                    self.asset_id().to_string(),
+                ));
+            }
+        }
+        let values = [self.vpub_old, self.vpub_new]
+            .into_iter()
+            .chain(notes.map(|note| note.value));
+        for value in values {
+            check_value(value)?;
+        }
+        let inputs: F = self.vpub_old + self.spent();
+        let outputs: F = self.vpub_new + self.created();
+        if inputs != outputs {
+            return Err(Error::ValueNotConserved(self.asset_id().to_string()));
+        }
+        Ok(())
+    }
+
+    fn spent(&self) -> F {
+        self.old_notes.iter().map(|note| note.value).sum()
+    }
+
+    fn created(&self) -> F {
+        self.new_notes.iter().map(|note| note.value).sum()
+    }
+}
+
+/// Computes the next element of the hash chain of the Pours that is kept in the state of the
+/// [`SupplyLedgerCircuit`]: the poseidon hash of the previous element followed by the Pour, as
+/// returned by `Pour::to_vec`. The chain starts at zero.
+pub fn pours_hash<F: PrimeField + Absorb>(
+    poseidon_config: &PoseidonConfig<F>,
+    previous: F,
+    pour: &Pour<F>,
+) -> Result<F, Error> {
+    let inputs = [vec![previous], pour.to_vec()].concat();
+    CRH::<F>::evaluate(poseidon_config, inputs).map_err(|e| Error::Other(e.to_string()))
+}
+
+/// Circuit that folds, at each step, one Pour (see [`Pour`]).
+/// The state is `[pours_hash, supplies]`, where `pours_hash` is the hash chain of the folded Pours
+/// (see [`pours_hash`]) and `supplies` has one element per asset, holding its shielded supply,
+/// where the `asset_id` of the notes is the index of the asset. The external inputs are the Pour,
+/// as returned by `Pour::to_vec`.
+#[derive(Clone, Debug)]
+pub struct SupplyLedgerCircuit<F: PrimeField + Absorb> {
+    _f: PhantomData<F>,
+    poseidon_config: PoseidonConfig<F>,
+    pub n_assets: usize,
+}
+impl<F: PrimeField + Absorb> FCircuit<F> for SupplyLedgerCircuit<F> {
+    /// poseidon config and number of tracked assets
+    type Params = (PoseidonConfig<F>, usize);
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        let (poseidon_config, n_assets) = params;
+        if n_assets == 0 {
+            return Err(Error::CantBeZero("n_assets".to_string()));
+        }
+        Ok(Self {
+            _f: PhantomData,
+            poseidon_config,
+            n_assets,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        1 + self.n_assets
+    }
+
+    fn external_inputs_len(&self) -> usize {
+        2 + 2 * POUR_NOTES * POUR_NOTE_LEN
+    }
+
+    fn step_native(
+        &self,
+        _i: usize,
+        z_i: Vec<F>,
+        external_inputs: Vec<F>,
+    ) -> Result<Vec<F>, Error> {
+        if z_i.len() != self.state_len() {
+            return Err(Error::NotSameLength(
+                "state_len()".to_string(),
+                self.state_len(),
+                "z_i.len()".to_string(),
+                z_i.len(),
+            ));
+        }
+        if external_inputs.len() != self.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "external_inputs_len()".to_string(),
+                self.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+        let pour = Pour::from_slice(&external_inputs);
+        let k = (0..self.n_assets)
+            .position(|k| F::from(k as u64) == pour.asset_id())
+            .ok_or(Error::OutOfBounds)?;
+        pour.check_balance()?;
+
+        // the spent notes can not hold more value than the pool
+        let (spent, created) = (pour.spent(), pour.created());
+        if spent > z_i[1 + k] {
+            return Err(Error::ValueNotConserved(pour.asset_id().to_string()));
+        }
+        let mut z_i1 = z_i;
+        z_i1[0] = pours_hash(&self.poseidon_config, z_i1[0], &pour)?;
+        z_i1[1 + k] += created - spent;
+        check_value(z_i1[1 + k])?;
+        Ok(z_i1)
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Vec<FpVar<F>>,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let crh_params =
+            CRHParametersVar::<F>::new_constant(cs.clone(), self.poseidon_config.clone())?;
+        let (vpub_old, vpub_new) = (&external_inputs[0], &external_inputs[1]);
+        let notes: Vec<&[FpVar<F>]> = external_inputs[2..].chunks(POUR_NOTE_LEN).collect();
+        let (old_notes, new_notes) = notes.split_at(POUR_NOTES);
+
+        // all the notes are of the asset of the first spent note
+        let asset_id = &old_notes[0][2];
+        for note in notes.iter() {
+            note[2].enforce_equal(asset_id)?;
+        }
+
+        // balance of the Pour, with all its values in 64 bits so that the sums can not wrap
+        ValueGadget::enforce_value(vpub_old)?;
+        ValueGadget::enforce_value(vpub_new)?;
+        for note in notes.iter() {
+            ValueGadget::enforce_value(&note[1])?;
+        }
+        let spent = old_notes
+            .iter()
+            .fold(FpVar::<F>::zero(), |acc, note| acc + &note[1]);
+        let created = new_notes
+            .iter()
+            .fold(FpVar::<F>::zero(), |acc, note| acc + &note[1]);
+        (vpub_old + &spent).enforce_equal(&(vpub_new + &created))?;
+
+        let is_asset = (0..self.n_assets)
+            .map(|k| {
+                let k = FpVar::<F>::new_constant(cs.clone(), F::from(k as u64))?;
+                asset_id.is_eq(&k)
+            })
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        // asset_id must be one of the tracked assets
+        Boolean::kary_or(&is_asset)?.enforce_equal(&Boolean::TRUE)?;
+
+        let pours_hash_inputs = [&z_i[..1], &external_inputs[..]].concat();
+        let mut z_i1 = vec![CRHGadget::<F>::evaluate(&crh_params, &pours_hash_inputs)?];
+        for (supply, is_asset) in z_i[1..].iter().zip(is_asset.iter()) {
+            let spent = is_asset.select(&spent, &FpVar::<F>::zero())?;
+            let created = is_asset.select(&created, &FpVar::<F>::zero())?;
+            // the spent notes can not hold more value than the pool
+            spent.enforce_cmp(supply, Ordering::Less, true)?;
+            let supply = supply - spent + created;
+            ValueGadget::enforce_value(&supply)?;
+            z_i1.push(supply);
+        }
+        Ok(z_i1)
+    }
+}
+
+/// Proof that the shielded supply of the assets tracked by the `SupplyLedgerCircuit`, after `i`
+/// folded steps, is `z_i`.
+#[derive(Debug, Clone)]
+pub struct SupplyProof<C1: CurveGroup, P> {
+    pub i: C1::ScalarField,
+    pub z_0: Vec<C1::ScalarField>,
+    pub z_i: Vec<C1::ScalarField>,
+    pub running_instance: CommittedInstance<C1>,
+    pub incoming_instance: CommittedInstance<C1>,
+    pub decider_proof: P,
+}
+
+impl<C1: CurveGroup, P> SupplyProof<C1, P> {
+    /// Generates the Decider proof for the current state of the given Nova instance.
+    #[allow(clippy::type_complexity)]
+    pub fn prove<GC1, C2, GC2, CS1, CS2, D>(
+        rng: impl RngCore + CryptoRng,
+        decider_pp: D::ProverParam,
+        nova: Nova<C1, GC1, C2, GC2, SupplyLedgerCircuit<C1::ScalarField>, CS1, CS2, false>,
+    ) -> Result<Self, Error>
+    where
+        GC1: CurveVar<C1, CF2<C1>> + ToConstraintFieldGadget<CF2<C1>>,
+        C2: CurveGroup,
+        GC2: CurveVar<C2, CF2<C2>> + ToConstraintFieldGadget<CF2<C2>>,
+        CS1: CommitmentScheme<C1, false>,
+        CS2: CommitmentScheme<C2, false>,
+        <C1 as CurveGroup>::BaseField: PrimeField,
+        <C2 as CurveGroup>::BaseField: PrimeField,
+        <C1 as Group>::ScalarField: Absorb,
+        <C2 as Group>::ScalarField: Absorb,
+        C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+        for<'a> &'a GC1: GroupOpsBounds<'a, C1, GC1>,
+        for<'a> &'a GC2: GroupOpsBounds<'a, C2, GC2>,
+        D: Decider<
+            C1,
+            C2,
+            SupplyLedgerCircuit<C1::ScalarField>,
+            Nova<C1, GC1, C2, GC2, SupplyLedgerCircuit<C1::ScalarField>, CS1, CS2, false>,
+            Proof = P,
+            CommittedInstance = CommittedInstance<C1>,
+        >,
+    {
+        let decider_proof = D::prove(rng, decider_pp, nova.clone())?;
+        Ok(Self {
+            i: nova.i,
+            z_0: nova.z_0,
+            z_i: nova.z_i,
+            running_instance: nova.U_i,
+            incoming_instance: nova.u_i,
+            decider_proof,
+        })
+    }
+
+    /// returns the hash chain of the Pours folded by the proof, see [`pours_hash`]
+    pub fn pours_hash(&self) -> Result<C1::ScalarField, Error> {
+        self.z_i.first().copied().ok_or(Error::OutOfBounds)
+    }
+
+    /// returns the shielded supply of the given asset claimed by the proof
+    pub fn supply(&self, asset_id: usize) -> Result<C1::ScalarField, Error> {
+        self.z_i
+            .get(1 + asset_id)
+            .copied()
+            .ok_or(Error::OutOfBounds)
+    }
+
+    /// Verifies that the folded ledger started from an empty pool, that the folded Pours are the
+    /// ones with the given hash chain (computed by the verifier from the Pours settled in the
+    /// ledger), and that, after `i` steps, the shielded supply of `asset_id` equals `supply`.
+    pub fn verify<C2, FS, D>(
+        &self,
+        vp: D::VerifierParam,
+        pours_hash: C1::ScalarField,
+        asset_id: usize,
+        supply: C1::ScalarField,
+    ) -> Result<bool, Error>
+    where
+        C1::ScalarField: Absorb,
+        C2: CurveGroup,
+        C2::BaseField: PrimeField,
+        C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+        FS: crate::FoldingScheme<C1, C2, SupplyLedgerCircuit<C1::ScalarField>>,
+        D: Decider<
+            C1,
+            C2,
+            SupplyLedgerCircuit<C1::ScalarField>,
+            FS,
+            Proof = P,
+            CommittedInstance = CommittedInstance<C1>,
+        >,
+    {
+        if self.z_0.iter().any(|z| !z.is_zero())
+            || self.pours_hash()? != pours_hash
+            || self.supply(asset_id)? != supply
+        {
+            return Ok(false);
+        }
+        D::verify(
+            vp,
+            self.i,
+            self.z_0.clone(),
+            self.z_i.clone(),
+            &self.running_instance,
+            &self.incoming_instance,
+            &self.decider_proof,
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{constraints::GVar, Bn254, Fr, G1Projective as Projective};
+    use ark_groth16::Groth16;
+    use ark_grumpkin::{constraints::GVar as GVar2, Projective as Projective2};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
+    use crate::folding::nova::{decider_eth::Decider as DeciderEth, PreprocessorParam};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::zcash::transaction::Blockchain;
+    use crate::FoldingScheme;
+
+    fn note(asset_id: u32, value: Fr) -> PourNote<Fr> {
+        let mut rng = ark_std::test_rng();
+        PourNote {
+            serial_number: Fr::rand(&mut rng),
+            value,
+            asset_id: Fr::from(asset_id),
+            receiver_public_key: Fr::rand(&mut rng),
+            lock_until: Fr::zero(),
+            expiry_height: Fr::zero(),
+        }
+    }
+
+    fn pour(asset_id: u32, vpub_old: Fr, vpub_new: u32, old: [u32; 2], new: [Fr; 2]) -> Pour<Fr> {
+        Pour {
+            vpub_old,
+            vpub_new: Fr::from(vpub_new),
+            old_notes: old.map(|value| note(asset_id, Fr::from(value))),
+            new_notes: new.map(|value| note(asset_id, value)),
+        }
+    }
+
+    /// returns a note of the given asset over its own constraint system
+    fn transaction(asset_id: u32, value: u32, sn_secret: u32) -> Transaction<Fr> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let [id, value, sender, receiver, sn, asset_id] = [1, value, 2, 3, sn_secret, asset_id]
+            .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+        Transaction::new(id, value, sender, receiver, sn).with_asset_id(asset_id)
+    }
+
+    #[test]
+    fn test_supply_ledger_circuit() {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let circuit = SupplyLedgerCircuit::<Fr>::new((poseidon_config, 2)).unwrap();
+        let z_i = vec![Fr::from(42_u32), Fr::from(10_u32), Fr::from(3_u32)];
+        let (v2, v5, v8) = (Fr::from(2_u32), Fr::from(5_u32), Fr::from(8_u32));
+
+        let mut mixed_assets = pour(0, v5, 0, [0, 0], [v5, Fr::zero()]);
+        mixed_assets.new_notes[0].asset_id = Fr::from(1_u32);
+        let cases = [
+            // unshield the whole pool of asset 0
+            (pour(0, v5, 15, [10, 0], [Fr::zero(); 2]), true),
+            // mint 5 of asset 1 and split a note of 3 into two
+            (pour(1, v5, 0, [3, 0], [v2, Fr::from(6_u32)]), true),
+            // spends notes holding more than the pool of asset 1
+            (pour(1, Fr::zero(), 0, [5, 0], [v5, Fr::zero()]), false),
+            // untracked asset
+            (pour(2, v5, 0, [0, 0], [v5, Fr::zero()]), false),
+            // inflation: the created notes hold more value than the minted one
+            (pour(0, v5, 0, [0, 0], [v8, Fr::zero()]), false),
+            // wraparound of the field: minting p-3 balances modulo p spending 5 and creating 2,
+            // which unshields 3 without declaring it in vpub_new
+            (
+                pour(0, -Fr::from(3_u32), 0, [5, 0], [v2, Fr::zero()]),
+                false,
+            ),
+            // minting asset 0 into a note of asset 1
+            (mixed_assets, false),
+        ];
+        for (pour, expected) in cases {
+            let external_inputs = pour.to_vec();
+            let z_i1 = circuit.step_native(0, z_i.clone(), external_inputs.clone());
+            assert_eq!(z_i1.is_ok(), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone())).unwrap();
+            let external_inputsVar =
+                Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(external_inputs)).unwrap();
+            let z_i1Var = circuit
+                .generate_step_constraints(cs.clone(), 0, z_iVar, external_inputsVar)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+            if expected {
+                assert_eq!(z_i1Var.value().unwrap(), z_i1.unwrap());
+            }
+        }
+
+        // a state without the pours_hash
+        let pour = pour(0, v5, 0, [0, 0], [v5, Fr::zero()]);
+        assert!(matches!(
+            circuit.step_native(0, z_i[1..].to_vec(), pour.to_vec()),
+            Err(Error::NotSameLength(..))
+        ));
+    }
+
+    #[test]
+    fn test_append_pour() {
+        let mut blockchain = Blockchain::<Fr>::new();
+        let (dummy_a, dummy_b) = (transaction(0, 0, 1), transaction(0, 0, 2));
+        let (minted, change) = (transaction(0, 10, 3), transaction(0, 7, 4));
+
+        // mint 10 into a note
+        let pour = blockchain
+            .append_pour(
+                Fr::from(10_u32),
+                Fr::zero(),
+                &[dummy_a, dummy_b],
+                &[minted, transaction(0, 0, 5)],
+            )
+            .unwrap();
+        assert_eq!(pour.vpub_old, Fr::from(10_u32));
+        assert_eq!(pour.new_notes[0].value, Fr::from(10_u32));
+        let minted = transaction(0, 10, 3);
+        assert!(blockchain.contains(&minted.serial_number().value().unwrap()));
+
+        // unshield 3 of the minted note
+        blockchain
+            .append_pour(
+                Fr::zero(),
+                Fr::from(3_u32),
+                &[minted, transaction(0, 0, 6)],
+                &[change, transaction(0, 0, 7)],
+            )
+            .unwrap();
+        let minted = transaction(0, 10, 3);
+        assert!(blockchain.is_spent(&minted.serial_number().value().unwrap()));
+
+        // the minted note can not be spent twice
+        assert!(matches!(
+            blockchain.append_pour(
+                Fr::zero(),
+                Fr::from(10_u32),
+                &[minted, transaction(0, 0, 8)],
+                &[transaction(0, 0, 9), transaction(0, 0, 10)],
+            ),
+            Err(Error::DoubleSpend(_))
+        ));
+        // a note that is not in the ledger can not be spent
+        assert!(matches!(
+            blockchain.append_pour(
+                Fr::zero(),
+                Fr::from(5_u32),
+                &[transaction(0, 5, 11), transaction(0, 0, 12)],
+                &[transaction(0, 0, 13), transaction(0, 0, 14)],
+            ),
+            Err(Error::UnknownNote(_))
+        ));
+        // an unbalanced Pour is rejected
+        assert!(matches!(
+            blockchain.append_pour(
+                Fr::zero(),
+                Fr::zero(),
+                &[transaction(0, 0, 15), transaction(0, 0, 16)],
+                &[transaction(0, 1, 17), transaction(0, 0, 18)],
+            ),
+            Err(Error::ValueNotConserved(_))
+        ));
+    }
+
+    #[test]
+    fn test_supply_proof() {
+        type N = Nova<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            SupplyLedgerCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type D = DeciderEth<
+            Projective,
+            GVar,
+            Projective2,
+            GVar2,
+            SupplyLedgerCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            N,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = SupplyLedgerCircuit::<Fr>::new((poseidon_config.clone(), 2)).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F_circuit.clone());
+        let nova_params = N::preprocess(&mut rng, &prep_param).unwrap();
+
+        // settle in the ledger: mint 10 of asset 0, mint 4 of asset 1, unshield 3 of asset 0
+        let mut blockchain = Blockchain::<Fr>::new();
+        let settled = [
+            (10, 0, [(0, 0, 1), (0, 0, 2)], [(0, 10, 3), (0, 0, 4)]),
+            (4, 0, [(1, 0, 5), (1, 0, 6)], [(1, 4, 7), (1, 0, 8)]),
+            (0, 3, [(0, 10, 3), (0, 0, 9)], [(0, 7, 10), (0, 0, 11)]),
+        ];
+        let notes = |notes: [(u32, u32, u32); 2]| notes.map(|(a, v, sn)| transaction(a, v, sn));
+        let pours = settled
+            .into_iter()
+            .map(|(vpub_old, vpub_new, old, new)| {
+                let (vpub_old, vpub_new) = (Fr::from(vpub_old), Fr::from(vpub_new));
+                blockchain.append_pour(vpub_old, vpub_new, &notes(old), &notes(new))
+            })
+            .collect::<Result<Vec<_>, Error>>()
+            .unwrap();
+
+        // start from the empty pool
+        let z_0 = vec![Fr::from(0_u32); 3];
+        let mut nova = N::init(&nova_params, F_circuit, z_0).unwrap();
+        // the verifier recomputes the hash chain from the Pours settled in the ledger
+        let mut hash = Fr::zero();
+        for pour in pours.iter() {
+            nova.prove_step(&mut rng, pour.to_vec(), None).unwrap();
+            hash = pours_hash(&poseidon_config, hash, pour).unwrap();
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let (decider_pp, decider_vp) = D::preprocess(&mut rng, &nova_params, nova.clone()).unwrap();
+        let proof =
+            SupplyProof::prove::<GVar, Projective2, GVar2, _, _, D>(rng, decider_pp, nova).unwrap();
+
+        assert!(proof
+            .verify::<Projective2, N, D>(decider_vp.clone(), hash, 0, Fr::from(7_u32))
+            .unwrap());
+        assert!(proof
+            .verify::<Projective2, N, D>(decider_vp.clone(), hash, 1, Fr::from(4_u32))
+            .unwrap());
+        // a wrong supply claim is rejected
+        assert!(!proof
+            .verify::<Projective2, N, D>(decider_vp.clone(), hash, 0, Fr::from(8_u32))
+            .unwrap());
+        // Pours that were not settled in the ledger are rejected
+        assert!(!proof
+            .verify::<Projective2, N, D>(decider_vp, Fr::zero(), 0, Fr::from(7_u32))
+            .unwrap());
+    }
+}
@@ -0,0 +1,582 @@
+/// Atomic swap between two parties, each of them spending notes of a different asset and
+/// receiving notes in exchange. A single set of constraints enforces the conservation of both
+/// assets and the spend authorization of both parties, so the swap either settles completely or
+/// not at all.
+/// The [`SwapLedgerCircuit`] folds one swap per step, checking it against the block height kept in
+/// its state.
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::{poseidon::PoseidonConfig, Absorb},
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use core::marker::PhantomData;
+
+use super::time_lock::check_time_lock;
+use super::transaction::{Address, Transaction};
+use super::value::{check_value, ValueGadget};
+use crate::{frontend::FCircuit, Error};
+
+/// One side of the swap: the notes that the party spends, all of them of the same asset, the
+/// secret key that owns them, and the notes that the party receives.
+pub struct SwapParty<F: PrimeField> {
+    spend_secret: FpVar<F>,
+    inputs: Vec<Transaction<F>>,
+    outputs: Vec<Transaction<F>>,
+}
+impl<F> SwapParty<F>
+where
+    F: PrimeField,
+{
+    pub fn new(
+        spend_secret: FpVar<F>,
+        inputs: Vec<Transaction<F>>,
+        outputs: Vec<Transaction<F>>,
+    ) -> Result<Self, Error> {
+        if inputs.is_empty() {
+            return Err(Error::Empty);
+        }
+        Ok(Self {
+            spend_secret,
+            inputs,
+            outputs,
+        })
+    }
+    pub fn inputs(&self) -> &[Transaction<F>] {
+        &self.inputs
+    }
+    pub fn outputs(&self) -> &[Transaction<F>] {
+        &self.outputs
+    }
+    /// returns the asset contributed by this party
+    pub fn asset_id(&self) -> FpVar<F> {
+        self.inputs[0].asset_id()
+    }
+}
+
+pub struct SwapTransaction<F: PrimeField> {
+    parties: [SwapParty<F>; 2],
+}
+impl<F> SwapTransaction<F>
+where
+    F: PrimeField,
+{
+    pub fn new(party_a: SwapParty<F>, party_b: SwapParty<F>) -> Self {
+        Self {
+            parties: [party_a, party_b],
+        }
+    }
+    pub fn parties(&self) -> &[SwapParty<F>; 2] {
+        &self.parties
+    }
+    /// returns the notes spent by both parties
+    pub fn inputs(&self) -> impl Iterator<Item = &Transaction<F>> {
+        self.parties.iter().flat_map(|party| party.inputs.iter())
+    }
+    /// returns the notes received by both parties
+    pub fn outputs(&self) -> impl Iterator<Item = &Transaction<F>> {
+        self.parties.iter().flat_map(|party| party.outputs.iter())
+    }
+
+    /// Enforces in-circuit that:
+    /// - the two parties contribute different assets, and each party only spends notes of its
+    ///   asset
+    /// - each party knows the secret key of the receiver address of the notes it spends
+    /// - every output is denominated in one of the two assets
+    /// - the value of every note fits in 64 bits, so that the sums below can not wrap around
+    /// - for each asset, the value of the inputs equals the value of the outputs
+    pub fn enforce_valid(&self) -> Result<(), SynthesisError> {
+        let assets = [self.parties[0].asset_id(), self.parties[1].asset_id()];
+        assets[0].enforce_not_equal(&assets[1])?;
+
+        for note in self.inputs().chain(self.outputs()) {
+            ValueGadget::enforce_value(&note.value())?;
+        }
+
+        for (party, asset) in self.parties.iter().zip(assets.iter()) {
+            let owner = Address::new(party.spend_secret.clone()).public_key();
+            for input in party.inputs.iter() {
+                input.asset_id().enforce_equal(asset)?;
+                input
+                    .receiver_address()
+                    .public_key()
+                    .enforce_equal(&owner)?;
+            }
+        }
+
+        let is_asset: Vec<[Boolean<F>; 2]> = self
+            .outputs()
+            .map(|output| {
+                Ok([
+                    output.asset_id().is_eq(&assets[0])?,
+                    output.asset_id().is_eq(&assets[1])?,
+                ])
+            })
+            .collect::<Result<_, SynthesisError>>()?;
+        for [is_a, is_b] in is_asset.iter() {
+            is_a.or(is_b)?.enforce_equal(&Boolean::TRUE)?;
+        }
+
+        for (j, party) in self.parties.iter().enumerate() {
+            let inputs_value = party
+                .inputs
+                .iter()
+                .fold(FpVar::<F>::zero(), |acc, input| acc + input.value());
+            let mut outputs_value = FpVar::<F>::zero();
+            for (output, is_asset) in self.outputs().zip(is_asset.iter()) {
+                outputs_value += is_asset[j].select(&output.value(), &FpVar::<F>::zero())?;
+            }
+            inputs_value.enforce_equal(&outputs_value)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces `enforce_valid`, and that all the notes of the swap can be included at the given
+    /// block height.
+    pub fn enforce_valid_at_height(&self, height: &FpVar<F>) -> Result<(), SynthesisError> {
+        self.enforce_valid()?;
+        for note in self.inputs().chain(self.outputs()) {
+            note.enforce_valid_at_height(height)?;
+        }
+        Ok(())
+    }
+
+    /// Native counterpart of `enforce_valid`, performing the same checks over the values of the
+    /// notes.
+    pub fn check_valid(&self) -> Result<(), Error> {
+        let assets = [
+            self.parties[0].asset_id().value()?,
+            self.parties[1].asset_id().value()?,
+        ];
+        if assets[0] == assets[1] {
+            return Err(Error::SwapSameAsset);
+        }
+
+        for (party, asset) in self.parties.iter().zip(assets.iter()) {
+            let owner = Address::public_key_from_scalar_field(party.spend_secret.value()?);
+            for input in party.inputs.iter() {
+                let input_asset = input.asset_id().value()?;
+                if input_asset != *asset {
+                    return Err(Error::AssetMismatch(
+                        input_asset.to_string(),
+                        asset.to_string(),
+                    ));
+                }
+                if input.receiver_address().public_key().value()? != owner {
+                    return Err(Error::SpendNotAuthorized(
+                        input.serial_number().value()?.to_string(),
+                    ));
+                }
+            }
+        }
+
+        let mut inputs_value = [F::zero(); 2];
+        let mut outputs_value = [F::zero(); 2];
+        for (notes, values) in [
+            (self.inputs().collect::<Vec<_>>(), &mut inputs_value),
+            (self.outputs().collect::<Vec<_>>(), &mut outputs_value),
+        ] {
+            for note in notes {
+                let asset = note.asset_id().value()?;
+                let j = assets.iter().position(|a| *a == asset).ok_or_else(|| {
+                    Error::AssetMismatch(
+                        asset.to_string(),
+                        format!("{} or {}", assets[0], assets[1]),
+                    )
+                })?;
+                let value = note.value().value()?;
+                check_value(value)?;
+                values[j] += value;
+            }
+        }
+        for ((input_value, output_value), asset) in
+            inputs_value.iter().zip(outputs_value).zip(assets)
+        {
+            if *input_value != output_value {
+                return Err(Error::ValueNotConserved(asset.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Native counterpart of `enforce_valid_at_height`.
+    pub fn check_valid_at_height(&self, height: F) -> Result<(), Error> {
+        self.check_valid()?;
+        for note in self.inputs().chain(self.outputs()) {
+            check_time_lock(
+                height,
+                note.lock_until().value()?,
+                note.expiry_height().value()?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of field elements describing each note in the external inputs of the
+/// [`SwapLedgerCircuit`]: `[transaction_id, value, sender_secret, receiver_secret, sn_secret,
+/// asset_id, lock_until, expiry_height]`.
+pub const SWAP_NOTE_LEN: usize = 8;
+
+/// Circuit that settles one swap at each step, where each party spends one note and receives one
+/// note.
+/// The state is `[height, swaps_hash]`, where `height` is the block height at which the swap is
+/// settled, which is increased by one at each step, and `swaps_hash` is a poseidon hash chain of
+/// the notes of the settled swaps (see [`SwapLedgerCircuit::note_fields`]). The circuit does not
+/// have access to the ledger, so `swaps_hash` commits to the serial number, value, asset and
+/// receiver of every spent note, for the verifier to check them against the ledger entries (as
+/// `Blockchain::append_swap` does natively).
+/// The external inputs are the spend secrets of both parties followed by the notes (see
+/// [`SWAP_NOTE_LEN`]) in the order: input of a, input of b, output of a, output of b.
+#[derive(Clone, Debug)]
+pub struct SwapLedgerCircuit<F: PrimeField + Absorb> {
+    _f: PhantomData<F>,
+    poseidon_config: PoseidonConfig<F>,
+}
+impl<F: PrimeField + Absorb> SwapLedgerCircuit<F> {
+    /// builds a note from its `SWAP_NOTE_LEN` variables
+    fn note(note: &[FpVar<F>]) -> Transaction<F> {
+        Transaction::new(
+            note[0].clone(),
+            note[1].clone(),
+            note[2].clone(),
+            note[3].clone(),
+            note[4].clone(),
+        )
+        .with_asset_id(note[5].clone())
+        .with_lock_until(note[6].clone())
+        .with_expiry_height(note[7].clone())
+    }
+
+    /// returns the fields of the note that are committed in the `swaps_hash`: `[serial_number,
+    /// value, asset_id, receiver_public_key, lock_until, expiry_height]`
+    fn note_fields(note: &Transaction<F>) -> Vec<FpVar<F>> {
+        vec![
+            note.serial_number(),
+            note.value(),
+            note.asset_id(),
+            note.receiver_address().public_key(),
+            note.lock_until(),
+            note.expiry_height(),
+        ]
+    }
+
+    /// builds the swap from the variables of the external inputs
+    fn swap(external_inputs: &[FpVar<F>]) -> SwapTransaction<F> {
+        let (secrets, notes) = external_inputs.split_at(2);
+        let mut notes = notes.chunks(SWAP_NOTE_LEN).map(Self::note);
+        let (a_input, b_input) = (notes.next(), notes.next());
+        let (a_output, b_output) = (notes.next(), notes.next());
+        let party = |spend_secret: &FpVar<F>,
+                     input: Option<Transaction<F>>,
+                     output: Option<Transaction<F>>| SwapParty {
+            spend_secret: spend_secret.clone(),
+            inputs: input.into_iter().collect(),
+            outputs: output.into_iter().collect(),
+        };
+        SwapTransaction::new(
+            party(&secrets[0], a_input, a_output),
+            party(&secrets[1], b_input, b_output),
+        )
+    }
+}
+impl<F: PrimeField + Absorb> FCircuit<F> for SwapLedgerCircuit<F> {
+    type Params = PoseidonConfig<F>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            _f: PhantomData,
+            poseidon_config: params,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        2
+    }
+
+    fn external_inputs_len(&self) -> usize {
+        2 + 4 * SWAP_NOTE_LEN
+    }
+
+    fn step_native(
+        &self,
+        _i: usize,
+        z_i: Vec<F>,
+        external_inputs: Vec<F>,
+    ) -> Result<Vec<F>, Error> {
+        if z_i.len() != self.state_len() {
+            return Err(Error::NotSameLength(
+                "state_len()".to_string(),
+                self.state_len(),
+                "z_i.len()".to_string(),
+                z_i.len(),
+            ));
+        }
+        if external_inputs.len() != self.external_inputs_len() {
+            return Err(Error::NotSameLength(
+                "external_inputs_len()".to_string(),
+                self.external_inputs_len(),
+                "external_inputs.len()".to_string(),
+                external_inputs.len(),
+            ));
+        }
+        // the notes are rebuilt over a standalone constraint system, only to compute natively the
+        // addresses and serial numbers from their values
+        let cs = ConstraintSystem::<F>::new_ref();
+        let external_inputs = Vec::<FpVar<F>>::new_witness(cs, || Ok(external_inputs))?;
+        let swap = Self::swap(&external_inputs);
+
+        let height = z_i[0];
+        swap.check_valid_at_height(height)?;
+
+        let mut swaps_hash_inputs = vec![z_i[1]];
+        for note in swap.inputs().chain(swap.outputs()) {
+            swaps_hash_inputs.extend(Self::note_fields(note).value()?);
+        }
+        let swaps_hash = CRH::<F>::evaluate(&self.poseidon_config, swaps_hash_inputs)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        Ok(vec![height + F::one(), swaps_hash])
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Vec<FpVar<F>>,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let crh_params =
+            CRHParametersVar::<F>::new_constant(cs.clone(), self.poseidon_config.clone())?;
+        let swap = Self::swap(&external_inputs);
+
+        let height = &z_i[0];
+        swap.enforce_valid_at_height(height)?;
+
+        let mut swaps_hash_inputs = vec![z_i[1].clone()];
+        for note in swap.inputs().chain(swap.outputs()) {
+            swaps_hash_inputs.extend(Self::note_fields(note));
+        }
+        let swaps_hash = CRHGadget::<F>::evaluate(&crh_params, &swaps_hash_inputs)?;
+        Ok(vec![height + FpVar::<F>::one(), swaps_hash])
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::{rand::RngCore, One, UniformRand};
+
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::zcash::transaction::Blockchain;
+
+    /// returns the values of a note, in the layout of the external inputs of the
+    /// `SwapLedgerCircuit`
+    fn note_values(
+        rng: &mut impl RngCore,
+        value: Fr,
+        asset_id: u64,
+        sender_secret: Fr,
+        receiver_secret: Fr,
+    ) -> Vec<Fr> {
+        vec![
+            Fr::rand(rng),
+            value,
+            sender_secret,
+            receiver_secret,
+            Fr::rand(rng),
+            Fr::from(asset_id),
+            Fr::from(0_u32),
+            Fr::from(0_u32),
+        ]
+    }
+
+    fn note(cs: ConstraintSystemRef<Fr>, values: Vec<Fr>) -> Transaction<Fr> {
+        let vars = Vec::<FpVar<Fr>>::new_witness(cs, || Ok(values)).unwrap();
+        SwapLedgerCircuit::<Fr>::note(&vars)
+    }
+
+    /// returns the external inputs of the `SwapLedgerCircuit` for a swap where a spends 10 units
+    /// of asset 1 and b spends 7 units of asset 2, b receiving `b_receives` units of asset 1
+    fn swap_values(b_receives: Fr, a_lock_until: u64) -> Vec<Fr> {
+        let mut rng = ark_std::test_rng();
+        let (secret_a, secret_b) = (Fr::from(1_u32), Fr::from(2_u32));
+        let mut a_input = note_values(&mut rng, Fr::from(10_u32), 1, secret_b, secret_a);
+        a_input[6] = Fr::from(a_lock_until);
+        let b_input = note_values(&mut rng, Fr::from(7_u32), 2, secret_a, secret_b);
+        let a_output = note_values(&mut rng, Fr::from(7_u32), 2, secret_b, secret_a);
+        let b_output = note_values(&mut rng, b_receives, 1, secret_a, secret_b);
+        [
+            vec![secret_a, secret_b],
+            a_input,
+            b_input,
+            a_output,
+            b_output,
+        ]
+        .concat()
+    }
+
+    fn swap(cs: ConstraintSystemRef<Fr>, values: Vec<Fr>) -> SwapTransaction<Fr> {
+        let vars = Vec::<FpVar<Fr>>::new_witness(cs, || Ok(values)).unwrap();
+        SwapLedgerCircuit::<Fr>::swap(&vars)
+    }
+
+    /// returns a blockchain containing the input notes of the swap, so that they can be spent
+    fn blockchain_with_inputs(swap: &SwapTransaction<Fr>) -> Blockchain<Fr> {
+        let mut blockchain = Blockchain::<Fr>::new();
+        for input in swap.inputs() {
+            blockchain.append_transaction(input.root(), input.serial_number());
+        }
+        blockchain
+    }
+
+    #[test]
+    fn test_swap() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let swap = swap(cs.clone(), swap_values(Fr::from(10_u32), 0));
+        swap.enforce_valid().unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        swap.check_valid().unwrap();
+
+        let mut blockchain = blockchain_with_inputs(&swap);
+        blockchain.append_swap(&swap).unwrap();
+        for input in swap.inputs() {
+            assert!(blockchain.is_spent(&input.serial_number().value().unwrap()));
+        }
+        for output in swap.outputs() {
+            assert!(blockchain.contains(&output.serial_number().value().unwrap()));
+        }
+        // the same swap can not be settled twice
+        assert!(blockchain.append_swap(&swap).is_err());
+    }
+
+    #[test]
+    fn test_swap_not_conserved() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        // b receives more units of asset 1 than what a spends
+        let swap = swap(cs.clone(), swap_values(Fr::from(11_u32), 0));
+        swap.enforce_valid().unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+        assert!(swap.check_valid().is_err());
+        assert!(blockchain_with_inputs(&swap).append_swap(&swap).is_err());
+    }
+
+    #[test]
+    fn test_append_swap_unknown_note() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let values = swap_values(Fr::from(10_u32), 0);
+        let swap = swap(cs.clone(), values.clone());
+
+        // the input notes were never added to the ledger
+        assert!(matches!(
+            Blockchain::<Fr>::new().append_swap(&swap),
+            Err(Error::UnknownNote(_))
+        ));
+
+        // the ledger contains a note with the same serial number as the input of a, but with a
+        // lower value, which can not be inflated when spending it
+        let mut stored_values = values[2..2 + SWAP_NOTE_LEN].to_vec();
+        stored_values[1] = Fr::from(5_u32);
+        let stored = note(cs, stored_values);
+        let mut blockchain = Blockchain::<Fr>::new();
+        blockchain.append_transaction(stored.root(), stored.serial_number());
+        for input in swap.inputs().skip(1) {
+            blockchain.append_transaction(input.root(), input.serial_number());
+        }
+        assert!(matches!(
+            blockchain.append_swap(&swap),
+            Err(Error::UnknownNote(_))
+        ));
+    }
+
+    #[test]
+    fn test_swap_value_wraparound() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut swap = swap(cs.clone(), swap_values(Fr::from(11_u32), 0));
+        // an extra output of p-1 units of asset 1 makes the outputs of asset 1 sum to 10 modulo p
+        let (secret_a, secret_b) = (Fr::from(1_u32), Fr::from(2_u32));
+        let wrap = note_values(&mut rng, -Fr::one(), 1, secret_b, secret_a);
+        swap.parties[0].outputs.push(note(cs.clone(), wrap));
+
+        swap.enforce_valid().unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+        assert!(matches!(swap.check_valid(), Err(Error::ValueOutOfRange(_))));
+    }
+
+    #[test]
+    fn test_swap_asset_mismatch() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut swap = swap(cs.clone(), swap_values(Fr::from(10_u32), 0));
+        // a spends a second note, of an asset different than the one it contributes
+        let (secret_a, secret_b) = (Fr::from(1_u32), Fr::from(2_u32));
+        let other = note_values(&mut rng, Fr::from(1_u32), 3, secret_b, secret_a);
+        swap.parties[0].inputs.push(note(cs.clone(), other));
+
+        swap.enforce_valid().unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+        assert!(matches!(swap.check_valid(), Err(Error::AssetMismatch(..))));
+    }
+
+    #[test]
+    fn test_append_swap_time_lock() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        // the note spent by a is locked until height 2
+        let swap = swap(cs, swap_values(Fr::from(10_u32), 2));
+
+        let mut blockchain = blockchain_with_inputs(&swap);
+        assert!(matches!(
+            blockchain.append_swap(&swap),
+            Err(Error::NoteLocked(..))
+        ));
+        blockchain.advance_height();
+        blockchain.advance_height();
+        blockchain.append_swap(&swap).unwrap();
+    }
+
+    #[test]
+    fn test_swap_ledger_circuit() {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let circuit = SwapLedgerCircuit::<Fr>::new(poseidon_config).unwrap();
+        let z_i = vec![Fr::from(1_u32), Fr::from(0_u32)];
+
+        // (b_receives, a_lock_until, expected)
+        let cases = [
+            (Fr::from(10_u32), 0, true),
+            (Fr::from(10_u32), 1, true),
+            (Fr::from(10_u32), 2, false), // the input of a is still locked
+            (Fr::from(11_u32), 0, false), // value not conserved
+        ];
+        for (b_receives, a_lock_until, expected) in cases {
+            let external_inputs = swap_values(b_receives, a_lock_until);
+            let z_i1 = circuit.step_native(0, z_i.clone(), external_inputs.clone());
+            assert_eq!(z_i1.is_ok(), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone())).unwrap();
+            let external_inputsVar =
+                Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(external_inputs)).unwrap();
+            let z_i1Var = circuit
+                .generate_step_constraints(cs.clone(), 0, z_iVar, external_inputsVar)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+            if expected {
+                assert_eq!(z_i1Var.value().unwrap(), z_i1.unwrap());
+            }
+        }
+    }
+}
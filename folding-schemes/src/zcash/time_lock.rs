@@ -0,0 +1,125 @@
+/// Block-height checks for time-locked notes and expiring transactions.
+/// A note carries a `lock_until` height before which it can not be spent, and a transaction
+/// carries an `expiry_height` after which it can no longer be included in the ledger.
+/// Both heights use zero as "not set": a `lock_until` of zero is always spendable and an
+/// `expiry_height` of zero never expires.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{boolean::Boolean, eq::EqGadget, fields::fp::FpVar, fields::FieldVar};
+use ark_relations::r1cs::SynthesisError;
+use core::cmp::Ordering;
+
+use crate::Error;
+
+/// Checks natively that a note locked until `lock_until` and a transaction expiring at
+/// `expiry_height` can be included at block `height`.
+pub fn check_time_lock<F: PrimeField>(
+    height: F,
+    lock_until: F,
+    expiry_height: F,
+) -> Result<(), Error> {
+    if height < lock_until {
+        return Err(Error::NoteLocked(
+            lock_until.to_string(),
+            height.to_string(),
+        ));
+    }
+    if !expiry_height.is_zero() && height > expiry_height {
+        return Err(Error::TransactionExpired(
+            expiry_height.to_string(),
+            height.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub struct TimeLockGadget;
+impl TimeLockGadget {
+    /// In-circuit counterpart of [`check_time_lock`]. Enforces `lock_until <= height` and
+    /// `expiry_height == 0 || height <= expiry_height`.
+    /// Heights are compared as integers, which requires them to be smaller than (p-1)/2, this is
+    /// also enforced by the comparison gadgets.
+    /// The gadget only compares the given heights, it is up to the caller to bind `lock_until` and
+    /// `expiry_height` to committed or public data (see
+    /// [`super::public_ledger::transactions_commitment`]), otherwise the prover can choose heights
+    /// that always pass the check.
+    pub fn enforce_time_lock<F: PrimeField>(
+        height: &FpVar<F>,
+        lock_until: &FpVar<F>,
+        expiry_height: &FpVar<F>,
+    ) -> Result<(), SynthesisError> {
+        lock_until.enforce_cmp(height, Ordering::Less, true)?;
+
+        let no_expiry = expiry_height.is_zero()?;
+        let not_expired = height.is_cmp(expiry_height, Ordering::Less, true)?;
+        no_expiry.or(&not_expired)?.enforce_equal(&Boolean::TRUE)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::zcash::transaction::{Blockchain, Transaction};
+
+    fn check_gadget(height: u64, lock_until: u64, expiry_height: u64) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let height = FpVar::new_witness(cs.clone(), || Ok(Fr::from(height))).unwrap();
+        let lock_until = FpVar::new_witness(cs.clone(), || Ok(Fr::from(lock_until))).unwrap();
+        let expiry_height = FpVar::new_witness(cs.clone(), || Ok(Fr::from(expiry_height))).unwrap();
+        TimeLockGadget::enforce_time_lock(&height, &lock_until, &expiry_height).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_time_lock() {
+        // (height, lock_until, expiry_height, expected)
+        let cases = [
+            (10, 0, 0, true),   // no lock, no expiry
+            (10, 10, 0, true),  // lock reached exactly
+            (10, 11, 0, false), // still locked
+            (10, 0, 10, true),  // last valid height
+            (10, 0, 9, false),  // expired
+            (10, 5, 20, true),  // inside the [lock_until, expiry_height] window
+            (30, 5, 20, false), // past the window
+        ];
+        for (height, lock_until, expiry_height, expected) in cases {
+            assert_eq!(
+                check_time_lock(
+                    Fr::from(height),
+                    Fr::from(lock_until),
+                    Fr::from(expiry_height)
+                )
+                .is_ok(),
+                expected
+            );
+            assert_eq!(check_gadget(height, lock_until, expiry_height), expected);
+        }
+    }
+
+    #[test]
+    fn test_append_transaction_at_height() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let [id, value, sender, receiver, sn, lock_until] = [1_u32, 10, 2, 3, 4, 1]
+            .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+        let transaction =
+            Transaction::new(id, value, sender, receiver, sn).with_lock_until(lock_until);
+
+        let mut blockchain = Blockchain::<Fr>::new();
+        assert!(matches!(
+            blockchain.append_transaction_at_height(&transaction),
+            Err(Error::NoteLocked(..))
+        ));
+        blockchain.advance_height();
+        blockchain
+            .append_transaction_at_height(&transaction)
+            .unwrap();
+        // a replayed transaction is rejected instead of aborting the process
+        assert!(matches!(
+            blockchain.append_transaction_at_height(&transaction),
+            Err(Error::DuplicateSerialNumber(_))
+        ));
+    }
+}
@@ -1,20 +1,30 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
 
 use ark_crypto_primitives::crh::{
-    sha256::constraints::{Sha256Gadget, UnitVar},
-    CRHSchemeGadget,
+    sha256::{
+        constraints::{Sha256Gadget, UnitVar},
+        Sha256,
+    },
+    CRHScheme, CRHSchemeGadget,
 };
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField, ToConstraintField, Zero};
 use ark_r1cs_std::{
     alloc::AllocVar,
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
     R1CSVar, ToBytesGadget, ToConstraintFieldGadget,
 };
-use ark_relations::r1cs::ConstraintSystem;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
 use ark_std::rand::thread_rng;
 
 use super::merkle_gadget::MerkleTreeGadget;
+use super::supply::{Pour, POUR_NOTES};
+use super::swap::SwapTransaction;
+use super::time_lock::{check_time_lock, TimeLockGadget};
+use crate::Error;
 type ROOT<F> = F;
 type SN<F> = F;
 
@@ -44,6 +54,13 @@ where
             secret_key,
         }
     }
+    /// Computes natively the public key that `Address::new` derives in-circuit from the secret key
+    pub fn public_key_from_scalar_field(secret_key: F) -> F {
+        Sha256::evaluate(&(), secret_key.into_bigint().to_bytes_le())
+            .unwrap()
+            .to_field_elements()
+            .unwrap()[0]
+    }
     pub fn public_key(&self) -> FpVar<F> {
         self.public_key.clone()
     }
@@ -87,6 +104,9 @@ pub struct Transaction<F: PrimeField> {
     sender_address: Address<F>, // built from the spending key of the sender
     receiver_address: Address<F>, // built from the spending key of the receiver
     serial_number: TransactionSerialNumber<F>,
+    asset_id: FpVar<F>,      // 0 is the native asset of the ledger
+    lock_until: FpVar<F>,    // height before which the note can not be spent, 0 if not locked
+    expiry_height: FpVar<F>, // last height at which the transaction is valid, 0 if it never expires
 }
 impl<F> Transaction<F>
 where
@@ -105,8 +125,26 @@ where
             sender_address: Address::new(sender_address_secret),
             receiver_address: Address::new(receiver_address_secret),
             serial_number: TransactionSerialNumber::new(sn_secret),
+            asset_id: FpVar::<F>::zero(),
+            lock_until: FpVar::<F>::zero(),
+            expiry_height: FpVar::<F>::zero(),
         }
     }
+    /// Sets the asset that the value of the note is denominated in
+    pub fn with_asset_id(mut self, asset_id: FpVar<F>) -> Self {
+        self.asset_id = asset_id;
+        self
+    }
+    /// Locks the note until the given block height
+    pub fn with_lock_until(mut self, height: FpVar<F>) -> Self {
+        self.lock_until = height;
+        self
+    }
+    /// Makes the transaction invalid after the given block height
+    pub fn with_expiry_height(mut self, height: FpVar<F>) -> Self {
+        self.expiry_height = height;
+        self
+    }
     pub fn transaction_id(&self) -> FpVar<F> {
         self.transaction_id.clone()
     }
@@ -122,6 +160,19 @@ where
     pub fn serial_number(&self) -> FpVar<F> {
         self.serial_number.sn()
     }
+    pub fn asset_id(&self) -> FpVar<F> {
+        self.asset_id.clone()
+    }
+    pub fn lock_until(&self) -> FpVar<F> {
+        self.lock_until.clone()
+    }
+    pub fn expiry_height(&self) -> FpVar<F> {
+        self.expiry_height.clone()
+    }
+    /// Enforces that the transaction can be included at the given block height
+    pub fn enforce_valid_at_height(&self, height: &FpVar<F>) -> Result<(), SynthesisError> {
+        TimeLockGadget::enforce_time_lock(height, &self.lock_until, &self.expiry_height)
+    }
     pub fn to_vec(&self) -> Vec<FpVar<F>> {
         vec![
             self.transaction_id(),
@@ -131,6 +182,9 @@ where
             self.receiver_address().public_key(),
             self.receiver_address().secret_key(),
             self.serial_number(),
+            self.asset_id(),
+            self.lock_until(),
+            self.expiry_height(),
         ]
     }
     pub fn root(&self) -> FpVar<F> {
@@ -186,6 +240,10 @@ where
                 sender_address: Address::new(sender_address_secret.clone()), // Sender remains the same
                 receiver_address: new_receiver_addresses[i].clone(), // New receiver address for this split
                 serial_number: new_serial_number,
+                // The split notes inherit the asset and time constraints of the original one
+                asset_id: self.asset_id(),
+                lock_until: self.lock_until(),
+                expiry_height: self.expiry_height(),
             };
 
             split_transactions.push(split_transaction);
@@ -196,6 +254,8 @@ where
 }
 pub struct Blockchain<F: PrimeField> {
     inner: HashMap<SN<F>, ROOT<F>>, // Use HashMap with serial numbers as keys
+    spent: HashSet<SN<F>>,          // serial numbers of the notes that have been spent
+    height: u64,
 }
 
 impl<F> Blockchain<F>
@@ -205,8 +265,17 @@ where
     pub fn new() -> Self {
         Self {
             inner: HashMap::new(),
+            spent: HashSet::new(),
+            height: 0,
         }
     }
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+    /// Moves the blockchain to the next block
+    pub fn advance_height(&mut self) {
+        self.height += 1;
+    }
 
     pub fn append_transaction(&mut self, root: FpVar<F>, serial_number: FpVar<F>) {
         // Convert FpVar<F> to concrete values
@@ -221,6 +290,107 @@ where
         // Insert the transaction into the HashMap
         self.inner.insert(sn_value, root_value);
     }
+    /// Appends the transaction after checking its lock and expiry heights against the current
+    /// height of the blockchain. Unlike `append_transaction`, a serial number that is already in
+    /// the blockchain is returned as an error instead of panicking.
+    pub fn append_transaction_at_height(
+        &mut self,
+        transaction: &Transaction<F>,
+    ) -> Result<(), Error> {
+        check_time_lock(
+            F::from(self.height),
+            transaction.lock_until().value()?,
+            transaction.expiry_height().value()?,
+        )?;
+        let sn = transaction.serial_number().value()?;
+        if self.contains(&sn) {
+            return Err(Error::DuplicateSerialNumber(sn.to_string()));
+        }
+        self.inner.insert(sn, transaction.root().value()?);
+        Ok(())
+    }
+    pub fn contains(&self, serial_number: &F) -> bool {
+        self.inner.contains_key(serial_number)
+    }
+    pub fn is_spent(&self, serial_number: &F) -> bool {
+        self.spent.contains(serial_number)
+    }
+    /// returns whether the nullifier (the serial number revealed by a transaction) has already
+    /// been recorded, either by an appended transaction or by a note spent in a swap
+    pub fn is_nullified(&self, nullifier: &F) -> bool {
+        self.contains(nullifier) || self.is_spent(nullifier)
+    }
+    /// Settles a swap atomically: either all the input notes are marked as spent and all the
+    /// output notes are appended, or the blockchain is left untouched. As in
+    /// `append_transaction_at_height`, the lock and expiry heights of all the notes of the swap
+    /// are checked against the current height of the blockchain.
+    /// Each input note has to be in the blockchain, and its root has to match the stored one, so
+    /// that its value, asset and receiver are the ones it was appended with.
+    pub fn append_swap(&mut self, swap: &SwapTransaction<F>) -> Result<(), Error> {
+        swap.check_valid_at_height(F::from(self.height))?;
+        self.settle(swap.inputs(), swap.outputs())
+    }
+    /// Settles a Pour atomically, as `append_swap` does for swaps, and returns it in the layout
+    /// folded by the `SupplyLedgerCircuit`. The spent notes of value zero are dummy notes (as in
+    /// Zcash), which are neither looked up in the blockchain nor marked as spent.
+    pub fn append_pour(
+        &mut self,
+        vpub_old: F,
+        vpub_new: F,
+        old_notes: &[Transaction<F>; POUR_NOTES],
+        new_notes: &[Transaction<F>; POUR_NOTES],
+    ) -> Result<Pour<F>, Error> {
+        let pour = Pour::from_transactions(vpub_old, vpub_new, old_notes, new_notes)?;
+        pour.check_balance()?;
+        for note in old_notes.iter().chain(new_notes.iter()) {
+            check_time_lock(
+                F::from(self.height),
+                note.lock_until().value()?,
+                note.expiry_height().value()?,
+            )?;
+        }
+        let spent = old_notes
+            .iter()
+            .zip(pour.old_notes.iter())
+            .filter(|(_, note)| !note.value.is_zero())
+            .map(|(note, _)| note);
+        self.settle(spent, new_notes.iter())?;
+        Ok(pour)
+    }
+    /// Marks the inputs as spent and appends the outputs, after checking that every input is in
+    /// the blockchain with a matching root and is spent only once, and that no output reuses a
+    /// serial number. Leaves the blockchain untouched on error.
+    fn settle<'a>(
+        &mut self,
+        inputs: impl Iterator<Item = &'a Transaction<F>>,
+        outputs: impl Iterator<Item = &'a Transaction<F>>,
+    ) -> Result<(), Error>
+    where
+        F: 'a,
+    {
+        let mut spent = HashSet::new();
+        for input in inputs {
+            let sn = input.serial_number().value()?;
+            if self.inner.get(&sn) != Some(&input.root().value()?) {
+                return Err(Error::UnknownNote(sn.to_string()));
+            }
+            if self.is_spent(&sn) || !spent.insert(sn) {
+                return Err(Error::DoubleSpend(sn.to_string()));
+            }
+        }
+        let mut created = HashMap::new();
+        for output in outputs {
+            let sn = output.serial_number().value()?;
+            if self.contains(&sn) || created.contains_key(&sn) {
+                return Err(Error::DuplicateSerialNumber(sn.to_string()));
+            }
+            created.insert(sn, output.root().value()?);
+        }
+
+        self.spent.extend(spent);
+        self.inner.extend(created);
+        Ok(())
+    }
     pub fn dump_transactions(&self) {
         println!("Blockchain Transactions:");
         println!("========================");
@@ -0,0 +1,60 @@
+/// Range checks for the values of the notes. Values are 64-bit unsigned integers encoded as field
+/// elements, so that sums of a bounded number of values can not wrap around the modulus, which
+/// would allow to satisfy the value conservation checks while creating value (eg. with an output
+/// of value `p-1`).
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{boolean::Boolean, eq::EqGadget, fields::fp::FpVar, ToBitsGadget};
+use ark_relations::r1cs::SynthesisError;
+
+use crate::Error;
+
+/// Number of bits of the values of the notes
+pub const VALUE_BITS: usize = 64;
+
+/// Checks natively that the value fits in [`VALUE_BITS`] bits.
+pub fn check_value<F: PrimeField>(value: F) -> Result<(), Error> {
+    if value.into_bigint().num_bits() as usize > VALUE_BITS {
+        return Err(Error::ValueOutOfRange(value.to_string()));
+    }
+    Ok(())
+}
+
+pub struct ValueGadget;
+impl ValueGadget {
+    /// In-circuit counterpart of [`check_value`]. Enforces that the canonical bit decomposition of
+    /// the value has all the bits above [`VALUE_BITS`] set to zero.
+    pub fn enforce_value<F: PrimeField>(value: &FpVar<F>) -> Result<(), SynthesisError> {
+        let bits = value.to_bits_le()?;
+        for bit in bits.iter().skip(VALUE_BITS) {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::One;
+
+    #[test]
+    fn test_value_range() {
+        let cases = [
+            (Fr::from(0_u64), true),
+            (Fr::from(u64::MAX), true),
+            (Fr::from(u64::MAX) + Fr::one(), false),
+            (-Fr::one(), false), // p-1
+        ];
+        for (value, expected) in cases {
+            assert_eq!(check_value(value).is_ok(), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let value = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            ValueGadget::enforce_value(&value).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
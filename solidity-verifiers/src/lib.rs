@@ -4,6 +4,7 @@ pub mod verifiers;
 
 pub use verifiers::*;
 pub use verifiers::{
-    get_decider_template_for_cyclefold_decider, Groth16VerifierKey, KZG10VerifierKey,
-    NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    get_decider_template_for_cyclefold_decider,
+    get_decider_template_for_hypernova_cyclefold_decider, Groth16VerifierKey,
+    HyperNovaCycleFoldVerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
 };
@@ -5,8 +5,9 @@
 use ark_bn254::{Bn254, Fq, Fr, G1Affine};
 use ark_groth16::VerifyingKey as ArkG16VerifierKey;
 use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use askama::Template;
+use std::io::{Read, Write};
 
 use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
 
@@ -130,10 +131,34 @@ impl NovaCycleFoldVerifierKey {
             z_len,
         }
     }
+
+    /// Builds the verifier key from the verifier param of Nova's `DeciderEth`, `(pp_hash, g16_vk,
+    /// kzg_vk)`, serialized in compressed form with `CanonicalSerialize`.
+    pub fn from_decider_vp<R: Read>(reader: R, z_len: usize) -> Result<Self, SerializationError> {
+        let decider_vp =
+            <(Fr, ArkG16VerifierKey<Bn254>, ArkKZG10VerifierKey<Bn254>)>::deserialize_compressed(
+                reader,
+            )?;
+        Ok(Self::from((decider_vp, z_len)))
+    }
+
+    /// Reads the serialized verifier param of Nova's `DeciderEth` (see
+    /// [`NovaCycleFoldVerifierKey::from_decider_vp`]), and writes the resulting verifier key into
+    /// `writer`, in the format expected by
+    /// [`ProtocolVerifierKey::deserialize_protocol_verifier_key`].
+    pub fn write_from_decider_vp<R: Read, W: Write>(
+        reader: R,
+        z_len: usize,
+        writer: &mut W,
+    ) -> Result<Self, SerializationError> {
+        let vk = Self::from_decider_vp(reader, z_len)?;
+        vk.serialize_protocol_verifier_key(writer)?;
+        Ok(vk)
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use ark_bn254::{constraints::GVar, Bn254, Fr, G1Projective as G1};
     use ark_ff::PrimeField;
     use ark_groth16::Groth16;
@@ -141,6 +166,7 @@ mod tests {
     use ark_r1cs_std::alloc::AllocVar;
     use ark_r1cs_std::fields::fp::FpVar;
     use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_serialize::CanonicalSerialize;
     use ark_std::Zero;
     use askama::Template;
     use std::marker::PhantomData;
@@ -298,6 +324,32 @@ mod tests {
         assert_eq!(nova_cyclefold_vk, obtained_nova_cyclefold_vk)
     }
 
+    #[test]
+    fn nova_cyclefold_vk_from_decider_vp() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+        let decider_vp = (pp_hash, g16_vk, kzg_vk);
+        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp.clone(), 1));
+
+        // serialize the decider verifier param, as it would be stored after the Decider preprocess
+        let mut decider_vp_bytes = vec![];
+        decider_vp
+            .serialize_compressed(&mut decider_vp_bytes)
+            .unwrap();
+
+        let mut bytes = vec![];
+        let obtained_nova_cyclefold_vk = NovaCycleFoldVerifierKey::write_from_decider_vp(
+            decider_vp_bytes.as_slice(),
+            1,
+            &mut bytes,
+        )
+        .unwrap();
+        assert_eq!(nova_cyclefold_vk, obtained_nova_cyclefold_vk);
+
+        let deserialized_nova_cyclefold_vk =
+            NovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice()).unwrap();
+        assert_eq!(nova_cyclefold_vk, deserialized_nova_cyclefold_vk);
+    }
+
     #[test]
     fn nova_cyclefold_decider_template_renders() {
         let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
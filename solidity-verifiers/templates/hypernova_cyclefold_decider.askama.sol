@@ -0,0 +1,113 @@
+/*
+    Sonobe's HyperNova + CycleFold decider verifier.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
+    Groth16 verifier implementation and a KZG10 Solidity template adapted from
+    https://github.com/weijiekoh/libkzg.
+    Additionally we implement the HyperNovaDecider contract, which combines the
+    Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
+    HyperNova+CycleFold folding.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+{{ kzg10_verifier }}
+
+/* =============================== */
+/* Groth16 verifier methods */
+{{ groth16_verifier }}
+
+
+/* =============================== */
+/* HyperNova+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   HyperNovaDecider contract, for verifying HyperNova IVC SNARK proofs.
+ * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
+ */
+contract HyperNovaDecider is Groth16Verifier, KZG10Verifier {
+    /**
+     * @notice  Verifies a hypernova cyclefold proof consisting of a KZG proof and of a groth16 proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     */
+    function verifyHyperNovaProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_C_u_i_C, // [U_i_C[2], u_i_C[2]]
+        uint256 rho, // folding challenge of the last fold
+        uint256[2] calldata pA, // groth16
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[2] calldata challenge_W_eval_W, // [challenge_W, eval_W]
+        uint256[2] calldata kzg_proof // proof_W
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        // from gamma_abc_len, we subtract 1.
+        uint256[{{ public_inputs_len - 1 }}] memory public_inputs;
+
+        public_inputs[0] = {{pp_hash}};
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < {{ z_len * 2 }}; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        {
+            // U_i.C and u_i.C, which are used in-circuit to fold the running & incoming instances
+            uint256[{{num_limbs}}] memory U_i_C_x_limbs = LimbsDecomposition.decompose(U_i_C_u_i_C[0]);
+            uint256[{{num_limbs}}] memory U_i_C_y_limbs = LimbsDecomposition.decompose(U_i_C_u_i_C[1]);
+            uint256[{{num_limbs}}] memory u_i_C_x_limbs = LimbsDecomposition.decompose(U_i_C_u_i_C[2]);
+            uint256[{{num_limbs}}] memory u_i_C_y_limbs = LimbsDecomposition.decompose(U_i_C_u_i_C[3]);
+
+            for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                public_inputs[{{ z_len * 2 + 2 }} + k] = U_i_C_x_limbs[k];
+                public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = U_i_C_y_limbs[k];
+                public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 }} + k] = u_i_C_x_limbs[k];
+                public_inputs[{{ z_len * 2 + 2 + num_limbs * 3 }} + k] = u_i_C_y_limbs[k];
+            }
+        }
+
+        {
+            // U_{i+1}.C = U_i.C + rho * u_i.C
+            uint256[2] memory mulScalarPoint = super.mulScalar([U_i_C_u_i_C[2], U_i_C_u_i_C[3]], rho);
+            uint256[2] memory C = super.add([U_i_C_u_i_C[0], U_i_C_u_i_C[1]], mulScalarPoint);
+
+            require(this.check(C, kzg_proof, challenge_W_eval_W[0], challenge_W_eval_W[1]), "KZG: verifying proof for challenge W failed");
+        }
+
+        {
+            // add challenge & evaluation
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 }}] = challenge_W_eval_W[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 + 1 }}] = challenge_W_eval_W[1];
+
+            // last element of the groth16 proof's public inputs is `rho`
+            public_inputs[{{ public_inputs_len - 2 }}] = rho;
+
+            bool success_g16 = this.verifyProof(pA, pB, pC, public_inputs);
+            require(success_g16 == true, "Groth16: verifying proof failed");
+        }
+
+        return(true);
+    }
+}